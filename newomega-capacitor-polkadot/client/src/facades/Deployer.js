@@ -57,7 +57,7 @@ export class Deployer {
             hashes['newomega'] = contracts['newomega'].address.toHuman();
             console.log('address.newomega ', hashes['newomega']);
 
-            contracts['newomegagame'] = await this.deployInnerContract('newomegagame', [contracts.newomega]);
+            contracts['newomegagame'] = await this.deployInnerContract('newomegagame');
             hashes['newomegagame'] = contracts['newomegagame'].address.toHuman();
             console.log('address.newomegagame ', hashes['newomegagame']);
 
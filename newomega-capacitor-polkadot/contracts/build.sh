@@ -2,10 +2,11 @@
 
 set -eu
 
-cargo +nightly contract build --manifest-path newomega/Cargo.toml
-cargo +nightly contract build --manifest-path newomegastorage/Cargo.toml
-cargo +nightly contract build --manifest-path newomegagame/Cargo.toml
-cargo +nightly contract build --manifest-path newomegauniverse/Cargo.toml
-cargo +nightly contract build --manifest-path newomegaranked/Cargo.toml
-cargo +nightly contract build --manifest-path newomegaindustrial/Cargo.toml
-cargo +nightly contract build
+cargo contract build --manifest-path newomega/Cargo.toml
+cargo contract build --manifest-path newomegaparameters/Cargo.toml
+cargo contract build --manifest-path newomegastorage/Cargo.toml
+cargo contract build --manifest-path newomegagame/Cargo.toml
+cargo contract build --manifest-path newomegauniverse/Cargo.toml
+cargo contract build --manifest-path newomegaranked/Cargo.toml
+cargo contract build --manifest-path newomegaindustrial/Cargo.toml
+cargo contract build
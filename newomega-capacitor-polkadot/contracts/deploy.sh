@@ -24,6 +24,11 @@ cp target/ink/newomegaindustrial/newomegaindustrial.contract ../client/src/ink/n
 cp target/ink/newomegaindustrial/newomegaindustrial.wasm ../client/src/ink/newomegaindustrial/
 cp target/ink/newomegaindustrial/metadata.json ../client/src/ink/newomegaindustrial/
 
+mkdir -p ../client/src/ink/newomegaparameters/
+cp target/ink/newomegaparameters/newomegaparameters.contract ../client/src/ink/newomegaparameters/
+cp target/ink/newomegaparameters/newomegaparameters.wasm ../client/src/ink/newomegaparameters/
+cp target/ink/newomegaparameters/metadata.json ../client/src/ink/newomegaparameters/
+
 cp target/ink/newomegastorage/newomegastorage.contract ../client/src/ink/newomegastorage/
 cp target/ink/newomegastorage/newomegastorage.wasm ../client/src/ink/newomegastorage/
 cp target/ink/newomegastorage/metadata.json ../client/src/ink/newomegastorage/
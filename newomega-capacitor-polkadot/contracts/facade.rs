@@ -1,34 +1,54 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![feature(destructuring_assignment)]
+// The ink! constructors take the place of Default, and the messages forward the full ship and
+// fleet setups (the generated call forwarders do not carry an item level allow)
+#![allow(clippy::new_without_default, clippy::too_many_arguments)]
+// Built as a dependency, ink! leaves out the contract implementation and what it uses
+#![cfg_attr(feature = "ink-as-dependency", allow(unused_imports, dead_code))]
 
 use ink_lang as ink;
 
 /// The Delegator Contract
 ///
 /// Instantiates all the other contracts, and acts as a facade to interact with them.
+#[newomegaaccess::access_control(NewOmegaDelegator)]
 #[ink::contract]
 mod newomegadelegator {
+    use newomegaaccess::AccessControl;
+    use newomegaaccess::Role;
     use newomega::NewOmega;
     use newomega::FightResult;
+    use newomega::FightSettings;
+    use newomega::Outcome;
     use newomega::Move;
-    use newomega::RunningEffect;
     use newomega::MAX_SHIPS;
     use newomega::ShipModule;
     use newomega::TargetingType;
     use newomegagame::NewOmegaGame;
+    use newomegagame::ShipClass;
+    use newomegagame::ShipCatalogueEntry;
     use newomegaranked::NewOmegaRanked;
     use newomegaranked::PlayerDefence;
     use newomegauniverse::NewOmegaUniverse;
     use newomegauniverse::System;
     use newomegauniverse::SystemCoordinate;
+    use newomegauniverse::GatewayRequest;
     use newomegauniverse::PlayerAssets;
-    use newomegauniverse::Planet;
+    use newomegauniverse::EmpireOverview;
+    use newomegauniverse::PendingHarvest;
     use newomegauniverse::GameStats;
+    use newomegauniverse::MapBounds;
+    use newomegauniverse::UpgradeCostTable;
+    use newomegauniverse::PlanetTypeDefinition;
+    use newomegauniverse::SystemSummary;
+    use newomegauniverse::SystemIntel;
     use newomegastorage::NewOmegaStorage;
     use newomegastorage::PlayerData;
     use newomegastorage::MAX_MINERALS;
     use newomegastorage::RegisteredTrade;
     use newomegaindustrial::NewOmegaIndustrial;
+    use newomegaparameters::NewOmegaParameters;
+    use newomegaparameters::GameParameters;
     use ink_prelude::vec::Vec;
     use ink_prelude::string::String;
     use ink_storage::{
@@ -43,7 +63,6 @@ mod newomegadelegator {
     // use ink_storage::{
     //     Lazy,
     // };
-    use ink_lang::ToAccountId;
 
     /// Withdrawal error reasons definition
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -54,6 +73,57 @@ mod newomegadelegator {
         BelowSubsistenceThreshold,
     }
 
+    /// Payment error reasons definition
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PaymentError {
+        /// Transferred value is below the price (it is refunded in full)
+        InsufficientPayment,
+    }
+
+    /// Features which can be paused separately, in an emergency
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy,
+        PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub enum Feature {
+        /// Ranked defence registration and ranked fights
+        Ranked,
+        /// Attacking planets and gateways in the Universe
+        UniverseCombat,
+        /// Registering and executing mineral trades
+        Trading,
+        /// Ship production
+        Production,
+        /// Registration grant for new Universe players
+        Faucet,
+    }
+
+    /// Categories of the fees collected by the payable messages
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy,
+        PartialEq, Eq, PartialOrd, Ord)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub enum FeeCategory {
+        Discovery,
+        Scout,
+        Harvest,
+        Rename,
+        Upgrade,
+    }
+
     #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
     #[cfg_attr(
         feature = "std",
@@ -71,14 +141,18 @@ mod newomegadelegator {
 
     #[ink(storage)]
     pub struct NewOmegaDelegator {
-        owner: AccountId,
+        access: AccessControl,
         new_omega: NewOmega,
         new_omega_storage: NewOmegaStorage,
         new_omega_game: NewOmegaGame,
         new_omega_ranked: NewOmegaRanked,
         new_omega_universe: NewOmegaUniverse,
         new_omega_industrial: NewOmegaIndustrial,
+        new_omega_parameters: NewOmegaParameters,
         free_discovery: StorageHashMap<AccountId, BlockNumber>,
+        fees_collected: StorageHashMap<FeeCategory, Balance>,
+        paused: bool,
+        paused_features: StorageHashMap<Feature, bool>,
     }
 
     #[ink(event)]
@@ -100,8 +174,29 @@ mod newomegadelegator {
         result: FightResult,
     }
 
-    const LOOT_CRATE_PRICE: u128 = 1;
-    const FREE_DISCOVERY_FREQUENCY_BLOCKS: BlockNumber = 1000;
+    /// Emitted when the whole game (feature is None) or a single feature is paused
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        by: AccountId,
+        feature: Option<Feature>,
+    }
+
+    /// Emitted when the whole game (feature is None) or a single feature is unpaused
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        by: AccountId,
+        feature: Option<Feature>,
+    }
+
+    /// Emitted when an Admin withdraws funds from the contract
+    #[ink(event)]
+    pub struct FundsWithdrawn {
+        #[ink(topic)]
+        by: AccountId,
+        value: Balance,
+    }
 
     impl NewOmegaDelegator {
         /// Instantiates the Delegator.
@@ -113,6 +208,8 @@ mod newomegadelegator {
         /// * `new_omega_game` - Contract address: NewOmegaGame
         /// * `new_omega_ranked` - Contract address: NewOmegaRanked
         /// * `new_omega_universe` - Contract address: NewOmegaUniverse
+        /// * `new_omega_industrial` - Contract address: NewOmegaIndustrial
+        /// * `new_omega_parameters` - Contract address: NewOmegaParameters
         #[ink(constructor)]
         pub fn new(
             new_omega: NewOmega,
@@ -120,17 +217,22 @@ mod newomegadelegator {
             new_omega_game: NewOmegaGame,
             new_omega_ranked: NewOmegaRanked,
             new_omega_universe: NewOmegaUniverse,
-            new_omega_industrial: NewOmegaIndustrial) -> Self {
+            new_omega_industrial: NewOmegaIndustrial,
+            new_omega_parameters: NewOmegaParameters) -> Self {
 
             Self {
-                owner: Self::env().caller(),
+                access: AccessControl::new(Self::env().caller()),
                 new_omega,
                 new_omega_storage,
                 new_omega_game,
                 new_omega_ranked,
                 new_omega_universe,
                 new_omega_industrial,
+                new_omega_parameters,
                 free_discovery: StorageHashMap::default(),
+                fees_collected: StorageHashMap::default(),
+                paused: false,
+                paused_features: StorageHashMap::default(),
             }
         }
 
@@ -138,6 +240,144 @@ mod newomegadelegator {
             self.free_discovery.entry(caller).or_insert(0);
         }
 
+        /// Collects the payment for a payable message. Any value transferred above the price
+        /// is refunded to the caller. If the value is below the price, it is refunded in full.
+        /// Panics if the refund fails, so that the whole call reverts and no value is lost.
+        ///
+        /// # Arguments
+        ///
+        /// * `category` - The fee category to account the payment under
+        /// * `price` - The price of the action
+        ///
+        /// # Returns
+        ///
+        /// * `result` - Ok if the price has been paid, PaymentError otherwise
+        fn collect_payment(&mut self, category: FeeCategory, price: Balance) -> Result<(), PaymentError> {
+            let caller: AccountId = self.env().caller();
+            let value: Balance = self.env().transferred_balance();
+
+            let (refund, result): (Balance, Result<(), PaymentError>) = if value < price {
+                (value, Err(PaymentError::InsufficientPayment))
+            } else {
+                (value - price, Ok(()))
+            };
+
+            if refund > 0 {
+                assert!(self.env().transfer(caller, refund).is_ok(), "Refund failed");
+            }
+
+            if result.is_ok() {
+                *self.fees_collected.entry(category).or_insert(0) += price;
+            }
+
+            result
+        }
+
+        /// Refunds the ranked stake to the attacker when the fight was not decisive (see Outcome),
+        /// as nothing is paid out of it. Panics if the refund fails, so that the whole call reverts.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - AccountId of the attacker
+        /// * `stake` - The value transferred with the attack
+        /// * `outcome` - The fight outcome
+        fn refund_undecided_stake(&mut self, caller: AccountId, stake: Balance, outcome: Outcome) {
+            let decisive: bool = outcome == Outcome::AttackerWin || outcome == Outcome::DefenderWin;
+            if !decisive && stake > 0 {
+                assert!(self.env().transfer(caller, stake).is_ok(), "Refund failed");
+            }
+        }
+
+        /// Pauses the whole game, or a single feature. Can be called by Admins and Guardians.
+        /// Queries, unregistering ranked defences (withdrawing stakes) and admin calls stay open.
+        ///
+        /// # Arguments
+        ///
+        /// * `feature` - The feature to pause, None to pause everything
+        ///
+        /// # Events
+        ///
+        /// * Paused - when paused
+        #[ink(message)]
+        pub fn pause(&mut self, feature: Option<Feature>) {
+            let caller: AccountId = self.env().caller();
+            self.access.ensure_any_role(caller, &[Role::Admin, Role::Guardian]);
+
+            match feature {
+                Some(feature) => { self.paused_features.insert(feature, true); },
+                None => self.paused = true,
+            }
+
+            self.env().emit_event(Paused {
+                by: caller,
+                feature,
+            });
+        }
+
+        /// Unpauses the whole game, or a single feature. Can only be called by Admins.
+        ///
+        /// # Arguments
+        ///
+        /// * `feature` - The feature to unpause, None to lift the global pause
+        ///
+        /// # Events
+        ///
+        /// * Unpaused - when unpaused
+        #[ink(message)]
+        pub fn unpause(&mut self, feature: Option<Feature>) {
+            let caller: AccountId = self.env().caller();
+            self.access.ensure_role(caller, Role::Admin);
+
+            match feature {
+                Some(feature) => { self.paused_features.take(&feature); },
+                None => self.paused = false,
+            }
+
+            self.env().emit_event(Unpaused {
+                by: caller,
+                feature,
+            });
+        }
+
+        /// Checks whether the whole game, or a single feature, is paused.
+        ///
+        /// # Arguments
+        ///
+        /// * `feature` - The feature to check, None to check the global pause only
+        ///
+        /// # Returns
+        ///
+        /// * `paused` - Whether the game or the feature is paused
+        #[ink(message)]
+        pub fn is_paused(&self, feature: Option<Feature>) -> bool {
+            self.paused || match feature {
+                Some(feature) => self.paused_features.get(&feature).is_some(),
+                None => false,
+            }
+        }
+
+        /// Panics if the whole game, or the given feature, is paused.
+        ///
+        /// # Arguments
+        ///
+        /// * `feature` - The feature the message belongs to, None if it belongs to no feature
+        fn assert_not_paused(&self, feature: Option<Feature>) {
+            assert!(!self.is_paused(feature), "Paused");
+        }
+
+        /// Gets the total fees collected, per fee category.
+        ///
+        /// # Returns
+        ///
+        /// * `fees` - A Vec containing a tuple of (fee category, total collected)
+        #[ink(message)]
+        pub fn get_fees_collected(&self) -> Vec<(FeeCategory, Balance)> {
+            self.fees_collected
+                .iter()
+                .map(|(category, total)| (*category, *total))
+                .collect()
+        }
+
 
         // /// Instantiates the Delegator.
         // ///
@@ -164,7 +404,7 @@ mod newomegadelegator {
         //         .salt_bytes(salt)
         //         .instantiate()
         //         .expect("Failed instantiating NewOmega");
-        //     let new_omega_game = NewOmegaGame::new(new_omega.clone())
+        //     let new_omega_game = NewOmegaGame::new()
         //         .endowment(total_balance / 8)
         //         .code_hash(newomega_game_code_hash)
         //         .salt_bytes(salt)
@@ -196,14 +436,20 @@ mod newomegadelegator {
         // }
 
         /// Returns a fight replay (detailed fight description).
+        /// The fight is recalculated with the fight rules and ships it was originally fought with.
         ///
         /// # Arguments
         ///
         /// * `seed` - Seed used to generate randomness
+        /// * `rules_version` - Version of the fight rules (FightResult.rules_version)
+        /// * `ships_version` - Version of the ship table (FightResult.ships_version)
         /// * `selection_lhs` - Attacker fleet composition (array with ship quantities)
         /// * `selection_rhs` - Defender fleet composition (array with ship quantities)
         /// * `modules_lhs` - An array that holds modules of the attacker fleet
         /// * `modules_rhs` - An array that holds modules of the defender fleet
+        /// * `targeting_lhs` - Attacker targeting
+        /// * `targeting_rhs` - Defender targeting
+        /// * `settings` - Maximum number of rounds, and the tie-break (FightResult.settings)
         ///
         /// # Returns
         ///
@@ -211,25 +457,31 @@ mod newomegadelegator {
         /// * `moves_lhs` - Logged moves of the attacker
         /// * `moves_rhs` - Logged moves of the defender
         #[ink(message)]
-        pub fn replay(&self, seed: u64, selection_lhs: [u8; MAX_SHIPS],
-            selection_rhs: [u8; MAX_SHIPS], modules_lhs: [ShipModule; MAX_SHIPS],
-            modules_rhs: [ShipModule; MAX_SHIPS], targeting_lhs: TargetingType,
-            targeting_rhs: TargetingType) -> (FightResult, Option<Vec<Move>>,
-                Option<Vec<Move>>) {
-
-            self.new_omega_game.fight(seed, true, selection_lhs, selection_rhs,
-                modules_lhs, modules_rhs, targeting_lhs, targeting_rhs)
+        pub fn replay(&self, seed: u64, rules_version: u16, ships_version: u16,
+            selection_lhs: [u8; MAX_SHIPS], selection_rhs: [u8; MAX_SHIPS],
+            modules_lhs: [ShipModule; MAX_SHIPS], modules_rhs: [ShipModule; MAX_SHIPS],
+            targeting_lhs: TargetingType, targeting_rhs: TargetingType,
+            settings: FightSettings) -> (FightResult, Option<Vec<Move>>, Option<Vec<Move>>) {
+
+            self.new_omega_game.replay(seed, true, rules_version, ships_version, selection_lhs,
+                selection_rhs, modules_lhs, modules_rhs, targeting_lhs, targeting_rhs, settings)
         }
 
         /// Returns a fight result (without detailed fight description).
+        /// The fight is recalculated with the fight rules and ships it was originally fought with.
         ///
         /// # Arguments
         ///
         /// * `seed` - Seed used to generate randomness
+        /// * `rules_version` - Version of the fight rules (FightResult.rules_version)
+        /// * `ships_version` - Version of the ship table (FightResult.ships_version)
         /// * `selection_lhs` - Attacker fleet composition (array with ship quantities)
         /// * `selection_rhs` - Defender fleet composition (array with ship quantities)
         /// * `modules_lhs` - An array that holds modules of the attacker fleet
         /// * `modules_rhs` - An array that holds modules of the defender fleet
+        /// * `targeting_lhs` - Attacker targeting
+        /// * `targeting_rhs` - Defender targeting
+        /// * `settings` - Maximum number of rounds, and the tie-break (FightResult.settings)
         ///
         /// # Returns
         ///
@@ -237,14 +489,14 @@ mod newomegadelegator {
         /// * `moves_lhs` - Always returning None
         /// * `moves_rhs` - Always returning None
         #[ink(message)]
-        pub fn replay_result(&self, seed: u64, selection_lhs: [u8; MAX_SHIPS],
-            selection_rhs: [u8; MAX_SHIPS], modules_lhs: [ShipModule; MAX_SHIPS],
-            modules_rhs: [ShipModule; MAX_SHIPS], targeting_lhs: TargetingType,
-            targeting_rhs: TargetingType) -> (FightResult, Option<Vec<Move>>,
-                Option<Vec<Move>>) {
-
-            self.new_omega_game.fight(seed, false, selection_lhs, selection_rhs,
-                modules_lhs, modules_rhs, targeting_lhs, targeting_rhs)
+        pub fn replay_result(&self, seed: u64, rules_version: u16, ships_version: u16,
+            selection_lhs: [u8; MAX_SHIPS], selection_rhs: [u8; MAX_SHIPS],
+            modules_lhs: [ShipModule; MAX_SHIPS], modules_rhs: [ShipModule; MAX_SHIPS],
+            targeting_lhs: TargetingType, targeting_rhs: TargetingType,
+            settings: FightSettings) -> (FightResult, Option<Vec<Move>>, Option<Vec<Move>>) {
+
+            self.new_omega_game.replay(seed, false, rules_version, ships_version, selection_lhs,
+                selection_rhs, modules_lhs, modules_rhs, targeting_lhs, targeting_rhs, settings)
         }
 
         /// Adds ship to the ship definitions
@@ -258,15 +510,76 @@ mod newomegadelegator {
         /// * `defence` - Ship Defence
         /// * `speed` - Ship Speed
         /// * `range` - Ship Range
+        /// * `name` - Ship name
+        /// * `class` - Ship class
+        /// * `icon_id` - Identifier of the ship icon
         #[ink(message)]
         pub fn add_ship(&mut self, cp: u16, hp: u16, attack_base: u16, attack_variable: u16,
-            defence: u16, speed: u8, range: u8) {
+            defence: u16, speed: u8, range: u8, name: String, class: ShipClass, icon_id: u16) {
 
-            assert_eq!(self.env().caller(), self.owner);
+            self.access.ensure_role(self.env().caller(), Role::Admin);
             self.new_omega_game.add_ship(cp, hp, attack_base,
+                attack_variable, defence, speed, range, name, class, icon_id);
+        }
+
+        /// Updates the statistics of a ship (older fights are replayed with the previous ones)
+        ///
+        /// # Arguments
+        ///
+        /// * `ship_id` - Ship identifier
+        /// * `cp` - Ship Command Power
+        /// * `hp` - Ship Health Points
+        /// * `attack_base` - Base attack
+        /// * `attack_variable` - Variable attack (subject to random)
+        /// * `defence` - Ship Defence
+        /// * `speed` - Ship Speed
+        /// * `range` - Ship Range
+        #[ink(message)]
+        pub fn update_ship(&mut self, ship_id: u8, cp: u16, hp: u16, attack_base: u16,
+            attack_variable: u16, defence: u16, speed: u8, range: u8) {
+
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_game.update_ship(ship_id, cp, hp, attack_base,
                 attack_variable, defence, speed, range);
         }
 
+        /// Retires a ship from production and new fleet selections
+        ///
+        /// # Arguments
+        ///
+        /// * `ship_id` - Ship identifier
+        #[ink(message)]
+        pub fn retire_ship(&mut self, ship_id: u8) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_game.retire_ship(ship_id);
+        }
+
+        /// Sets the name, class and icon of a ship
+        ///
+        /// # Arguments
+        ///
+        /// * `ship_id` - Ship identifier
+        /// * `name` - Ship name
+        /// * `class` - Ship class
+        /// * `icon_id` - Identifier of the ship icon
+        #[ink(message)]
+        pub fn set_ship_details(&mut self, ship_id: u8, name: String, class: ShipClass,
+            icon_id: u16) {
+
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_game.set_ship_details(ship_id, name, class, icon_id);
+        }
+
+        /// Gets the ship catalogue (statistics, details and retirement of every ship)
+        ///
+        /// # Returns
+        ///
+        /// * `catalogue` - A Vec of the catalogue entries, by ship identifier
+        #[ink(message)]
+        pub fn get_ship_catalogue(&self) -> Vec<ShipCatalogueEntry> {
+            self.new_omega_game.get_ship_catalogue()
+        }
+
         /// Registers a fleet for Ranked Defence.
         ///
         /// # Arguments
@@ -277,6 +590,7 @@ mod newomegadelegator {
         #[ink(message, payable)]
         pub fn register_defence(&mut self, selection: [u8; MAX_SHIPS],
             modules: [ShipModule; MAX_SHIPS], name: String, targeting: TargetingType) {
+            self.assert_not_paused(Some(Feature::Ranked));
 
             let caller: AccountId = self.env().caller();
             let value: Balance = self.env().transferred_balance();
@@ -309,12 +623,13 @@ mod newomegadelegator {
 
         #[ink(message)]
         pub fn admin_withdraw_funds(&mut self, value: Balance) {
-            assert!(self.owner == self.env().caller());
+            let caller: AccountId = self.env().caller();
+            self.access.ensure_role(caller, Role::Admin);
 
             let self_balance: Balance = self.env().balance();
             assert!(value <= self_balance, "Insufficient funds!");
 
-            match self.env().transfer(self.owner, value) {
+            match self.env().transfer(caller, value) {
                 Err(ink_env::Error::BelowSubsistenceThreshold) => {
                     panic!(
                         "Requested transfer would have brought contract\
@@ -324,6 +639,11 @@ mod newomegadelegator {
                 Err(_) => panic!("Transfer failed!"),
                 Ok(_) => {}
             }
+
+            self.env().emit_event(FundsWithdrawn {
+                by: caller,
+                value,
+            });
         }
 
         /// Gets the registered defence of a player.
@@ -347,7 +667,29 @@ mod newomegadelegator {
             self.new_omega_ranked.get_all_defenders()
         }
 
+        /// Sets the settings of ranked fights (maximum rounds and tie-break)
+        ///
+        /// # Arguments
+        ///
+        /// * `settings` - The new fight settings
+        #[ink(message)]
+        pub fn set_ranked_fight_settings(&mut self, settings: FightSettings) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_ranked.set_fight_settings(settings);
+        }
+
+        /// Gets the settings of ranked fights
+        ///
+        /// # Returns
+        ///
+        /// * `settings` - The fight settings
+        #[ink(message)]
+        pub fn get_ranked_fight_settings(&self) -> FightSettings {
+            self.new_omega_ranked.get_fight_settings()
+        }
+
         /// Calculates a ranked fight between caller and another player.
+        /// If nobody wins (a Draw or a mutual destruction), the stake is refunded.
         ///
         /// # Arguments
         ///
@@ -361,6 +703,7 @@ mod newomegadelegator {
         #[ink(message, payable)]
         pub fn attack(&mut self, target: AccountId, selection: [u8; MAX_SHIPS],
             modules: [ShipModule; MAX_SHIPS], targeting: TargetingType) {
+            self.assert_not_paused(Some(Feature::Ranked));
 
             let caller: AccountId = self.env().caller();
             let result: FightResult;
@@ -371,6 +714,8 @@ mod newomegadelegator {
                 caller, target, selection, modules,
                 transferred_balance, targeting);
 
+            self.refund_undecided_stake(caller, transferred_balance, result.outcome);
+
             self.env().emit_event(RankedFightComplete {
                 attacker: caller,
                 defender: target,
@@ -406,13 +751,14 @@ mod newomegadelegator {
 
         #[ink(message)]
         pub fn universe_register_player(&mut self, name: String) {
+            self.assert_not_paused(Some(Feature::Faucet));
             let caller: AccountId = self.env().caller();
             let self_balance: Balance = self.env().balance();
 
             self.new_omega_universe.register_player(caller, name);
             self.ensure_free_discovery(caller);
 
-            let value: Balance = 10 * 1000000 * 1000000; // 10 units
+            let value: Balance = self.new_omega_parameters.get_parameters().registration_grant;
             assert!(value <= self_balance, "Insufficient funds!");
 
             match self.env().transfer(caller, value) {
@@ -427,21 +773,31 @@ mod newomegadelegator {
             }
         }
 
+        /// Discovers a System. One discovery per the free discovery period is free,
+        /// any other costs the discovery fee (excess payment is refunded).
+        ///
+        /// # Arguments
+        ///
+        /// * `target` - The SystemCoordinate of the System to discover
         #[ink(message, payable)]
-        pub fn discover_system(&mut self, target: SystemCoordinate) {
+        pub fn discover_system(&mut self, target: SystemCoordinate) -> Result<(), PaymentError> {
+            self.assert_not_paused(None);
             let caller = self.env().caller();
             let block_number = self.env().block_number();
             self.ensure_free_discovery(caller);
 
+            let parameters: GameParameters = self.new_omega_parameters.get_parameters();
             let free_discovery = self.free_discovery.get(&caller).unwrap();
-            if block_number - free_discovery > FREE_DISCOVERY_FREQUENCY_BLOCKS {
+            let is_free: bool = block_number - free_discovery > parameters.free_discovery_frequency_blocks;
+            let price: Balance = if is_free { 0 } else { parameters.discovery_fee };
+            self.collect_payment(FeeCategory::Discovery, price)?;
+
+            if is_free {
                 self.free_discovery.insert(caller, block_number);
-            } else {
-                let value: Balance = self.env().transferred_balance();
-                assert_eq!(value, 1 * 1000000 * 1000000); // 1 Unit    
             }
 
             self.new_omega_universe.discover_system(caller, target);
+            Ok(())
         }
 
         #[ink(message)]
@@ -454,27 +810,86 @@ mod newomegadelegator {
             }
         }
 
+        /// Sets the bounds of the Universe map.
+        ///
+        /// # Arguments
+        ///
+        /// * `min_x` - Lowest allowed x coordinate
+        /// * `max_x` - Highest allowed x coordinate
+        /// * `min_y` - Lowest allowed y coordinate
+        /// * `max_y` - Highest allowed y coordinate
+        #[ink(message)]
+        pub fn set_map_bounds(&mut self, min_x: i32, max_x: i32, min_y: i32, max_y: i32) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_universe.set_map_bounds(min_x, max_x, min_y, max_y);
+        }
+
+        #[ink(message)]
+        pub fn get_map_bounds(&self) -> MapBounds {
+            self.new_omega_universe.get_map_bounds()
+        }
+
         #[ink(message)]
         pub fn get_universe_map(&self, root: AccountId) -> Vec<System> {
-            self.new_omega_universe.get_universe_map(root)
+            self.new_omega_universe.get_universe_map(self.env().caller(), root)
+        }
+
+        #[ink(message)]
+        pub fn get_system_count(&self, root: AccountId) -> u32 {
+            self.new_omega_universe.get_system_count(root)
+        }
+
+        #[ink(message)]
+        pub fn get_universe_region(&self, root: AccountId, x_min: i32, x_max: i32,
+            y_min: i32, y_max: i32) -> Vec<System> {
+
+            self.new_omega_universe.get_universe_region(self.env().caller(), root,
+                x_min, x_max, y_min, y_max)
+        }
+
+        #[ink(message)]
+        pub fn get_universe_page(&self, root: AccountId, offset: u32, limit: u32) -> Vec<System> {
+            self.new_omega_universe.get_universe_page(self.env().caller(), root, offset, limit)
+        }
+
+        #[ink(message)]
+        pub fn get_universe_summary(&self, root: AccountId, offset: u32, limit: u32) -> Vec<SystemSummary> {
+            self.new_omega_universe.get_universe_summary(root, offset, limit)
+        }
+
+        #[ink(message)]
+        pub fn get_system_intel(&self, coord: SystemCoordinate) -> SystemIntel {
+            self.new_omega_universe.get_system_intel(self.env().caller(), coord)
+        }
+
+        #[ink(message, payable)]
+        pub fn scout_system(&mut self, target: SystemCoordinate) -> Result<(), PaymentError> {
+            self.assert_not_paused(None);
+            self.collect_payment(FeeCategory::Scout, self.new_omega_parameters.get_parameters().scout_fee)?;
+            self.new_omega_universe.scout_system(self.env().caller(), target);
+            Ok(())
         }
 
         #[ink(message)]
         pub fn attack_planet(&mut self, target: SystemCoordinate, planet_id: u8,
-            selection: [u8; MAX_SHIPS], modules: [ShipModule; MAX_SHIPS], targeting: TargetingType) {
+            selection: [u8; MAX_SHIPS], modules: [ShipModule; MAX_SHIPS], targeting: TargetingType,
+            garrison: [u8; MAX_SHIPS]) {
+            self.assert_not_paused(Some(Feature::UniverseCombat));
 
             let caller: AccountId = self.env().caller();
             let self_balance: Balance = self.env().balance();
+            let defender: AccountId = self.new_omega_universe.get_planet_owner(target, planet_id);
             let result: FightResult = self.new_omega_universe.attack_planet(
                 caller,
                 target,
                 planet_id,
                 selection,
                 modules,
-                targeting);
+                targeting,
+                garrison);
 
-            if result.rhs_dead && !result.lhs_dead {
-                let value: Balance = 1 * 1000000 * 100000; // 0.1 units
+            if result.outcome == Outcome::AttackerWin {
+                let value: Balance = self.new_omega_parameters.get_parameters().planet_capture_reward;
                 assert!(value <= self_balance, "Insufficient funds!");
     
                 match self.env().transfer(caller, value) {
@@ -491,7 +906,7 @@ mod newomegadelegator {
 
             self.env().emit_event(UniverseFightComplete {
                 attacker: caller,
-                defender: target.root,
+                defender,
                 result,
             });
         }
@@ -507,8 +922,60 @@ mod newomegadelegator {
         }
 
         #[ink(message)]
-        pub fn build_gateway(&mut self, source: SystemCoordinate) {
-            self.new_omega_universe.build_gateway(self.env().caller(), source);
+        pub fn build_gateway(&mut self, source: SystemCoordinate, target_root: AccountId) {
+            self.assert_not_paused(None);
+            self.new_omega_universe.build_gateway(self.env().caller(), source, target_root);
+        }
+
+        #[ink(message)]
+        pub fn set_open_gateways(&mut self, open: bool) {
+            self.assert_not_paused(None);
+            self.new_omega_universe.set_open_gateways(self.env().caller(), open);
+        }
+
+        #[ink(message)]
+        pub fn get_gateway_requests(&self) -> Vec<GatewayRequest> {
+            self.new_omega_universe.get_gateway_requests(self.env().caller())
+        }
+
+        #[ink(message)]
+        pub fn accept_gateway(&mut self, source: SystemCoordinate, target: SystemCoordinate) {
+            self.assert_not_paused(None);
+            self.new_omega_universe.accept_gateway(self.env().caller(), source, target);
+        }
+
+        #[ink(message)]
+        pub fn reject_gateway(&mut self, source: SystemCoordinate) {
+            self.assert_not_paused(None);
+            self.new_omega_universe.reject_gateway(self.env().caller(), source);
+        }
+
+        #[ink(message)]
+        pub fn destroy_gateway(&mut self, source: SystemCoordinate, target: SystemCoordinate) {
+            self.assert_not_paused(None);
+            self.new_omega_universe.destroy_gateway(self.env().caller(), source, target);
+        }
+
+        #[ink(message)]
+        pub fn attack_gateway(&mut self, target: SystemCoordinate, gateway_target: SystemCoordinate,
+            selection: [u8; MAX_SHIPS], modules: [ShipModule; MAX_SHIPS], targeting: TargetingType) {
+            self.assert_not_paused(Some(Feature::UniverseCombat));
+
+            let caller: AccountId = self.env().caller();
+            let defender: AccountId = self.new_omega_universe.get_gateway_defender_owner(target);
+            let result: FightResult = self.new_omega_universe.attack_gateway(
+                caller,
+                target,
+                gateway_target,
+                selection,
+                modules,
+                targeting);
+
+            self.env().emit_event(UniverseFightComplete {
+                attacker: caller,
+                defender,
+                result,
+            });
         }
 
         #[ink(message)]
@@ -520,6 +987,7 @@ mod newomegadelegator {
         pub fn reinforce_planet(&mut self, target: SystemCoordinate,
             planet_id: u8, selection: [u8; MAX_SHIPS], modules: [ShipModule; MAX_SHIPS],
             targeting: TargetingType) {
+            self.assert_not_paused(None);
 
             let caller = self.env().caller();
             self.new_omega_universe.reinforce_planet(caller, target, planet_id,
@@ -532,14 +1000,26 @@ mod newomegadelegator {
         }
 
         #[ink(message, payable)]
-        pub fn harvest(&mut self) {
-            let value: Balance = self.env().transferred_balance();
-            assert_eq!(value, 1 * 1000000 * 1000000); // 1 Unit
+        pub fn harvest(&mut self) -> Result<(), PaymentError> {
+            self.assert_not_paused(None);
+            self.collect_payment(FeeCategory::Harvest, self.new_omega_parameters.get_parameters().harvest_fee)?;
             self.new_omega_universe.harvest(self.env().caller());
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_pending_harvest(&self) -> PendingHarvest {
+            self.new_omega_universe.get_pending_harvest(self.env().caller())
+        }
+
+        #[ink(message)]
+        pub fn get_empire_overview(&self) -> EmpireOverview {
+            self.new_omega_universe.get_empire_overview(self.env().caller())
         }
 
         #[ink(message)]
         pub fn harvest_planet(&mut self, target: SystemCoordinate, planet_id: u8) {
+            self.assert_not_paused(None);
             self.new_omega_universe.harvest_planet(self.env().caller(), target, planet_id);
         }
 
@@ -550,16 +1030,19 @@ mod newomegadelegator {
 
         #[ink(message)]
         pub fn register_trade(&mut self, resource_id: u8, trade: RegisteredTrade) {
+            self.assert_not_paused(Some(Feature::Trading));
             self.new_omega_storage.register_trade(self.env().caller(), resource_id, trade);
         }
 
         #[ink(message)]
         pub fn trade(&mut self, target: AccountId, resource_id: u8, trade: RegisteredTrade) {
+            self.assert_not_paused(Some(Feature::Trading));
             self.new_omega_storage.trade(self.env().caller(), target, resource_id, trade);
         }
 
         #[ink(message)]
         pub fn produce_ships(&mut self, ship_id: u8, amount: u32) {
+            self.assert_not_paused(Some(Feature::Production));
             self.new_omega_industrial.produce_ships(self.env().caller(), ship_id, amount);
         }
 
@@ -569,17 +1052,229 @@ mod newomegadelegator {
         }
 
         #[ink(message, payable)]
-        pub fn rename_planet(&mut self, target: SystemCoordinate, planet_id: u8, name: String) {
-            let value: Balance = self.env().transferred_balance();
-            assert_eq!(value, 1 * 1000000 * 1000000); // 1 Unit
+        pub fn rename_planet(&mut self, target: SystemCoordinate, planet_id: u8, name: String)
+            -> Result<(), PaymentError> {
+            self.assert_not_paused(None);
+            self.collect_payment(FeeCategory::Rename, self.new_omega_parameters.get_parameters().rename_fee)?;
             self.new_omega_universe.rename_planet(self.env().caller(), target, planet_id, name);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_upgrade_cost_table(&mut self, upgrade_costs: UpgradeCostTable) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_universe.set_upgrade_cost_table(upgrade_costs);
+        }
+
+        #[ink(message)]
+        pub fn get_upgrade_cost_table(&self) -> UpgradeCostTable {
+            self.new_omega_universe.get_upgrade_cost_table()
         }
 
+        #[ink(message)]
+        pub fn set_universe_fight_settings(&mut self, settings: FightSettings) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_universe.set_fight_settings(settings);
+        }
+
+        #[ink(message)]
+        pub fn get_universe_fight_settings(&self) -> FightSettings {
+            self.new_omega_universe.get_fight_settings()
+        }
+
+        #[ink(message)]
+        pub fn set_planet_type(&mut self, planet_type: u8, definition: PlanetTypeDefinition) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_universe.set_planet_type(planet_type, definition);
+        }
+
+        #[ink(message)]
+        pub fn get_planet_types(&self) -> Vec<PlanetTypeDefinition> {
+            self.new_omega_universe.get_planet_types()
+        }
+
+        #[ink(message)]
+        pub fn get_upgrade_cost(&self, target: SystemCoordinate, planet_id: u8) -> [u32; MAX_MINERALS] {
+            self.new_omega_universe.get_upgrade_cost(target, planet_id)
+        }
+
+        /// Upgrades a planet. The upgrade fee is paid in value, and the mineral cost
+        /// (see get_upgrade_cost) is taken from the caller's minerals on top of it.
         #[ink(message, payable)]
-        pub fn upgrade_planet(&mut self, target: SystemCoordinate, planet_id: u8) {
-            let value: Balance = self.env().transferred_balance();
-            assert_eq!(value, 2 * 1000000 * 1000000); // 1 Unit
+        pub fn upgrade_planet(&mut self, target: SystemCoordinate, planet_id: u8) -> Result<(), PaymentError> {
+            self.assert_not_paused(None);
+            self.collect_payment(FeeCategory::Upgrade, self.new_omega_parameters.get_parameters().upgrade_fee)?;
             self.new_omega_universe.upgrade_planet(self.env().caller(), target, planet_id);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_parameters(&mut self, parameters: GameParameters) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_parameters.set_parameters(parameters);
+        }
+
+        #[ink(message)]
+        pub fn get_parameters(&self) -> GameParameters {
+            self.new_omega_parameters.get_parameters()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_env::{
+            call,
+            call::FromAccountId,
+            test,
+        };
+        use ink_lang as ink;
+        type Accounts = test::DefaultAccounts<Environment>;
+
+        fn default_accounts() -> Accounts {
+            test::default_accounts()
+                .expect("Test environment is expected to be initialized.")
+        }
+
+        fn contract_id() -> AccountId {
+            test::get_current_contract_account_id::<Environment>()
+                .expect("Cannot get contract id")
+        }
+
+        fn set_sender(sender: AccountId, value: Balance) {
+            test::push_execution_context::<Environment>(
+                sender,
+                contract_id(),
+                1000000,
+                value,
+                test::CallData::new(call::Selector::new([0x00; 4])),
+            );
+        }
+
+        fn balance_of(account: AccountId) -> Balance {
+            test::get_account_balance::<Environment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        /// The payment tests never call into the other contracts, so any address will do
+        fn prepare_contract() -> NewOmegaDelegator {
+            let address: AccountId = AccountId::from([0x01; 32]);
+            NewOmegaDelegator::new(
+                FromAccountId::from_account_id(address),
+                FromAccountId::from_account_id(address),
+                FromAccountId::from_account_id(address),
+                FromAccountId::from_account_id(address),
+                FromAccountId::from_account_id(address),
+                FromAccountId::from_account_id(address),
+                FromAccountId::from_account_id(address))
+        }
+
+        fn get_fees(contract: &NewOmegaDelegator, category: FeeCategory) -> Option<Balance> {
+            contract.get_fees_collected()
+                .iter()
+                .find(|(c, _)| *c == category)
+                .map(|(_, total)| *total)
+        }
+
+        #[ink::test]
+        fn test_collect_payment_exact() {
+            let mut contract: NewOmegaDelegator = prepare_contract();
+            let accounts = default_accounts();
+            test::set_account_balance::<Environment>(contract_id(), 100).unwrap();
+            let balance_before: Balance = balance_of(accounts.alice);
+
+            set_sender(accounts.alice, 10);
+            assert_eq!(contract.collect_payment(FeeCategory::Discovery, 10), Ok(()));
+
+            assert_eq!(balance_of(accounts.alice), balance_before);
+            assert_eq!(balance_of(contract_id()), 100);
+            assert_eq!(get_fees(&contract, FeeCategory::Discovery), Some(10));
+        }
+
+        #[ink::test]
+        fn test_collect_payment_overpayment() {
+            let mut contract: NewOmegaDelegator = prepare_contract();
+            let accounts = default_accounts();
+            test::set_account_balance::<Environment>(contract_id(), 100).unwrap();
+            let balance_before: Balance = balance_of(accounts.alice);
+
+            set_sender(accounts.alice, 15);
+            assert_eq!(contract.collect_payment(FeeCategory::Scout, 10), Ok(()));
+
+            // The excess is refunded
+            assert_eq!(balance_of(accounts.alice), balance_before + 5);
+            assert_eq!(balance_of(contract_id()), 95);
+            assert_eq!(get_fees(&contract, FeeCategory::Scout), Some(10));
+        }
+
+        #[ink::test]
+        fn test_collect_payment_underpayment() {
+            let mut contract: NewOmegaDelegator = prepare_contract();
+            let accounts = default_accounts();
+            test::set_account_balance::<Environment>(contract_id(), 100).unwrap();
+            let balance_before: Balance = balance_of(accounts.alice);
+
+            set_sender(accounts.alice, 5);
+            assert_eq!(contract.collect_payment(FeeCategory::Harvest, 10),
+                Err(PaymentError::InsufficientPayment));
+
+            // Refunded in full, nothing accounted
+            assert_eq!(balance_of(accounts.alice), balance_before + 5);
+            assert_eq!(get_fees(&contract, FeeCategory::Harvest), None);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_collect_payment_refund_failed() {
+            let mut contract: NewOmegaDelegator = prepare_contract();
+            let accounts = default_accounts();
+            test::set_account_balance::<Environment>(contract_id(), 0).unwrap();
+
+            set_sender(accounts.alice, 15);
+            let _ = contract.collect_payment(FeeCategory::Rename, 10);
+        }
+
+        #[ink::test]
+        fn test_refund_undecided_stake() {
+            let mut contract: NewOmegaDelegator = prepare_contract();
+            let accounts = default_accounts();
+            test::set_account_balance::<Environment>(contract_id(), 100).unwrap();
+            let balance_before: Balance = balance_of(accounts.alice);
+            set_sender(accounts.alice, 10);
+
+            // Decisive fights keep the stake
+            contract.refund_undecided_stake(accounts.alice, 10, Outcome::AttackerWin);
+            contract.refund_undecided_stake(accounts.alice, 10, Outcome::DefenderWin);
+            assert_eq!(balance_of(accounts.alice), balance_before);
+            assert_eq!(balance_of(contract_id()), 100);
+
+            contract.refund_undecided_stake(accounts.alice, 10, Outcome::Draw);
+            assert_eq!(balance_of(accounts.alice), balance_before + 10);
+            contract.refund_undecided_stake(accounts.alice, 10, Outcome::MutualDestruction);
+            assert_eq!(balance_of(accounts.alice), balance_before + 20);
+            assert_eq!(balance_of(contract_id()), 80);
+        }
+
+        #[ink::test]
+        fn test_fees_collected_per_category() {
+            let mut contract: NewOmegaDelegator = prepare_contract();
+            let accounts = default_accounts();
+            test::set_account_balance::<Environment>(contract_id(), 100).unwrap();
+
+            set_sender(accounts.alice, 10);
+            assert_eq!(contract.collect_payment(FeeCategory::Scout, 10), Ok(()));
+            set_sender(accounts.bob, 12);
+            assert_eq!(contract.collect_payment(FeeCategory::Scout, 10), Ok(()));
+            set_sender(accounts.bob, 3);
+            assert_eq!(contract.collect_payment(FeeCategory::Upgrade, 3), Ok(()));
+            set_sender(accounts.bob, 1);
+            assert_eq!(contract.collect_payment(FeeCategory::Upgrade, 3),
+                Err(PaymentError::InsufficientPayment));
+
+            assert_eq!(get_fees(&contract, FeeCategory::Scout), Some(20));
+            assert_eq!(get_fees(&contract, FeeCategory::Upgrade), Some(3));
+            assert_eq!(get_fees(&contract, FeeCategory::Discovery), None);
+            assert_eq!(contract.get_fees_collected().len(), 2);
         }
     }
 }
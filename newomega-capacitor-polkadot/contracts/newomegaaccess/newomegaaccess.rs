@@ -0,0 +1,246 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Role based access control, shared by all the New Omega contracts.
+//! Embedded in the storage of each contract, which then guards its methods with
+//! `ensure_role`, and exposes the role management methods as messages
+//! (generated by the `access_control` attribute).
+
+use ink_env::AccountId;
+pub use newomegaaccessmacro::access_control;
+use ink_storage::{
+    collections::{
+        HashMap as StorageHashMap,
+    },
+    traits::{
+        PackedLayout,
+        SpreadLayout,
+    },
+};
+
+/// Defines the roles an account can hold
+#[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy,
+    PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        scale_info::TypeInfo,
+        ink_storage::traits::StorageLayout
+    )
+)]
+pub enum Role {
+    /// Configures the contract, grants and revokes roles. Does not imply the other roles,
+    /// which have to be granted explicitly.
+    Admin,
+    /// The Delegator contract
+    Delegator,
+    /// Other game contracts (Universe, Ranked, Industrial)
+    GameContract,
+    /// Allowed to pause the game in an emergency
+    Guardian,
+}
+
+/// Holds the owner and the roles granted to accounts
+#[derive(SpreadLayout)]
+#[cfg_attr(feature = "std", derive(Debug, ink_storage::traits::StorageLayout))]
+pub struct AccessControl {
+    owner: AccountId,
+    pending_owner: Option<AccountId>,
+    roles: StorageHashMap<(AccountId, Role), bool>,
+}
+
+impl AccessControl {
+    /// Creates the access control, with the owner holding the Admin role
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - AccountId of the owner (usually the contract deployer)
+    pub fn new(owner: AccountId) -> Self {
+        let mut roles: StorageHashMap<(AccountId, Role), bool> = StorageHashMap::default();
+        roles.insert((owner, Role::Admin), true);
+
+        Self {
+            owner,
+            pending_owner: None,
+            roles,
+        }
+    }
+
+    /// Gets the owner
+    ///
+    /// # Returns
+    ///
+    /// * `owner` - AccountId of the owner
+    pub fn owner(&self) -> AccountId {
+        self.owner
+    }
+
+    /// Checks whether an account holds a role (roles do not imply each other)
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - AccountId to check
+    /// * `role` - The role to check
+    ///
+    /// # Returns
+    ///
+    /// * `has_role` - Whether the account holds the role
+    pub fn has_role(&self, account: AccountId, role: Role) -> bool {
+        self.roles.get(&(account, role)).is_some()
+    }
+
+    /// Panics if the account does not hold the role
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - AccountId to check
+    /// * `role` - The required role
+    pub fn ensure_role(&self, account: AccountId, role: Role) {
+        assert!(self.has_role(account, role), "Missing role");
+    }
+
+    /// Panics if the account does not hold any of the roles
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - AccountId to check
+    /// * `roles` - The roles, any of which is sufficient
+    pub fn ensure_any_role(&self, account: AccountId, roles: &[Role]) {
+        assert!(roles.iter().any(|role| self.has_role(account, *role)), "Missing role");
+    }
+
+    /// Grants a role to an account. Only Admins can grant roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - AccountId of the caller
+    /// * `role` - The role to grant
+    /// * `account` - AccountId to grant the role to
+    pub fn grant_role(&mut self, caller: AccountId, role: Role, account: AccountId) {
+        self.ensure_role(caller, Role::Admin);
+        self.roles.insert((account, role), true);
+    }
+
+    /// Revokes a role from an account. Only Admins can revoke roles,
+    /// and the owner cannot lose the Admin role (other than by transferring ownership).
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - AccountId of the caller
+    /// * `role` - The role to revoke
+    /// * `account` - AccountId to revoke the role from
+    pub fn revoke_role(&mut self, caller: AccountId, role: Role, account: AccountId) {
+        self.ensure_role(caller, Role::Admin);
+        assert!(!(role == Role::Admin && account == self.owner), "Cant revoke owner");
+        self.roles.take(&(account, role));
+    }
+
+    /// Starts transferring the ownership. Only the owner can do this, and the transfer
+    /// only completes once the new owner accepts it.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - AccountId of the caller
+    /// * `new_owner` - AccountId of the proposed new owner
+    pub fn transfer_ownership(&mut self, caller: AccountId, new_owner: AccountId) {
+        assert_eq!(caller, self.owner);
+        self.pending_owner = Some(new_owner);
+    }
+
+    /// Completes the ownership transfer. The Admin role moves from the previous owner
+    /// to the new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - AccountId of the caller, has to be the proposed new owner
+    ///
+    /// # Returns
+    ///
+    /// * `previous_owner` - AccountId of the previous owner
+    pub fn accept_ownership(&mut self, caller: AccountId) -> AccountId {
+        assert_eq!(Some(caller), self.pending_owner, "Not the pending owner");
+
+        let previous_owner: AccountId = self.owner;
+        self.roles.take(&(previous_owner, Role::Admin));
+        self.roles.insert((caller, Role::Admin), true);
+        self.owner = caller;
+        self.pending_owner = None;
+
+        previous_owner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_env::{
+        test,
+        DefaultEnvironment,
+    };
+    use ink_lang as ink;
+    type Accounts = test::DefaultAccounts<DefaultEnvironment>;
+
+    fn default_accounts() -> Accounts {
+        test::default_accounts()
+            .expect("Test environment is expected to be initialized.")
+    }
+
+    #[ink::test]
+    fn test_closed_by_default() {
+        let accounts = default_accounts();
+        let access = AccessControl::new(accounts.alice);
+
+        assert!(access.has_role(accounts.alice, Role::Admin));
+        assert!(!access.has_role(accounts.alice, Role::Delegator));
+        assert!(!access.has_role(accounts.alice, Role::GameContract));
+        assert!(!access.has_role(accounts.bob, Role::Delegator));
+        assert!(!access.has_role(accounts.bob, Role::GameContract));
+    }
+
+    #[ink::test]
+    fn test_grant_revoke() {
+        let accounts = default_accounts();
+        let mut access = AccessControl::new(accounts.alice);
+
+        access.grant_role(accounts.alice, Role::Delegator, accounts.bob);
+        assert!(access.has_role(accounts.bob, Role::Delegator));
+        assert!(!access.has_role(accounts.bob, Role::Guardian));
+        access.ensure_any_role(accounts.bob, &[Role::GameContract, Role::Delegator]);
+
+        access.revoke_role(accounts.alice, Role::Delegator, accounts.bob);
+        assert!(!access.has_role(accounts.bob, Role::Delegator));
+    }
+
+    #[ink::test]
+    #[should_panic]
+    fn test_grant_unauthorised() {
+        let accounts = default_accounts();
+        let mut access = AccessControl::new(accounts.alice);
+
+        access.grant_role(accounts.bob, Role::Admin, accounts.bob);
+    }
+
+    #[ink::test]
+    fn test_ownership_transfer() {
+        let accounts = default_accounts();
+        let mut access = AccessControl::new(accounts.alice);
+
+        access.transfer_ownership(accounts.alice, accounts.bob);
+        assert_eq!(access.owner(), accounts.alice);
+
+        assert_eq!(access.accept_ownership(accounts.bob), accounts.alice);
+        assert_eq!(access.owner(), accounts.bob);
+        assert!(access.has_role(accounts.bob, Role::Admin));
+        assert!(!access.has_role(accounts.alice, Role::Admin));
+    }
+
+    #[ink::test]
+    #[should_panic]
+    fn test_ownership_transfer_not_accepted_by_others() {
+        let accounts = default_accounts();
+        let mut access = AccessControl::new(accounts.alice);
+
+        access.transfer_ownership(accounts.alice, accounts.bob);
+        access.accept_ownership(accounts.charlie);
+    }
+}
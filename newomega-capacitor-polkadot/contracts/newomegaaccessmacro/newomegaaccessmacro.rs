@@ -0,0 +1,192 @@
+//! Procedural macro generating the role management messages of the New Omega contracts
+//! (see newomegaaccess).
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input,
+    parse_quote,
+    Ident,
+    ItemMod,
+};
+
+/// Generates the role management messages (`grant_role`, `revoke_role`, `transfer_ownership`,
+/// `accept_ownership`, `has_role` and `get_owner`) and their events (`RoleGranted`, `RoleRevoked`,
+/// `OwnershipTransferStarted` and `OwnershipTransferred`) for a contract.
+///
+/// ink! reads the contract module before any macro inside of it is expanded, so the attribute
+/// goes on the module, above `#[ink::contract]`, and appends the messages to it before ink! runs.
+/// It takes the name of the contract storage, which has to hold the AccessControl in an `access`
+/// field, and the module has to import `Role`.
+///
+/// It is a procedural macro rather than a `macro_rules!` wrapping the module, because ink!
+/// rebuilds the message receivers with the span of the whole message, which inside a
+/// `macro_rules!` expansion no longer resolves the `self` of the message bodies.
+///
+/// ```text
+/// #[newomegaaccess::access_control(NewOmegaGame)]
+/// #[ink::contract]
+/// mod newomegagame {
+///     use newomegaaccess::AccessControl;
+///     use newomegaaccess::Role;
+///
+///     #[ink(storage)]
+///     pub struct NewOmegaGame {
+///         access: AccessControl,
+///     }
+///     ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn access_control(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let storage: Ident = parse_macro_input!(attr as Ident);
+    let mut module: ItemMod = parse_macro_input!(item as ItemMod);
+
+    let generated: syn::File = parse_quote! {
+        /// Emitted when a role is granted to an account
+        #[ink(event)]
+        pub struct RoleGranted {
+            #[ink(topic)]
+            account: AccountId,
+            role: Role,
+        }
+
+        /// Emitted when a role is revoked from an account
+        #[ink(event)]
+        pub struct RoleRevoked {
+            #[ink(topic)]
+            account: AccountId,
+            role: Role,
+        }
+
+        /// Emitted when the owner starts transferring the ownership
+        #[ink(event)]
+        pub struct OwnershipTransferStarted {
+            #[ink(topic)]
+            owner: AccountId,
+            #[ink(topic)]
+            new_owner: AccountId,
+        }
+
+        /// Emitted when the ownership transfer completes
+        #[ink(event)]
+        pub struct OwnershipTransferred {
+            #[ink(topic)]
+            previous_owner: AccountId,
+            #[ink(topic)]
+            new_owner: AccountId,
+        }
+
+        impl #storage {
+            /// Grants a role to an account. Only Admins can grant roles.
+            ///
+            /// # Arguments
+            ///
+            /// * `role` - The role to grant
+            /// * `account` - AccountId to grant the role to
+            ///
+            /// # Events
+            ///
+            /// * RoleGranted - when the role is granted
+            #[ink(message)]
+            pub fn grant_role(&mut self, role: Role, account: AccountId) {
+                self.access.grant_role(self.env().caller(), role, account);
+                self.env().emit_event(RoleGranted {
+                    account,
+                    role,
+                });
+            }
+
+            /// Revokes a role from an account. Only Admins can revoke roles.
+            ///
+            /// # Arguments
+            ///
+            /// * `role` - The role to revoke
+            /// * `account` - AccountId to revoke the role from
+            ///
+            /// # Events
+            ///
+            /// * RoleRevoked - when the role is revoked
+            #[ink(message)]
+            pub fn revoke_role(&mut self, role: Role, account: AccountId) {
+                self.access.revoke_role(self.env().caller(), role, account);
+                self.env().emit_event(RoleRevoked {
+                    account,
+                    role,
+                });
+            }
+
+            /// Starts transferring the ownership, which completes once the new owner accepts it.
+            ///
+            /// # Arguments
+            ///
+            /// * `new_owner` - AccountId of the proposed new owner
+            ///
+            /// # Events
+            ///
+            /// * OwnershipTransferStarted - when the transfer is started
+            #[ink(message)]
+            pub fn transfer_ownership(&mut self, new_owner: AccountId) {
+                let owner: AccountId = self.env().caller();
+                self.access.transfer_ownership(owner, new_owner);
+                self.env().emit_event(OwnershipTransferStarted {
+                    owner,
+                    new_owner,
+                });
+            }
+
+            /// Accepts the pending ownership transfer, called by the proposed new owner.
+            ///
+            /// # Events
+            ///
+            /// * OwnershipTransferred - when the ownership is transferred
+            #[ink(message)]
+            pub fn accept_ownership(&mut self) {
+                let new_owner: AccountId = self.env().caller();
+                let previous_owner: AccountId = self.access.accept_ownership(new_owner);
+                self.env().emit_event(OwnershipTransferred {
+                    previous_owner,
+                    new_owner,
+                });
+            }
+
+            /// Checks whether an account holds a role (roles do not imply each other).
+            ///
+            /// # Arguments
+            ///
+            /// * `account` - AccountId to check
+            /// * `role` - The role to check
+            ///
+            /// # Returns
+            ///
+            /// * `has_role` - Whether the account holds the role
+            #[ink(message)]
+            pub fn has_role(&self, account: AccountId, role: Role) -> bool {
+                self.access.has_role(account, role)
+            }
+
+            /// Gets the contract owner.
+            ///
+            /// # Returns
+            ///
+            /// * `owner` - AccountId of the owner
+            #[ink(message)]
+            pub fn get_owner(&self) -> AccountId {
+                self.access.owner()
+            }
+        }
+    };
+
+    match module.content {
+        Some((_, ref mut items)) => items.extend(generated.items),
+        None => {
+            return syn::Error::new_spanned(&module, "The contract module must have a body")
+                .to_compile_error()
+                .into();
+        }
+    }
+
+    TokenStream::from(quote!(#module))
+}
@@ -0,0 +1,572 @@
+//! Monte Carlo balance analysis of ship definitions. Runs the New Omega fight engine natively
+//! over many seeds and fleet compositions under a Command Power budget, and reports per ship type
+//! win rates, CP efficiency and fight lengths, ship and targeting matchup matrices,
+//! and the dominant strategies, as JSON or CSV.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use newomegaengine::FightEngine;
+use newomegaengine::FightResult;
+use newomegaengine::FightSettings;
+use newomegaengine::Outcome;
+use newomegaengine::Ship;
+use newomegaengine::ShipModule;
+use newomegaengine::TargetingType;
+use newomegaengine::MAX_SHIPS;
+use serde::Deserialize;
+use serde::Serialize;
+use structopt::StructOpt;
+
+/// All the targeting types, in the order of the matrices
+const TARGETING_TYPES: [TargetingType; 10] = [
+    TargetingType::Furthest,
+    TargetingType::Closest,
+    TargetingType::HighestHp,
+    TargetingType::LowestHp,
+    TargetingType::HighestSpeed,
+    TargetingType::LowestSpeed,
+    TargetingType::HighestDefence,
+    TargetingType::LowestDefence,
+    TargetingType::HighestAttack,
+    TargetingType::LowestAttack,
+];
+
+#[derive(StructOpt)]
+#[structopt(name = "newomegabalance", about = "Monte Carlo balance analysis of New Omega ships")]
+struct Options {
+    /// Ship definitions file (.json or .toml, with a `ships` list), defaults to the game ships
+    #[structopt(long, parse(from_os_str))]
+    ships: Option<PathBuf>,
+    /// Command Power budget of every fleet
+    #[structopt(long, default_value = "100")]
+    cp_budget: u32,
+    /// Number of random fleet matchups
+    #[structopt(long, default_value = "500")]
+    fleets: u32,
+    /// Number of fight seeds per matchup
+    #[structopt(long, default_value = "5")]
+    seeds: u32,
+    /// Seed of the fleet and targeting sampling
+    #[structopt(long, default_value = "0")]
+    sampling_seed: u64,
+    /// Win rate above which a strategy is flagged as dominant
+    #[structopt(long, default_value = "0.75")]
+    dominance_threshold: f64,
+    /// Output format, json or csv
+    #[structopt(long, default_value = "json")]
+    format: String,
+    /// Directory to write the CSV files to
+    #[structopt(long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+/// Ship definitions file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShipsConfig {
+    ships: Vec<ShipConfig>,
+}
+
+/// Ship definition, as read from a file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShipConfig {
+    cp: u16,
+    hp: u16,
+    attack_base: u16,
+    attack_variable: u16,
+    defence: u16,
+    speed: u8,
+    range: u8,
+}
+
+/// Analysis settings
+struct Settings {
+    cp_budget: u32,
+    fleets: u32,
+    seeds: u32,
+    sampling_seed: u64,
+    dominance_threshold: f64,
+}
+
+/// Results for a single ship type
+#[derive(Serialize)]
+struct ShipTypeReport {
+    /// Index of the ship type
+    ship: usize,
+    /// Command Power of the ship type
+    cp: u16,
+    /// Command Power of the single ship type fleet of the ship matchups, at most the CP budget
+    mono_fleet_cp: u32,
+    /// Win rate of the random fleets, weighted by the CP share of the ship type
+    win_rate: f64,
+    /// Enemy CP destroyed per own CP lost, weighted by the CP share of the ship type
+    cp_efficiency: f64,
+    /// Average fight length in rounds, weighted by the CP share of the ship type
+    average_rounds: f64,
+}
+
+/// A strategy which wins too often
+#[derive(Serialize)]
+struct DominantStrategy {
+    /// ship, ship_mix or targeting
+    kind: String,
+    /// Name of the strategy
+    name: String,
+    /// Why it was flagged
+    reason: String,
+}
+
+/// Full analysis report
+#[derive(Serialize)]
+struct Report {
+    fights: u32,
+    cp_budget: u32,
+    average_rounds: f64,
+    /// Per ship type results, from the random fleets
+    ship_types: Vec<ShipTypeReport>,
+    /// Win rates of single ship type fleets, row (attacker) against column (defender)
+    ship_matchups: Vec<Vec<f64>>,
+    /// Names of the targeting types, in the order of the targeting matrix
+    targeting_types: Vec<String>,
+    /// Win rates of mirrored fleets, row targeting against column targeting
+    targeting_matchups: Vec<Vec<f64>>,
+    dominant_strategies: Vec<DominantStrategy>,
+}
+
+/// Deterministic random number generator (xorshift64*), so that runs can be reproduced
+struct Sampler {
+    state: u64,
+}
+
+impl Sampler {
+    fn new(seed: u64) -> Self {
+        Sampler {
+            state: seed ^ 0x9E37_79B9_7F4A_7C15 | 1,
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator > 0.0 {
+        numerator / denominator
+    } else {
+        0.0
+    }
+}
+
+fn fleet_cp(ships: &[Ship], selection: &[u8; MAX_SHIPS]) -> u32 {
+    (0..MAX_SHIPS).map(|i| selection[i] as u32 * ships[i].cp as u32).sum()
+}
+
+/// Builds a fleet of a single ship type, using up the CP budget
+fn mono_fleet(ships: &[Ship], ship: usize, cp_budget: u32) -> [u8; MAX_SHIPS] {
+    let mut selection: [u8; MAX_SHIPS] = [0; MAX_SHIPS];
+    selection[ship] = (cp_budget / ships[ship].cp as u32).min(u8::MAX as u32) as u8;
+    selection
+}
+
+/// Builds a random fleet, adding random ships until the CP budget is used up
+fn random_fleet(ships: &[Ship], cp_budget: u32, sampler: &mut Sampler) -> [u8; MAX_SHIPS] {
+    let mut selection: [u8; MAX_SHIPS] = [0; MAX_SHIPS];
+    let mut remaining: u32 = cp_budget;
+
+    loop {
+        let affordable: Vec<usize> = (0..MAX_SHIPS)
+            .filter(|&i| ships[i].cp as u32 <= remaining && selection[i] < u8::MAX)
+            .collect();
+        if affordable.is_empty() {
+            return selection;
+        }
+
+        let ship: usize = affordable[sampler.below(affordable.len())];
+        selection[ship] += 1;
+        remaining -= ships[ship].cp as u32;
+    }
+}
+
+fn run_fight(ships: &[Ship], seed: u64, selection_lhs: [u8; MAX_SHIPS], selection_rhs: [u8; MAX_SHIPS],
+    targeting_lhs: TargetingType, targeting_rhs: TargetingType) -> FightResult {
+    let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+    let (result, _, _) = FightEngine::default().fight(seed, false, ships.to_vec(),
+        selection_lhs, selection_rhs, modules, modules, targeting_lhs, targeting_rhs,
+        FightSettings::default());
+    result
+}
+
+fn cp_lost(ships: &[Ship], ships_lost: &[u8; MAX_SHIPS]) -> f64 {
+    fleet_cp(ships, ships_lost) as f64
+}
+
+/// Runs the whole analysis
+fn analyse(ships: &[Ship], settings: &Settings) -> Report {
+    let mut sampler: Sampler = Sampler::new(settings.sampling_seed);
+    let mut fights: u32 = 0;
+    let mut total_rounds: f64 = 0.0;
+
+    // Random fleets, results attributed to ship types by their CP share
+    let mut weights: [f64; MAX_SHIPS] = [0.0; MAX_SHIPS];
+    let mut wins: [f64; MAX_SHIPS] = [0.0; MAX_SHIPS];
+    let mut destroyed: [f64; MAX_SHIPS] = [0.0; MAX_SHIPS];
+    let mut lost: [f64; MAX_SHIPS] = [0.0; MAX_SHIPS];
+    let mut rounds: [f64; MAX_SHIPS] = [0.0; MAX_SHIPS];
+
+    for _ in 0..settings.fleets {
+        let selection_lhs: [u8; MAX_SHIPS] = random_fleet(ships, settings.cp_budget, &mut sampler);
+        let selection_rhs: [u8; MAX_SHIPS] = random_fleet(ships, settings.cp_budget, &mut sampler);
+        let targeting_lhs: TargetingType = TARGETING_TYPES[sampler.below(TARGETING_TYPES.len())];
+        let targeting_rhs: TargetingType = TARGETING_TYPES[sampler.below(TARGETING_TYPES.len())];
+
+        for _ in 0..settings.seeds {
+            let result: FightResult = run_fight(ships, sampler.next(), selection_lhs, selection_rhs,
+                targeting_lhs, targeting_rhs);
+            let fight_outcome: Outcome = result.outcome;
+            fights += 1;
+            total_rounds += result.rounds as f64;
+
+            let sides = [
+                (selection_lhs, Outcome::AttackerWin, result.ships_lost_rhs, result.ships_lost_lhs),
+                (selection_rhs, Outcome::DefenderWin, result.ships_lost_lhs, result.ships_lost_rhs),
+            ];
+            for (selection, winning_outcome, enemy_lost, own_lost) in sides.iter() {
+                let total_cp: f64 = fleet_cp(ships, selection) as f64;
+                for i in 0..MAX_SHIPS {
+                    let share: f64 = ratio(selection[i] as f64 * ships[i].cp as f64, total_cp);
+                    weights[i] += share;
+                    if fight_outcome == *winning_outcome {
+                        wins[i] += share;
+                    }
+                    destroyed[i] += share * cp_lost(ships, enemy_lost);
+                    lost[i] += share * cp_lost(ships, own_lost);
+                    rounds[i] += share * result.rounds as f64;
+                }
+            }
+        }
+    }
+
+    let ship_types: Vec<ShipTypeReport> = (0..MAX_SHIPS)
+        .map(|i| ShipTypeReport {
+            ship: i,
+            cp: ships[i].cp,
+            mono_fleet_cp: fleet_cp(ships, &mono_fleet(ships, i, settings.cp_budget)),
+            win_rate: ratio(wins[i], weights[i]),
+            cp_efficiency: ratio(destroyed[i], lost[i].max(1.0)),
+            average_rounds: ratio(rounds[i], weights[i]),
+        })
+        .collect();
+
+    // Single ship type fleets against each other
+    let mut ship_matchups: Vec<Vec<f64>> = vec![vec![0.0; MAX_SHIPS]; MAX_SHIPS];
+    for (row, matchups) in ship_matchups.iter_mut().enumerate() {
+        for (column, win_rate) in matchups.iter_mut().enumerate() {
+            let mut matchup_wins: u32 = 0;
+            for _ in 0..settings.seeds {
+                let result: FightResult = run_fight(ships, sampler.next(),
+                    mono_fleet(ships, row, settings.cp_budget), mono_fleet(ships, column, settings.cp_budget),
+                    TargetingType::default(), TargetingType::default());
+                fights += 1;
+                total_rounds += result.rounds as f64;
+                if result.outcome == Outcome::AttackerWin {
+                    matchup_wins += 1;
+                }
+            }
+            *win_rate = ratio(matchup_wins as f64, settings.seeds as f64);
+        }
+    }
+
+    // Mirrored random fleets, only the targeting differs
+    let targeting_fleets: u32 = (settings.fleets / TARGETING_TYPES.len() as u32).max(1);
+    let mut targeting_matchups: Vec<Vec<f64>> = vec![vec![0.0; TARGETING_TYPES.len()]; TARGETING_TYPES.len()];
+    for (row, matchups) in targeting_matchups.iter_mut().enumerate() {
+        for (column, win_rate) in matchups.iter_mut().enumerate() {
+            let mut matchup_wins: u32 = 0;
+            let mut matchup_fights: u32 = 0;
+            for _ in 0..targeting_fleets {
+                let selection: [u8; MAX_SHIPS] = random_fleet(ships, settings.cp_budget, &mut sampler);
+                let result: FightResult = run_fight(ships, sampler.next(), selection, selection,
+                    TARGETING_TYPES[row], TARGETING_TYPES[column]);
+                fights += 1;
+                matchup_fights += 1;
+                total_rounds += result.rounds as f64;
+                if result.outcome == Outcome::AttackerWin {
+                    matchup_wins += 1;
+                }
+            }
+            *win_rate = ratio(matchup_wins as f64, matchup_fights as f64);
+        }
+    }
+
+    let dominant_strategies: Vec<DominantStrategy> = find_dominant_strategies(&ship_types,
+        &ship_matchups, &targeting_matchups, settings.dominance_threshold);
+
+    Report {
+        fights,
+        cp_budget: settings.cp_budget,
+        average_rounds: ratio(total_rounds, fights as f64),
+        ship_types,
+        ship_matchups,
+        targeting_types: TARGETING_TYPES.iter().map(|targeting| format!("{:?}", targeting)).collect(),
+        targeting_matchups,
+        dominant_strategies,
+    }
+}
+
+/// Flags the strategies which win too often: ship types and targeting types which beat
+/// every other one, and ship types which win too often as a part of random fleets
+fn find_dominant_strategies(ship_types: &[ShipTypeReport], ship_matchups: &[Vec<f64>],
+    targeting_matchups: &[Vec<f64>], threshold: f64) -> Vec<DominantStrategy> {
+    let mut strategies: Vec<DominantStrategy> = Vec::new();
+
+    for (row, matchups) in ship_matchups.iter().enumerate() {
+        if matchups.iter().enumerate().all(|(column, win_rate)| column == row || *win_rate >= threshold) {
+            strategies.push(DominantStrategy {
+                kind: String::from("ship"),
+                name: format!("ship {}", row),
+                reason: format!("single ship type fleet wins at least {} against every other", threshold),
+            });
+        }
+    }
+
+    for ship_type in ship_types.iter() {
+        if ship_type.win_rate >= threshold {
+            strategies.push(DominantStrategy {
+                kind: String::from("ship_mix"),
+                name: format!("ship {}", ship_type.ship),
+                reason: format!("fleets win {:.3} weighted by its CP share", ship_type.win_rate),
+            });
+        }
+    }
+
+    for (row, matchups) in targeting_matchups.iter().enumerate() {
+        if matchups.iter().enumerate().all(|(column, win_rate)| column == row || *win_rate >= threshold) {
+            strategies.push(DominantStrategy {
+                kind: String::from("targeting"),
+                name: format!("{:?}", TARGETING_TYPES[row]),
+                reason: format!("wins at least {} against every other targeting", threshold),
+            });
+        }
+    }
+
+    strategies
+}
+
+/// Reads the ship definitions file, JSON or TOML depending on the extension
+fn read_ships(path: &Path) -> Result<Vec<Ship>, String> {
+    let contents: String = fs::read_to_string(path)
+        .map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+
+    let config: ShipsConfig = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(&contents)
+            .map_err(|error| format!("Invalid JSON in {}: {}", path.display(), error))?,
+        Some("toml") => toml::from_str(&contents)
+            .map_err(|error| format!("Invalid TOML in {}: {}", path.display(), error))?,
+        _ => return Err(format!("Unsupported file type: {}", path.display())),
+    };
+
+    Ok(config.ships
+        .iter()
+        .map(|ship| Ship {
+            cp: ship.cp,
+            hp: ship.hp,
+            attack_base: ship.attack_base,
+            attack_variable: ship.attack_variable,
+            defence: ship.defence,
+            speed: ship.speed,
+            range: ship.range,
+        })
+        .collect())
+}
+
+/// Checks that the ships can be used by the engine, within the CP budget. A fleet holds at most
+/// 255 ships of a type, so the budget has to be small enough to be filled by any single ship type.
+fn validate_ships(ships: &[Ship], cp_budget: u32) -> Result<(), String> {
+    if ships.len() != MAX_SHIPS {
+        return Err(format!("Expected {} ships, got {}", MAX_SHIPS, ships.len()));
+    }
+    newomegaengine::validate_ships(ships)
+        .map_err(|error| format!("Invalid ships: {:?}", error))?;
+    for (i, ship) in ships.iter().enumerate() {
+        if ship.cp as u32 > cp_budget {
+            return Err(format!("Ship {} does not fit in the CP budget", i));
+        }
+        if cp_budget / ship.cp as u32 > u8::MAX as u32 {
+            return Err(format!("Ship {} needs more than {} ships to fill the CP budget", i, u8::MAX));
+        }
+    }
+    Ok(())
+}
+
+fn matrix_csv(header: &[String], matrix: &[Vec<f64>]) -> String {
+    let mut csv: String = format!(",{}\n", header.join(","));
+    for (row, values) in matrix.iter().enumerate() {
+        let values: Vec<String> = values.iter().map(|value| format!("{:.4}", value)).collect();
+        csv.push_str(&format!("{},{}\n", header[row], values.join(",")));
+    }
+    csv
+}
+
+/// Writes the report as CSV files, one per table
+fn write_csv(report: &Report, directory: &Path) -> Result<(), String> {
+    fs::create_dir_all(directory)
+        .map_err(|error| format!("Cannot create {}: {}", directory.display(), error))?;
+
+    let mut ship_types: String = String::from("ship,cp,mono_fleet_cp,win_rate,cp_efficiency,average_rounds\n");
+    for ship_type in report.ship_types.iter() {
+        ship_types.push_str(&format!("{},{},{},{:.4},{:.4},{:.2}\n", ship_type.ship, ship_type.cp,
+            ship_type.mono_fleet_cp, ship_type.win_rate, ship_type.cp_efficiency, ship_type.average_rounds));
+    }
+
+    let ship_names: Vec<String> = (0..MAX_SHIPS).map(|i| format!("ship {}", i)).collect();
+
+    let mut dominant_strategies: String = String::from("kind,name,reason\n");
+    for strategy in report.dominant_strategies.iter() {
+        dominant_strategies.push_str(&format!("{},{},\"{}\"\n", strategy.kind, strategy.name, strategy.reason));
+    }
+
+    let files: [(&str, String); 4] = [
+        ("ship_types.csv", ship_types),
+        ("ship_matchups.csv", matrix_csv(&ship_names, &report.ship_matchups)),
+        ("targeting_matchups.csv", matrix_csv(&report.targeting_types, &report.targeting_matchups)),
+        ("dominant_strategies.csv", dominant_strategies),
+    ];
+    for (name, contents) in files.iter() {
+        let path: PathBuf = directory.join(name);
+        fs::write(&path, contents)
+            .map_err(|error| format!("Cannot write {}: {}", path.display(), error))?;
+    }
+
+    Ok(())
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let ships: Vec<Ship> = match &options.ships {
+        Some(path) => read_ships(path)?,
+        None => newomegaengine::prepare_ships(),
+    };
+    validate_ships(&ships, options.cp_budget)?;
+
+    let report: Report = analyse(&ships, &Settings {
+        cp_budget: options.cp_budget,
+        fleets: options.fleets,
+        seeds: options.seeds.max(1),
+        sampling_seed: options.sampling_seed,
+        dominance_threshold: options.dominance_threshold,
+    });
+
+    match options.format.as_str() {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            Ok(())
+        },
+        "csv" => {
+            let directory: &PathBuf = options.output.as_ref().ok_or("CSV output needs --output")?;
+            write_csv(&report, directory)
+        },
+        format => Err(format!("Unknown format: {}", format)),
+    }
+}
+
+fn main() {
+    if let Err(error) = run(Options::from_args()) {
+        eprintln!("Error: {}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> Settings {
+        Settings {
+            cp_budget: 40,
+            fleets: 20,
+            seeds: 2,
+            sampling_seed: 7,
+            dominance_threshold: 0.75,
+        }
+    }
+
+    #[test]
+    fn test_random_fleet_within_budget() {
+        let ships: Vec<Ship> = newomegaengine::prepare_ships();
+        let mut sampler: Sampler = Sampler::new(1);
+
+        for _ in 0..100 {
+            let selection: [u8; MAX_SHIPS] = random_fleet(&ships, 100, &mut sampler);
+            let cp: u32 = fleet_cp(&ships, &selection);
+            assert!(cp <= 100);
+            // Nothing fits anymore, as the cheapest ship has 1 CP
+            assert_eq!(cp, 100);
+        }
+    }
+
+    #[test]
+    fn test_mono_fleet() {
+        let ships: Vec<Ship> = newomegaengine::prepare_ships();
+
+        assert_eq!(mono_fleet(&ships, 3, 100), [0, 0, 0, 10]);
+        assert_eq!(mono_fleet(&ships, 0, 1000), [u8::MAX, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_analysis_is_reproducible() {
+        let ships: Vec<Ship> = newomegaengine::prepare_ships();
+        let first: Report = analyse(&ships, &settings());
+        let second: Report = analyse(&ships, &settings());
+
+        assert_eq!(serde_json::to_string(&first).unwrap(), serde_json::to_string(&second).unwrap());
+        assert_eq!(first.ship_types.len(), MAX_SHIPS);
+        assert_eq!(first.targeting_matchups.len(), TARGETING_TYPES.len());
+        assert!(first.ship_types.iter().all(|ship_type| ship_type.win_rate >= 0.0 && ship_type.win_rate <= 1.0));
+        // 40 CP is not a multiple of the 3 CP ship type
+        let mono_fleet_cp: Vec<u32> = first.ship_types.iter().map(|ship_type| ship_type.mono_fleet_cp).collect();
+        assert_eq!(mono_fleet_cp, vec![40, 39, 40, 40]);
+    }
+
+    #[test]
+    fn test_dominant_strategies() {
+        let ship_types: Vec<ShipTypeReport> = Vec::new();
+        let ship_matchups: Vec<Vec<f64>> = vec![
+            vec![0.5, 0.9, 0.8, 0.8],
+            vec![0.1, 0.5, 0.6, 0.6],
+            vec![0.2, 0.4, 0.5, 0.9],
+            vec![0.2, 0.4, 0.1, 0.5],
+        ];
+        let strategies: Vec<DominantStrategy> = find_dominant_strategies(&ship_types, &ship_matchups,
+            &[], 0.75);
+
+        assert_eq!(strategies.len(), 1);
+        assert_eq!(strategies[0].kind, "ship");
+        assert_eq!(strategies[0].name, "ship 0");
+    }
+
+    #[test]
+    fn test_validate_ships() {
+        let ships: Vec<Ship> = newomegaengine::prepare_ships();
+
+        assert!(validate_ships(&ships, 100).is_ok());
+        assert!(validate_ships(&ships, 5).is_err());
+        // 256 ships of the 1 CP ship type would be needed
+        assert!(validate_ships(&ships, 255).is_ok());
+        assert!(validate_ships(&ships, 256).is_err());
+        assert!(validate_ships(&ships[..2], 100).is_err());
+
+        let mut invalid_ships: Vec<Ship> = ships;
+        invalid_ships[0].hp = 0;
+        assert!(validate_ships(&invalid_ships, 100).is_err());
+    }
+}
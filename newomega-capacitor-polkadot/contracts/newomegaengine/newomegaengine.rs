@@ -0,0 +1,1892 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! The New Omega combat rules, as a plain Rust library (without ink!).
+//! Simulates fights, given a set of input parameters, for which it always gives
+//! a deterministic result, so the exact fight (moves of the players) can always be
+//! regenerated provided the same set of input parameters (fleet selection).
+//! Used by the contracts (NewOmega, NewOmegaGame), the native tools (simulator, balance
+//! analysis), and can be compiled to wasm for local replays in the client.
+//!
+//! Features:
+//! * `std` - standard library support (Debug, PartialEq and TypeInfo derives)
+//!
+//! The contracts keep the engine types in storage through newomegalayout, which provides
+//! their storage layouts.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+pub const MAX_SHIPS: usize = 4;
+/// Default maximum length of a fight, in rounds (see FightSettings)
+pub const MAX_ROUNDS: u8 = 50;
+
+/// Version of the fight rules implemented by the engine. Has to be bumped whenever a change
+/// to the engine alters the outcome of a fight, with the previous rules kept available
+/// (see FightEngine::new), so that older fights stay replayable.
+pub const RULES_VERSION: u16 = 1;
+
+/// Move types, as logged in Move.move_type
+pub const MOVE_TYPE_SHOOT: u8 = 1;
+pub const MOVE_TYPE_REPOSITION: u8 = 2;
+
+#[derive(scale::Encode, scale::Decode, Copy, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub enum TargetingType {
+    Furthest,
+    Closest,
+    HighestHp,
+    LowestHp,
+    HighestSpeed,
+    LowestSpeed,
+    HighestDefence,
+    LowestDefence,
+    HighestAttack,
+    LowestAttack
+}
+
+// `#[default]` on enum variants is not available on the pinned nightly (which does not know the lint either)
+#[allow(unknown_lints, clippy::derivable_impls)]
+impl Default for TargetingType {
+    fn default() -> Self { TargetingType::Furthest }
+}
+
+/// Outcome of a fight
+#[derive(scale::Encode, scale::Decode, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        scale_info::TypeInfo
+    )
+)]
+pub enum Outcome {
+    /// The defender was destroyed, or lost the tie-break
+    AttackerWin,
+    /// The attacker was destroyed, or lost the tie-break
+    DefenderWin,
+    /// Both fleets survived the maximum number of rounds, and no tie-break decided the fight
+    Draw,
+    /// Both fleets were destroyed in the same round
+    MutualDestruction,
+}
+
+impl Outcome {
+    /// Gets the outcome of a fight which ended with (at least) one of the fleets destroyed,
+    /// or a Draw if none was.
+    ///
+    /// # Arguments
+    ///
+    /// * `lhs_dead` - Did the attacker die?
+    /// * `rhs_dead` - Did the defender die?
+    pub fn from_dead(lhs_dead: bool, rhs_dead: bool) -> Self {
+        match (lhs_dead, rhs_dead) {
+            (true, true) => Outcome::MutualDestruction,
+            (true, false) => Outcome::DefenderWin,
+            (false, true) => Outcome::AttackerWin,
+            (false, false) => Outcome::Draw,
+        }
+    }
+}
+
+/// How a fight which is still not finished after the maximum number of rounds is decided
+#[derive(scale::Encode, scale::Decode, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        scale_info::TypeInfo
+    )
+)]
+pub enum TieBreak {
+    /// No tie-break, the fight is a Draw
+    None,
+    /// The side with the higher percentage of its starting Health Points left wins
+    HpPercentage,
+    /// The side with the higher percentage of its starting Command Power left wins
+    CpPercentage,
+}
+
+// See TargetingType, the default variant cannot be derived on the contracts' toolchain
+#[allow(unknown_lints, clippy::derivable_impls)]
+impl Default for TieBreak {
+    fn default() -> Self { TieBreak::None }
+}
+
+/// Settings of a single fight, chosen by the game mode the fight happens in
+#[derive(scale::Encode, scale::Decode, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        scale_info::TypeInfo
+    )
+)]
+pub struct FightSettings {
+    /// Maximum length of the fight, in rounds
+    pub max_rounds: u8,
+    /// How the fight is decided, if it is still not finished after max_rounds
+    pub tie_break: TieBreak,
+    /// Extra Health Points of the defender ships, in percent. Applied as a proportional
+    /// reduction of the damage the defender takes, so losses stay counted in whole ships.
+    pub hp_bonus_rhs: u16,
+}
+
+impl Default for FightSettings {
+    fn default() -> Self {
+        Self {
+            max_rounds: MAX_ROUNDS,
+            tie_break: TieBreak::None,
+            hp_bonus_rhs: 0,
+        }
+    }
+}
+
+/// Describes a single move in a fight.
+/// A move can be pure reposition, shoot, or reposition with shoot.
+#[derive(scale::Encode, scale::Decode, Copy, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub struct Move {
+    /// Shoot, Reposition
+    pub move_type: u8,
+    /// Round the move took place in
+    pub round: u8,
+    /// Source ship id
+    pub source: u8,
+    /// Target ship id, in the case of shoot
+    pub target: u8,
+    /// Position to move to, if needed
+    pub target_position: i16,
+    /// Damage of the shot, if needed
+    pub damage: u32,
+    /// Effects running on the attacker
+    pub effects_lhs: [RunningEffect; MAX_SHIPS],
+    /// Effects running on the defender
+    pub effects_rhs: [RunningEffect; MAX_SHIPS],
+}
+
+/// Describes a ship module definition
+#[derive(scale::Encode, scale::Decode, Copy, Clone, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub struct ShipModule {
+    /// Power of the snare effect
+    pub snare: u8,
+    /// Power of the root effect
+    pub root: u8,
+    /// Power of the blind effect
+    pub blind: u8,
+    /// Power of the attack debuff effect
+    pub attack_debuff: u8,
+    /// Power of the defence debuff effect
+    pub defence_debuff: u8,
+    /// Power of the range debuff effect
+    pub range_debuff: u8,
+}
+
+/// Describes a single Ship on the board
+/// A move can be pure reposition, shoot, or reposition with shoot.
+#[derive(scale::Encode, scale::Decode, Copy, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub struct Ship {
+    /// Command Power (to calculate fleet weights)
+    pub cp: u16,
+    /// Health Points of the ship
+    pub hp: u16,
+    /// Base attack
+    pub attack_base: u16,
+    /// Variable attack (subject to random)
+    pub attack_variable: u16,
+    /// Defence of the ship
+    pub defence: u16,
+    /// Speed, number of fields the ship can move in a round
+    pub speed: u8,
+    /// Range, number of fields in front of it the ship can shoot to in a round
+    pub range: u8
+}
+
+impl Ship {
+    /// Checks whether the ship definition can be used in fights.
+    ///
+    /// # Returns
+    ///
+    /// * `result` - Ok if the ship is valid, or the first problem found
+    pub fn validate(&self) -> Result<(), ShipError> {
+        if self.cp == 0 {
+            return Err(ShipError::ZeroCommandPower);
+        }
+        if self.hp == 0 {
+            return Err(ShipError::ZeroHp);
+        }
+        if self.attack_variable == 0 {
+            return Err(ShipError::ZeroAttackVariable);
+        }
+
+        Ok(())
+    }
+}
+
+/// Problems with a single ship definition
+#[derive(scale::Encode, scale::Decode, Copy, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub enum ShipError {
+    /// Ships without Command Power would be free to produce and field
+    ZeroCommandPower,
+    /// Ships without Health Points cannot be counted from the fleet HPs
+    ZeroHp,
+    /// The variable attack is a modulo of the seed, so it has to be positive
+    ZeroAttackVariable,
+}
+
+/// Reasons for a fight to be refused by the engine
+#[derive(scale::Encode, scale::Decode, Copy, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub enum FightError {
+    /// Less than MAX_SHIPS ship definitions were given
+    NotEnoughShips,
+    /// A ship definition is invalid (ship identifier, problem)
+    InvalidShip(u8, ShipError),
+    /// The fight settings allow no rounds at all
+    ZeroMaxRounds,
+}
+
+#[derive(scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub struct FightResult {
+    /// Attacker fleet composition
+    pub selection_lhs: [u8; MAX_SHIPS],
+    /// Defencer fleet composition
+    pub selection_rhs: [u8; MAX_SHIPS],
+    /// Attacker ship modules
+    pub modules_lhs: [ShipModule; MAX_SHIPS],
+    /// Defender ship modules
+    pub modules_rhs: [ShipModule; MAX_SHIPS],
+    /// Attacker targeting
+    pub targeting_lhs: TargetingType,
+    /// Defender targeting
+    pub targeting_rhs: TargetingType,
+    /// Did the attacker die?
+    pub lhs_dead: bool,
+    /// Did the defender die?
+    pub rhs_dead: bool,
+    /// Outcome of the fight (including the tie-break, if the fight reached the maximum rounds)
+    pub outcome: Outcome,
+    /// Settings the fight was generated with
+    pub settings: FightSettings,
+    /// Length of the fight in rounds
+    pub rounds: u8,
+    /// Random seed the fight was generated with
+    pub seed: u64,
+    /// Attackers ships lost
+    pub ships_lost_lhs: [u8; MAX_SHIPS],
+    /// Defenders ships lost
+    pub ships_lost_rhs: [u8; MAX_SHIPS],
+    /// Version of the fight rules the fight was generated with
+    pub rules_version: u16,
+    /// Version of the ship table the fight was generated with (set by the owner of the table)
+    pub ships_version: u16,
+    /// Attacker statistics, per ship type
+    pub stats_lhs: FleetStats,
+    /// Defender statistics, per ship type
+    pub stats_rhs: FleetStats,
+}
+
+/// Output of a fight: the result, and the moves of both sides (when logged)
+pub type FightOutput = (FightResult, Option<Vec<Move>>, Option<Vec<Move>>);
+
+/// Per ship type statistics of one side of a fight
+#[derive(scale::Encode, scale::Decode, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub struct FleetStats {
+    /// Damage dealt by each ship type (as logged in the moves, ie including overkill)
+    pub damage_dealt: [u32; MAX_SHIPS],
+    /// Damage received by each ship type (as logged in the moves, ie including overkill)
+    pub damage_received: [u32; MAX_SHIPS],
+    /// Number of shots fired by each ship type
+    pub shots_fired: [u16; MAX_SHIPS],
+    /// Number of effects applied to the enemy by each ship type (through its modules)
+    pub effects_applied: [u16; MAX_SHIPS],
+    /// Enemy ships destroyed, by source (own ship type) and target (enemy ship type)
+    pub kills: [[u16; MAX_SHIPS]; MAX_SHIPS],
+    /// Health Points left of each ship type, at the end of the fight
+    pub hp_remaining: [u32; MAX_SHIPS],
+}
+
+#[derive(scale::Encode, scale::Decode, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub struct RunningEffect {
+    /// Number of rounds left on the snare effect
+    pub snare: u8,
+    /// Number of rounds left on the root effect
+    pub root: u8,
+    /// Number of rounds left on the blind effect
+    pub blind: u8,
+    /// Number of rounds left on the attack debuff effect
+    pub attack_debuff: u8,
+    /// Number of rounds left on the defence debuff effect
+    pub defence_debuff: u8,
+    /// Number of rounds left on the range debuff effect
+    pub range_debuff: u8,
+}
+
+pub fn prepare_ships() -> Vec<Ship> {
+    // Initialize default ships
+    alloc::vec![
+        Ship {
+            cp: 1,
+            hp: 120,
+            attack_base: 80,
+            attack_variable: 20,
+            defence: 20,
+            speed: 4,
+            range: 4,
+        },
+        Ship {
+            cp: 3,
+            hp: 150,
+            attack_base: 65,
+            attack_variable: 20,
+            defence: 30,
+            speed: 3,
+            range: 8,
+        },
+        Ship {
+            cp: 4,
+            hp: 220,
+            attack_base: 65,
+            attack_variable: 20,
+            defence: 35,
+            speed: 2,
+            range: 15,
+        },
+        Ship {
+            cp: 10,
+            hp: 450,
+            attack_base: 80,
+            attack_variable: 20,
+            defence: 40,
+            speed: 1,
+            range: 30,
+        },
+    ]
+}
+
+/// Checks whether a ship table can be used in fights (only the first MAX_SHIPS ships fight).
+///
+/// # Arguments
+///
+/// * `ships` - A Vec that holds the definiton of all the ships
+///
+/// # Returns
+///
+/// * `result` - Ok if the ships are valid, or the first problem found
+pub fn validate_ships(ships: &[Ship]) -> Result<(), FightError> {
+    if ships.len() < MAX_SHIPS {
+        return Err(FightError::NotEnoughShips);
+    }
+    for (i, ship) in ships.iter().enumerate().take(MAX_SHIPS) {
+        if let Err(error) = ship.validate() {
+            return Err(FightError::InvalidShip(i as u8, error));
+        }
+    }
+
+    Ok(())
+}
+
+/// The fight engine. Holds no state other than the version of the rules it follows,
+/// all its methods are pure.
+#[derive(Clone, Copy)]
+pub struct FightEngine {
+    rules_version: u16,
+}
+
+impl Default for FightEngine {
+    fn default() -> Self {
+        Self::new(RULES_VERSION)
+    }
+}
+
+impl FightEngine {
+
+    /// Creates an engine following a given version of the fight rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules_version` - Version of the fight rules, from 1 up to RULES_VERSION
+    pub fn new(rules_version: u16) -> Self {
+        assert!(rules_version >= 1 && rules_version <= RULES_VERSION, "Unsupported rules version");
+
+        Self {
+            rules_version,
+        }
+    }
+
+    /// Gets the version of the fight rules the engine follows.
+    ///
+    /// # Returns
+    ///
+    /// * `rules_version` - Version of the fight rules
+    pub fn rules_version(&self) -> u16 {
+        self.rules_version
+    }
+
+    /// Return minimum of two i32 values
+    fn min(&self, lhs: i32, rhs: i32) -> i32 {
+        if lhs > rhs {
+            rhs
+        } else {
+            lhs
+        }
+    }
+
+    /// Return maximum of two i32 values
+    fn max(&self, lhs: i32, rhs: i32) -> i32 {
+        if lhs > rhs {
+            lhs
+        } else {
+            rhs
+        }
+    }
+
+    /// Gets the score of a fleet for the tie-break, as the amount left and the amount
+    /// the fleet started with (of Health Points or Command Power, depending on the tie-break).
+    ///
+    /// # Arguments
+    ///
+    /// * `tie_break` - The tie-break used
+    /// * `ships` - A Vec that holds the definiton of all the ships
+    /// * `selection` - Fleet composition (array with ship quantities)
+    /// * `ships_lost` - Ships lost by the fleet
+    /// * `hp_remaining` - Health Points left of each ship type
+    ///
+    /// # Returns
+    ///
+    /// * `left` - Amount left at the end of the fight
+    /// * `total` - Amount at the start of the fight
+    fn get_tie_break_score(&self, tie_break: TieBreak, ships: &[Ship],
+        selection: [u8; MAX_SHIPS], ships_lost: [u8; MAX_SHIPS],
+        hp_remaining: [u32; MAX_SHIPS]) -> (u64, u64) {
+
+        let mut left: u64 = 0;
+        let mut total: u64 = 0;
+
+        for i in 0..MAX_SHIPS {
+            match tie_break {
+                TieBreak::None => {},
+                TieBreak::HpPercentage => {
+                    left += hp_remaining[i] as u64;
+                    total += selection[i] as u64 * ships[i].hp as u64;
+                },
+                TieBreak::CpPercentage => {
+                    left += (selection[i] - ships_lost[i]) as u64 * ships[i].cp as u64;
+                    total += selection[i] as u64 * ships[i].cp as u64;
+                },
+            }
+        }
+
+        (left, total)
+    }
+
+    /// Decides a fight which is still not finished after the maximum number of rounds.
+    /// The side with the higher percentage left wins, equal percentages are a Draw.
+    ///
+    /// # Arguments
+    ///
+    /// * `tie_break` - The tie-break used
+    /// * `score_lhs` - Attacker score (left, total), see get_tie_break_score
+    /// * `score_rhs` - Defender score (left, total), see get_tie_break_score
+    ///
+    /// # Returns
+    ///
+    /// * `outcome` - Outcome of the fight
+    fn break_tie(&self, tie_break: TieBreak, score_lhs: (u64, u64), score_rhs: (u64, u64)) -> Outcome {
+        if tie_break == TieBreak::None {
+            return Outcome::Draw;
+        }
+
+        // Compare left_lhs / total_lhs with left_rhs / total_rhs, without dividing
+        let (left_lhs, total_lhs) = score_lhs;
+        let (left_rhs, total_rhs) = score_rhs;
+        let percentage_lhs: u64 = left_lhs.saturating_mul(total_rhs);
+        let percentage_rhs: u64 = left_rhs.saturating_mul(total_lhs);
+
+        match percentage_lhs.cmp(&percentage_rhs) {
+            Ordering::Greater => Outcome::AttackerWin,
+            Ordering::Less => Outcome::DefenderWin,
+            Ordering::Equal => Outcome::Draw,
+        }
+    }
+
+    /// Checks whether player is dead, according to their ship hp's
+    ///
+    /// # Arguments
+    ///
+    /// * `ship_hps` - An array of fleet HPs of the player
+    ///
+    /// # Returns
+    ///
+    /// * `is_dead` - Whether the player fleet is dead
+    fn is_dead(&self, ship_hps: [i32; MAX_SHIPS]) -> bool {
+        ship_hps.iter().all(|hp| *hp <= 0)
+    }
+
+    /// Gets the defence stat of a ship, possibly modified by the effect
+    ///
+    /// # Arguments
+    ///
+    /// * `stat` - Base ship statistic to modify
+    /// * `effect` - The running effect
+    ///
+    /// # Returns
+    ///
+    /// * `final_stat` - The modified defence stat
+    fn get_defence_stat(&self, stat: u16, effect: &RunningEffect) -> u16 {
+        if effect.defence_debuff > 0 {
+            stat / 2
+        } else {
+            stat
+        }
+    }
+
+    /// Gets the attack stat of a ship, possibly modified by the effect
+    ///
+    /// # Arguments
+    ///
+    /// * `stat` - Base ship statistic to modify
+    /// * `effect` - The running effect
+    ///
+    /// # Returns
+    ///
+    /// * `final_stat` - The modified attack stat
+    fn get_attack_stat(&self, stat: u16, effect: &RunningEffect) -> u16 {
+        if effect.attack_debuff > 0 {
+            stat / 2
+        } else {
+            stat
+        }
+    }
+
+    /// Gets the range stat of a ship, possibly modified by the effect
+    ///
+    /// # Arguments
+    ///
+    /// * `stat` - Base ship statistic to modify
+    /// * `effect` - The running effect
+    ///
+    /// # Returns
+    ///
+    /// * `final_stat` - The modified defence stat
+    fn get_range_stat(&self, stat: u8, effect: &RunningEffect) -> u8 {
+        if effect.range_debuff > 0 {
+            stat / 2
+        } else {
+            stat
+        }
+    }
+
+    /// Gets the speed stat of a ship, possibly modified by the effect
+    ///
+    /// # Arguments
+    ///
+    /// * `stat` - Base ship statistic to modify
+    /// * `effect` - The running effect
+    ///
+    /// # Returns
+    ///
+    /// * `final_stat` - The modified defence stat
+    fn get_speed_stat(&self, stat: u8, effect: &RunningEffect) -> u8 {
+        if effect.root > 0 || effect.blind > 0 {
+            0
+        } else if effect.snare > 0 {
+            stat / 2
+        } else {
+            stat
+        }
+    }
+
+    /// Picks a target for a ship.
+    ///
+    /// # Arguments
+    ///
+    /// * `ships` - A Vec that holds the definiton of all the ships
+    /// * `current_ship` - Index of the ship to pick target for
+    /// * `ship_positions_own` - An array of fleet positions of the player performing the move
+    /// * `ship_positions_enemy` - An array of fleet positions of the player NOT performing the move
+    /// * `ship_hps_own` - An array of fleet HPs of the player performing the move
+    /// * `ship_hps_enemy` - An array of fleet HPs of the player NOT performing the move
+    ///
+    /// # Returns
+    ///
+    /// * `has_target` - A bool, indicating whether a target has been found
+    /// * `target` - Target ship identifier
+    /// * `proposed_move` - The new source ship position (can be unchanged)
+    ///
+    /// # Algorithm rules:
+    /// ```text
+    ///     To be considered in range, target ship must be within range+speed from source ship
+    ///     Targets are picked according to their size, ie bigger ships first
+    /// ```
+    #[allow(clippy::too_many_arguments)] // Both fleets' positions, HPs and effects
+    fn get_target(&self,
+        ships: &[Ship],
+        current_ship: u8,
+        ship_positions_own: [i16; MAX_SHIPS],
+        ship_positions_enemy: [i16; MAX_SHIPS],
+        ship_hps_enemy: [i32; MAX_SHIPS],
+        effects_own: &[RunningEffect; MAX_SHIPS],
+        effects_enemy: &[RunningEffect; MAX_SHIPS],
+        targeting: TargetingType) -> (bool, u8, u8) {
+
+        let current_ship_usize:usize = current_ship as usize;
+        let position:i16 = ship_positions_own[current_ship_usize];
+        let mut proposed_move:u8 = 0;
+        let mut best_target:u8 = MAX_SHIPS as u8;
+        let mut best_value:u16 = 0;
+
+        if effects_own[current_ship_usize].blind == 0 {
+            for enemy_ship in (0..MAX_SHIPS as u8).rev() {
+                let enemy_ship_usize:usize = enemy_ship as usize;
+
+                let position_diff:i16 = position.saturating_sub(ship_positions_enemy[enemy_ship_usize]);
+                // Distances beyond the u8 range are out of reach anyway
+                let delta:u8 = self.min(position_diff.saturating_abs() as i32, u8::MAX as i32) as u8;
+                let range:u8 = self.get_range_stat(ships[current_ship_usize].range,
+                    &effects_own[current_ship_usize]);
+                let speed:u8 = self.get_speed_stat(ships[current_ship_usize].speed,
+                    &effects_own[current_ship_usize]);
+
+                if (delta <= range.saturating_add(speed)) && ship_hps_enemy[enemy_ship_usize] > 0 {
+                    let cur_value:u16 = self.get_stat_for_targeting(
+                        targeting, enemy_ship_usize, delta, ships, effects_enemy);
+                    if (best_target == MAX_SHIPS as u8) || self.is_stat_better_for_targeting(
+                        targeting, cur_value, best_value) {
+
+                        // We have found a target
+                        best_target = enemy_ship;
+                        best_value = cur_value;
+
+                        // Do we need to move?
+                        if delta > range {
+                            proposed_move = delta - range;
+                        } else {
+                            proposed_move = 0;
+                        }
+                    }
+                }
+            }
+        }
+
+        (best_target < (MAX_SHIPS as u8), best_target, proposed_move)
+    }
+
+    fn get_stat_for_targeting(&self, targeting: TargetingType,
+        target_usize: usize, delta: u8, ships: &[Ship],
+        effects: &[RunningEffect; MAX_SHIPS]) -> u16 {
+
+        let attack: u16 = self.get_attack_stat(ships[target_usize].attack_base,
+            &effects[target_usize]);
+        let defence: u16 = self.get_defence_stat(ships[target_usize].defence,
+            &effects[target_usize]);
+        let speed: u16 = self.get_speed_stat(ships[target_usize].speed,
+            &effects[target_usize]) as u16;
+        let hp: u16 = ships[target_usize].hp;
+
+        match targeting {
+            TargetingType::Closest => delta as u16,
+            TargetingType::Furthest => delta as u16,
+            TargetingType::LowestAttack => attack,
+            TargetingType::LowestDefence => defence,
+            TargetingType::LowestSpeed => speed,
+            TargetingType::LowestHp => hp,
+            TargetingType::HighestAttack => attack,
+            TargetingType::HighestDefence => defence,
+            TargetingType::HighestSpeed => speed,
+            TargetingType::HighestHp => hp,
+        }
+    }
+
+    fn is_stat_better_for_targeting(&self, targeting: TargetingType,
+        cur_value: u16, best_value: u16) -> bool {
+
+        match targeting {
+            TargetingType::Closest => cur_value < best_value,
+            TargetingType::Furthest => cur_value > best_value,
+            TargetingType::LowestAttack => cur_value < best_value,
+            TargetingType::LowestDefence => cur_value < best_value,
+            TargetingType::LowestSpeed => cur_value < best_value,
+            TargetingType::LowestHp => cur_value < best_value,
+            TargetingType::HighestAttack => cur_value > best_value,
+            TargetingType::HighestDefence => cur_value > best_value,
+            TargetingType::HighestSpeed => cur_value > best_value,
+            TargetingType::HighestHp => cur_value > best_value,
+        }
+    }
+
+    fn get_number_of_ships_from_hp(&self, hp_total: u32, hp: u16) -> u16 {
+        let hp32: u32 = hp as u32;
+        if hp32 == 0 {
+            return 0;
+        }
+
+        // u32::is_multiple_of is newer than the contracts' toolchain
+        #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+        let ships_count: u32 = if hp_total % hp32 == 0 {
+            hp_total / hp32
+        } else {
+            hp_total / hp32 + 1
+        };
+
+        self.min(ships_count as i32, u16::MAX as i32) as u16
+    }
+
+    /// Calculate damage done by a ship to another ship.
+    ///
+    /// # Arguments
+    ///
+    /// * `variables` - An array that holds the precalculated variable damage coefficients
+    /// * `effects_source` - An array that holds effects of the fleet of the player shooting
+    /// * `effects_target` - An array that holds effects of the fleet of the player NOT shooting
+    /// * `ships` - A Vec that holds the definiton of all the ships
+    /// * `source` - Index of the ship shooting
+    /// * `target` - Index of the ship being shot at
+    /// * `source_hp` - HPs left, of the shooting ship
+    /// * `hp_bonus_target` - Extra Health Points of the ship being shot at, in percent
+    ///
+    /// # Returns
+    ///
+    /// * `damage` - The calculated damage
+    #[allow(clippy::too_many_arguments)] // The stats of both sides of the shot
+    fn calculate_damage(&self, variables: [u16; MAX_SHIPS], effects_source: &[RunningEffect; MAX_SHIPS],
+        effects_target: &[RunningEffect; MAX_SHIPS], ships: &[Ship], source: u8,
+        target: u8, source_hp: u32, hp_bonus_target: u16) -> u32 {
+
+        let source_usize: usize = source as usize;
+        let target_usize: usize = target as usize;
+        let attack: u16 = self.get_attack_stat(ships[source_usize].attack_base,
+            &effects_source[source_usize]).saturating_add(variables[source_usize]);
+        let source_ships_count: u16 = self.get_number_of_ships_from_hp(source_hp, ships[source_usize].hp);
+        let cap_damage: u32 = (source_ships_count as u32).saturating_mul(ships[target_usize].hp as u32);
+        let mut defence: u16 = self.get_defence_stat(ships[target_usize].defence,
+            &effects_target[target_usize]);
+
+        if defence > attack {
+            defence = attack;
+        }
+
+        let damage: u32 = ((attack - defence) as u32).saturating_mul(source_ships_count as u32);
+        // The extra Health Points of the target absorb their share of the damage
+        let damage: u32 = (damage as u64 * 100 / (100 + hp_bonus_target as u64)) as u32;
+
+        if damage > cap_damage {
+            cap_damage
+        } else {
+            damage
+        }
+    }
+
+    /// Logs the Shoot move into the moves array.
+    ///
+    /// # Arguments
+    ///
+    /// * `round` - Round in which the move took place
+    /// * `moves` - The Moves array to modify (mutable)
+    /// * `source` - Index of the ship performing the move
+    /// * `target` - Index of the target ship
+    /// * `damage` - Damage inflicted
+    /// * `position` - New ship position (can be unchanged)
+    #[allow(clippy::too_many_arguments)] // Mirrors the fields of the logged Move
+    fn log_shoot(&self, round: u8, moves: &mut Vec<Move>,
+        source: u8, target: u8, damage: u32, position: i16,
+        effects_lhs: &[RunningEffect; MAX_SHIPS],
+        effects_rhs: &[RunningEffect; MAX_SHIPS]) {
+
+        moves.push(Move {
+            move_type: MOVE_TYPE_SHOOT,
+            round,
+            source,
+            target,
+            damage,
+            target_position: position,
+            effects_lhs: *effects_lhs,
+            effects_rhs: *effects_rhs,
+        });
+    }
+
+    /// Logs the Reposition move into the moves array.
+    ///
+    /// # Arguments
+    ///
+    /// * `round` - Round in which the move took place
+    /// * `moves` - The Moves array to modify (mutable)
+    /// * `source` - Index of the ship performing the move
+    /// * `target` - Index of the target ship
+    /// * `damage` - Damage inflicted
+    /// * `position` - New ship position (can be unchanged)
+    fn log_move(&self, round: u8, moves: &mut Vec<Move>,
+        source: u8, target_position: i16,
+        effects_lhs: &[RunningEffect; MAX_SHIPS],
+        effects_rhs: &[RunningEffect; MAX_SHIPS]) {
+
+        moves.push(Move {
+            move_type: MOVE_TYPE_REPOSITION,
+            round,
+            source,
+            target_position,
+            target: 0,
+            damage: 0,
+            effects_lhs: *effects_lhs,
+            effects_rhs: *effects_rhs,
+        });
+    }
+
+    fn retire_effects(&self, effects_target: &mut [RunningEffect; MAX_SHIPS], target: u8) {
+        let target_usize = target as usize;
+        let target_effect: &mut RunningEffect = &mut effects_target[target_usize];
+
+        if target_effect.snare > 0 {
+            target_effect.snare -= 1;
+        }
+        if target_effect.root > 0 {
+            target_effect.root -= 1;
+        }
+        if target_effect.blind > 0 {
+            target_effect.blind -= 1;
+        }
+        if target_effect.defence_debuff > 0 {
+            target_effect.defence_debuff -= 1;
+        }
+        if target_effect.attack_debuff > 0 {
+            target_effect.attack_debuff -= 1;
+        }
+        if target_effect.range_debuff > 0 {
+            target_effect.range_debuff -= 1;
+        }
+    }
+
+    /// Applies the effects of the source ship modules to the target ship.
+    ///
+    /// # Returns
+    ///
+    /// * `effects_applied` - Number of effects applied
+    fn apply_effects(&self, modules_source: &[ShipModule; MAX_SHIPS],
+        effects_target: &mut [RunningEffect; MAX_SHIPS],
+        source: u8, target: u8, seed: u64) -> u8 {
+
+        let source_usize = source as usize;
+        let target_usize = target as usize;
+        let ship_module: ShipModule = modules_source[source_usize];
+        let target_effect: &mut RunningEffect = &mut effects_target[target_usize];
+        let dice_roll: u8 = (seed % 100) as u8;
+        let effect_length: u8 = 1;
+        let mut effects_applied: u8 = 0;
+
+        if dice_roll < ship_module.snare {
+            target_effect.snare = target_effect.snare.saturating_add(effect_length);
+            effects_applied += 1;
+        }
+        if dice_roll < ship_module.root {
+            target_effect.root = target_effect.root.saturating_add(effect_length);
+            effects_applied += 1;
+        }
+        if dice_roll < ship_module.blind {
+            target_effect.blind = target_effect.blind.saturating_add(effect_length);
+            effects_applied += 1;
+        }
+        if dice_roll < ship_module.defence_debuff {
+            target_effect.defence_debuff = target_effect.defence_debuff.saturating_add(effect_length);
+            effects_applied += 1;
+        }
+        if dice_roll < ship_module.attack_debuff {
+            target_effect.attack_debuff = target_effect.attack_debuff.saturating_add(effect_length);
+            effects_applied += 1;
+        }
+        if dice_roll < ship_module.range_debuff {
+            target_effect.range_debuff = target_effect.range_debuff.saturating_add(effect_length);
+            effects_applied += 1;
+        }
+
+        effects_applied
+    }
+
+    /// Records a shot in the statistics of both sides.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats_source` - Statistics of the player shooting (mutable)
+    /// * `stats_target` - Statistics of the player NOT shooting (mutable)
+    /// * `ships` - A Vec that holds the definiton of all the ships
+    /// * `source` - Index of the ship shooting
+    /// * `target` - Index of the ship being shot at
+    /// * `damage` - Damage inflicted
+    /// * `target_hp_before` - HPs of the target ship, before the shot
+    /// * `target_hp_after` - HPs of the target ship, after the shot
+    /// * `effects_applied` - Number of effects applied by the shot
+    #[allow(clippy::too_many_arguments)] // Both sides' stats, and the outcome of the shot
+    fn record_shot(&self, stats_source: &mut FleetStats, stats_target: &mut FleetStats,
+        ships: &[Ship], source: u8, target: u8, damage: u32, target_hp_before: i32,
+        target_hp_after: i32, effects_applied: u8) {
+
+        let source_usize: usize = source as usize;
+        let target_usize: usize = target as usize;
+        let ships_before: u16 = self.get_number_of_ships_from_hp(
+            self.max(target_hp_before, 0) as u32, ships[target_usize].hp);
+        let ships_after: u16 = self.get_number_of_ships_from_hp(
+            self.max(target_hp_after, 0) as u32, ships[target_usize].hp);
+
+        stats_source.damage_dealt[source_usize] =
+            stats_source.damage_dealt[source_usize].saturating_add(damage);
+        stats_target.damage_received[target_usize] =
+            stats_target.damage_received[target_usize].saturating_add(damage);
+        stats_source.shots_fired[source_usize] =
+            stats_source.shots_fired[source_usize].saturating_add(1);
+        stats_source.effects_applied[source_usize] =
+            stats_source.effects_applied[source_usize].saturating_add(effects_applied as u16);
+        stats_source.kills[source_usize][target_usize] =
+            stats_source.kills[source_usize][target_usize].saturating_add(ships_before - ships_after);
+    }
+
+    /// Calculates a fight, see try_fight. Panics if the fight is refused (invalid ships).
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seed used to generate randomness
+    /// * `log_moves` - Whether to return a detailed fight log
+    /// * `ships` - A Vec that holds the definiton of all the ships
+    /// * `selection_lhs` - Attacker fleet composition (array with ship quantities)
+    /// * `selection_rhs` - Defender fleet composition (array with ship quantities)
+    /// * `modules_lhs` - An array that holds modules of the attacker fleet
+    /// * `modules_rhs` - An array that holds modules of the defender fleet
+    /// * `targeting_lhs` - Attacker targeting
+    /// * `targeting_rhs` - Defender targeting
+    /// * `settings` - Maximum number of rounds, and the tie-break of fights reaching it
+    ///
+    /// # Returns
+    ///
+    /// * `result` - A FightResult structure containing the result
+    /// * `moves_lhs` - Logged moves of the attacker, if requested. None if not.
+    /// * `moves_rhs` - Logged moves of the defender, if requested. None if not.
+    ///
+    /// # Algorithm rules:
+    /// ```text
+    ///     A fight is divided into rounds.
+    ///     Each round, ships perform moves in turns, starting from smallest ships.
+    ///     In each round, the same type of ship, of both the attacker and defender,
+    ///        attacks at the same time.
+    ///     Ships can move, shoot, or both, depending on their Range and Speed.
+    ///     The winner is declared when one player is dead, or when the fight is still not finished
+    ///        after maximum number of rounds (by the tie-break, if any - otherwise it is a draw).
+    /// ```
+    #[allow(clippy::too_many_arguments)] // The full set of fight inputs, so a fight can be replayed
+    pub fn fight(&self, seed: u64, log_moves: bool, ships: Vec<Ship>,
+        selection_lhs: [u8; MAX_SHIPS], selection_rhs: [u8; MAX_SHIPS],
+        modules_lhs: [ShipModule; MAX_SHIPS], modules_rhs: [ShipModule; MAX_SHIPS],
+        targeting_lhs: TargetingType, targeting_rhs: TargetingType, settings: FightSettings) ->
+            FightOutput {
+
+        match self.try_fight(seed, log_moves, ships, selection_lhs, selection_rhs,
+            modules_lhs, modules_rhs, targeting_lhs, targeting_rhs, settings) {
+            Ok(fight) => fight,
+            Err(FightError::ZeroMaxRounds) => panic!("Invalid fight settings"),
+            Err(_) => panic!("Invalid ships"),
+        }
+    }
+
+    /// Calculates a fight, provided the ships (see validate_ships) and the settings are valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Seed used to generate randomness
+    /// * `log_moves` - Whether to return a detailed fight log
+    /// * `ships` - A Vec that holds the definiton of all the ships
+    /// * `selection_lhs` - Attacker fleet composition (array with ship quantities)
+    /// * `selection_rhs` - Defender fleet composition (array with ship quantities)
+    /// * `modules_lhs` - An array that holds modules of the attacker fleet
+    /// * `modules_rhs` - An array that holds modules of the defender fleet
+    /// * `targeting_lhs` - Attacker targeting
+    /// * `targeting_rhs` - Defender targeting
+    /// * `settings` - Maximum number of rounds, and the tie-break of fights reaching it
+    ///
+    /// # Returns
+    ///
+    /// * `result` - The FightResult with the logged moves (as returned by fight),
+    ///   or the FightError the fight was refused with
+    #[allow(clippy::too_many_arguments)] // Same inputs as fight
+    pub fn try_fight(&self, seed: u64, log_moves: bool, ships: Vec<Ship>,
+        selection_lhs: [u8; MAX_SHIPS], selection_rhs: [u8; MAX_SHIPS],
+        modules_lhs: [ShipModule; MAX_SHIPS], modules_rhs: [ShipModule; MAX_SHIPS],
+        targeting_lhs: TargetingType, targeting_rhs: TargetingType, settings: FightSettings) ->
+            Result<FightOutput, FightError> {
+
+        validate_ships(&ships)?;
+        if settings.max_rounds == 0 {
+            return Err(FightError::ZeroMaxRounds);
+        }
+
+        // Starting ship positions for both sides
+        let mut ship_positions_lhs: [i16; MAX_SHIPS] = [10, 11, 12, 13];
+        let mut ship_positions_rhs: [i16; MAX_SHIPS] = [-10, -11, -12, -13];
+        // Current ship HPs, per ship type
+        let mut ship_hps_lhs: [i32; MAX_SHIPS] = [0; MAX_SHIPS];
+        let mut ship_hps_rhs: [i32; MAX_SHIPS] = [0; MAX_SHIPS];
+        // Precalculated variable damage coefficients
+        let mut variables_lhs: [u16; MAX_SHIPS] = [0; MAX_SHIPS];
+        let mut variables_rhs: [u16; MAX_SHIPS] = [0; MAX_SHIPS];
+        // Tracking running effects
+        let mut effects_lhs: [RunningEffect; MAX_SHIPS] = [RunningEffect::default(); MAX_SHIPS];
+        let mut effects_rhs: [RunningEffect; MAX_SHIPS] = [RunningEffect::default(); MAX_SHIPS];
+        // Statistics of both sides
+        let mut stats_lhs: FleetStats = FleetStats::default();
+        let mut stats_rhs: FleetStats = FleetStats::default();
+
+        // Precalculate the variables and initialize the ship HPs
+        for i in 0..MAX_SHIPS {
+            ship_hps_lhs[i] = (ships[i].hp as i32).saturating_mul(selection_lhs[i] as i32);
+            ship_hps_rhs[i] = (ships[i].hp as i32).saturating_mul(selection_rhs[i] as i32);
+            variables_lhs[i] = seed.checked_rem(ships[i].attack_variable as u64).unwrap_or(0) as u16;
+            variables_rhs[i] = (seed / 2).checked_rem(ships[i].attack_variable as u64).unwrap_or(0) as u16;
+        }
+
+        let mut lhs_moves: Option<Vec<Move>> = None;
+        let mut rhs_moves: Option<Vec<Move>> = None;
+        let mut total_rounds: u8 = 0;
+
+        // Only initialize the moves when required, to save gas
+        if log_moves {
+            lhs_moves = Some(Vec::new());
+            rhs_moves = Some(Vec::new());
+        }
+
+        // Loop intented to be broken out of if resolution is found quicker than max_rounds
+        for round in 0..settings.max_rounds {
+            if self.is_dead(ship_hps_lhs) || self.is_dead(ship_hps_rhs) {
+                break;
+            }
+
+            let round_u8: u8 = round;
+            total_rounds += 1;
+
+            // Loop through all the ships
+            for current_ship in 0..MAX_SHIPS {
+                let current_ship_u8: u8 = current_ship as u8;
+                let lhs_dead_ship: bool = ship_hps_lhs[current_ship] <= 0;
+                let rhs_dead_ship: bool = ship_hps_rhs[current_ship] <= 0;
+                let lhs_current_ship_speed: u8 = self.get_speed_stat(
+                    ships[current_ship].speed, &effects_lhs[current_ship]);
+                let rhs_current_ship_speed: u8 = self.get_speed_stat(
+                    ships[current_ship].speed, &effects_rhs[current_ship]);
+
+                // Note, moving and dealing damage to attacker is delayed until defender has moved also
+                let (lhs_has_target, lhs_target, lhs_delta_move, lhs_damage): (bool, u8, u8, u32) =
+                    if !lhs_dead_ship {
+                        let (has_target, target, delta_move): (bool, u8, u8) = self.get_target(
+                            &ships, current_ship_u8, ship_positions_lhs, ship_positions_rhs,
+                            ship_hps_rhs, &effects_lhs, &effects_rhs, targeting_lhs);
+                        let damage: u32 = if has_target {
+                            self.calculate_damage(variables_lhs, &effects_lhs, &effects_rhs,
+                                &ships, current_ship_u8, target, ship_hps_lhs[current_ship] as u32,
+                                settings.hp_bonus_rhs)
+                        } else {
+                            0
+                        };
+
+                        (has_target, target, delta_move, damage)
+                    } else {
+                        (false, 0, 0, 0)
+                    };
+
+                if !rhs_dead_ship {
+                    let (rhs_has_target, rhs_target, rhs_delta_move): (bool, u8, u8) = self.get_target(
+                        &ships, current_ship_u8, ship_positions_rhs, ship_positions_lhs,
+                        ship_hps_lhs, &effects_rhs, &effects_lhs, targeting_rhs);
+
+                    if rhs_has_target {
+                        let rhs_damage: u32 = self.calculate_damage(variables_rhs, &effects_rhs, &effects_lhs,
+                            &ships, current_ship_u8, rhs_target, ship_hps_rhs[current_ship] as u32, 0);
+
+                        // Move the ships, apply the damage
+                        let target_hp_before: i32 = ship_hps_lhs[rhs_target as usize];
+                        ship_hps_lhs[rhs_target as usize] =
+                            ship_hps_lhs[rhs_target as usize].saturating_sub(rhs_damage as i32);
+                        ship_positions_rhs[current_ship] =
+                            ship_positions_rhs[current_ship].saturating_add(rhs_delta_move as i16);
+
+                        self.retire_effects(&mut effects_rhs, current_ship_u8);
+                        let effects_applied: u8 = self.apply_effects(&modules_rhs, &mut effects_lhs,
+                            current_ship_u8, rhs_target, seed);
+                        self.record_shot(&mut stats_rhs, &mut stats_lhs, &ships, current_ship_u8,
+                            rhs_target, rhs_damage, target_hp_before, ship_hps_lhs[rhs_target as usize],
+                            effects_applied);
+
+                        // Log the move, if required
+                        if let Some(ref mut moves) = rhs_moves {
+                            self.log_shoot(round_u8, moves, current_ship_u8, rhs_target, rhs_damage,
+                                ship_positions_rhs[current_ship], &effects_lhs, &effects_rhs);
+                        }
+                    } else {
+                        self.retire_effects(&mut effects_rhs, current_ship_u8);
+
+                        // Move the ships
+                        ship_positions_rhs[current_ship] =
+                            ship_positions_rhs[current_ship].saturating_add(rhs_current_ship_speed as i16);
+
+                        // Log the move, if required
+                        if let Some(ref mut moves) = rhs_moves {
+                            self.log_move(round_u8, moves, current_ship_u8,
+                                ship_positions_rhs[current_ship], &effects_lhs, &effects_rhs);
+                        }
+                    }
+                }
+
+                // Now applying attacker moves
+                if !lhs_dead_ship {
+                    if lhs_has_target {
+                        // Move the ships, apply the damage
+                        let target_hp_before: i32 = ship_hps_rhs[lhs_target as usize];
+                        ship_hps_rhs[lhs_target as usize] =
+                            ship_hps_rhs[lhs_target as usize].saturating_sub(lhs_damage as i32);
+                        ship_positions_lhs[current_ship] =
+                            ship_positions_lhs[current_ship].saturating_sub(lhs_delta_move as i16);
+
+                        self.retire_effects(&mut effects_lhs, current_ship_u8);
+                        let effects_applied: u8 = self.apply_effects(&modules_lhs, &mut effects_rhs,
+                            current_ship_u8, lhs_target, seed);
+                        self.record_shot(&mut stats_lhs, &mut stats_rhs, &ships, current_ship_u8,
+                            lhs_target, lhs_damage, target_hp_before, ship_hps_rhs[lhs_target as usize],
+                            effects_applied);
+
+                        // Log the move, if required
+                        if let Some(ref mut moves) = lhs_moves {
+                            self.log_shoot(round_u8, moves, current_ship_u8, lhs_target, lhs_damage,
+                                ship_positions_lhs[current_ship], &effects_lhs, &effects_rhs);
+                        }
+                    } else {
+                        self.retire_effects(&mut effects_lhs, current_ship_u8);
+
+                        // Move the ships
+                        ship_positions_lhs[current_ship] =
+                            ship_positions_lhs[current_ship].saturating_sub(lhs_current_ship_speed as i16);
+
+                        // Log the move, if required
+                        if let Some(ref mut moves) = lhs_moves {
+                            self.log_move(round_u8, moves, current_ship_u8,
+                                ship_positions_lhs[current_ship], &effects_lhs, &effects_rhs);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Calculate ships lost according to HPs left
+        let mut ships_lost_lhs: [u8; MAX_SHIPS] = [0; MAX_SHIPS];
+        let mut ships_lost_rhs: [u8; MAX_SHIPS] = [0; MAX_SHIPS];
+        for i in 0..MAX_SHIPS {
+            let safe_hp_lhs: u32 = self.max(ship_hps_lhs[i], 0) as u32;
+            let safe_hp_rhs: u32 = self.max(ship_hps_rhs[i], 0) as u32;
+            stats_lhs.hp_remaining[i] = safe_hp_lhs;
+            stats_rhs.hp_remaining[i] = safe_hp_rhs;
+            let hp: u32 = ships[i].hp as u32;
+            ships_lost_lhs[i] = ((selection_lhs[i] as u32 * hp).saturating_sub(safe_hp_lhs) / hp) as u8;
+            ships_lost_rhs[i] = ((selection_rhs[i] as u32 * hp).saturating_sub(safe_hp_rhs) / hp) as u8;
+        }
+
+        let total_rhs_ships: u16 = selection_rhs.iter().map(|amount| *amount as u16).sum();
+
+        let lhs_dead: bool = total_rhs_ships > 0 && self.is_dead(ship_hps_lhs);
+        let rhs_dead: bool = self.is_dead(ship_hps_rhs);
+        let outcome: Outcome = if lhs_dead || rhs_dead {
+            Outcome::from_dead(lhs_dead, rhs_dead)
+        } else {
+            self.break_tie(settings.tie_break,
+                self.get_tie_break_score(settings.tie_break, &ships, selection_lhs, ships_lost_lhs,
+                    stats_lhs.hp_remaining),
+                self.get_tie_break_score(settings.tie_break, &ships, selection_rhs, ships_lost_rhs,
+                    stats_rhs.hp_remaining))
+        };
+
+        let result: FightResult = FightResult {
+            selection_lhs,
+            selection_rhs,
+            modules_lhs,
+            modules_rhs,
+            targeting_lhs,
+            targeting_rhs,
+            lhs_dead,
+            rhs_dead,
+            outcome,
+            settings,
+            ships_lost_lhs,
+            ships_lost_rhs,
+            rounds: total_rounds,
+            seed,
+            rules_version: self.rules_version,
+            ships_version: 0,
+            stats_lhs,
+            stats_rhs,
+        };
+
+        Ok((result, lhs_moves, rhs_moves))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scale::Encode;
+
+    /// A fight, and the outputs it is locked to for a given rules version
+    struct GoldenVector {
+        seed: u64,
+        selection_lhs: [u8; MAX_SHIPS],
+        selection_rhs: [u8; MAX_SHIPS],
+        modules_lhs: [ShipModule; MAX_SHIPS],
+        modules_rhs: [ShipModule; MAX_SHIPS],
+        targeting_lhs: TargetingType,
+        targeting_rhs: TargetingType,
+        lhs_dead: bool,
+        rhs_dead: bool,
+        rounds: u8,
+        ships_lost_lhs: [u8; MAX_SHIPS],
+        ships_lost_rhs: [u8; MAX_SHIPS],
+        moves: usize,
+        moves_checksum: u64,
+    }
+
+    const NO_MODULE: ShipModule = ShipModule { snare: 0, root: 0, blind: 0, attack_debuff: 0,
+        defence_debuff: 0, range_debuff: 0 };
+    const NO_MODULES: [ShipModule; MAX_SHIPS] = [NO_MODULE; MAX_SHIPS];
+    const MODULES_A: [ShipModule; MAX_SHIPS] = [
+        ShipModule { snare: 10, ..NO_MODULE },
+        NO_MODULE,
+        ShipModule { attack_debuff: 5, ..NO_MODULE },
+        NO_MODULE,
+    ];
+    const MODULES_B: [ShipModule; MAX_SHIPS] = [
+        NO_MODULE,
+        ShipModule { root: 3, ..NO_MODULE },
+        ShipModule { blind: 2, ..NO_MODULE },
+        ShipModule { defence_debuff: 4, range_debuff: 6, ..NO_MODULE },
+    ];
+
+    /// Outputs of the version 1 rules, with the default ships (prepare_ships).
+    /// Never edit these, a rules change has to bump RULES_VERSION and add a new set instead.
+    const GOLDEN_VECTORS_V1: [GoldenVector; 9] = [
+        GoldenVector { seed: 1337, selection_lhs: [20, 20, 20, 20], selection_rhs: [5, 5, 5, 5],
+            modules_lhs: NO_MODULES, modules_rhs: NO_MODULES,
+            targeting_lhs: TargetingType::Closest, targeting_rhs: TargetingType::Closest,
+            lhs_dead: false, rhs_dead: true, rounds: 4,
+            ships_lost_lhs: [14, 0, 0, 0], ships_lost_rhs: [5, 5, 5, 5],
+            moves: 25, moves_checksum: 0x41323119cb9ea655 },
+        GoldenVector { seed: 0, selection_lhs: [10, 10, 10, 10], selection_rhs: [10, 10, 10, 10],
+            modules_lhs: NO_MODULES, modules_rhs: NO_MODULES,
+            targeting_lhs: TargetingType::Furthest, targeting_rhs: TargetingType::Furthest,
+            lhs_dead: true, rhs_dead: true, rounds: 31,
+            ships_lost_lhs: [10, 10, 10, 10], ships_lost_rhs: [10, 10, 10, 10],
+            moves: 138, moves_checksum: 0xcfee8801693ad84c },
+        GoldenVector { seed: 42, selection_lhs: [50, 0, 0, 0], selection_rhs: [0, 0, 0, 5],
+            modules_lhs: NO_MODULES, modules_rhs: NO_MODULES,
+            targeting_lhs: TargetingType::HighestHp, targeting_rhs: TargetingType::LowestHp,
+            lhs_dead: false, rhs_dead: true, rounds: 6,
+            ships_lost_lhs: [11, 0, 0, 0], ships_lost_rhs: [0, 0, 0, 5],
+            moves: 11, moves_checksum: 0xf9c7eae7ba735c3c },
+        GoldenVector { seed: 7, selection_lhs: [0, 30, 0, 0], selection_rhs: [0, 0, 20, 0],
+            modules_lhs: NO_MODULES, modules_rhs: NO_MODULES,
+            targeting_lhs: TargetingType::HighestSpeed, targeting_rhs: TargetingType::LowestSpeed,
+            lhs_dead: true, rhs_dead: false, rounds: 8,
+            ships_lost_lhs: [0, 30, 0, 0], ships_lost_rhs: [0, 0, 6, 0],
+            moves: 16, moves_checksum: 0x13aa07f36e5d33ec },
+        GoldenVector { seed: 123456789, selection_lhs: [15, 10, 5, 2], selection_rhs: [15, 10, 5, 2],
+            modules_lhs: MODULES_A, modules_rhs: MODULES_B,
+            targeting_lhs: TargetingType::HighestDefence, targeting_rhs: TargetingType::LowestDefence,
+            lhs_dead: true, rhs_dead: false, rounds: 9,
+            ships_lost_lhs: [15, 10, 5, 2], ships_lost_rhs: [9, 9, 0, 1],
+            moves: 57, moves_checksum: 0x9d949dc6e14405fd },
+        GoldenVector { seed: 99, selection_lhs: [1, 0, 0, 0], selection_rhs: [0, 0, 0, 1],
+            modules_lhs: NO_MODULES, modules_rhs: NO_MODULES,
+            targeting_lhs: TargetingType::HighestAttack, targeting_rhs: TargetingType::LowestAttack,
+            lhs_dead: true, rhs_dead: false, rounds: 2,
+            ships_lost_lhs: [1, 0, 0, 0], ships_lost_rhs: [0, 0, 0, 0],
+            moves: 4, moves_checksum: 0x2fbd167863f6bbdb },
+        GoldenVector { seed: 2021, selection_lhs: [40, 20, 10, 5], selection_rhs: [5, 10, 20, 40],
+            modules_lhs: MODULES_B, modules_rhs: MODULES_A,
+            targeting_lhs: TargetingType::Closest, targeting_rhs: TargetingType::HighestHp,
+            lhs_dead: true, rhs_dead: false, rounds: 6,
+            ships_lost_lhs: [40, 20, 10, 5], ships_lost_rhs: [5, 10, 17, 0],
+            moves: 31, moves_checksum: 0x3710ed1ed892f0ee },
+        GoldenVector { seed: u64::MAX, selection_lhs: [255, 255, 255, 255], selection_rhs: [255, 255, 255, 255],
+            modules_lhs: NO_MODULES, modules_rhs: NO_MODULES,
+            targeting_lhs: TargetingType::Furthest, targeting_rhs: TargetingType::Closest,
+            lhs_dead: false, rhs_dead: true, rounds: 16,
+            ships_lost_lhs: [255, 255, 255, 125], ships_lost_rhs: [255, 255, 255, 255],
+            moves: 82, moves_checksum: 0x1a28778b11daf88a },
+        GoldenVector { seed: 31337, selection_lhs: [10, 10, 10, 10], selection_rhs: [0, 0, 0, 0],
+            modules_lhs: NO_MODULES, modules_rhs: NO_MODULES,
+            targeting_lhs: TargetingType::Closest, targeting_rhs: TargetingType::Closest,
+            lhs_dead: false, rhs_dead: true, rounds: 0,
+            ships_lost_lhs: [0, 0, 0, 0], ships_lost_rhs: [0, 0, 0, 0],
+            moves: 0, moves_checksum: 0x08328807b4eb6fed },
+    ];
+
+    /// FNV-1a hash of the SCALE encoded moves of both sides
+    fn moves_checksum(moves_lhs: &[Move], moves_rhs: &[Move]) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in moves_lhs.encode().iter().chain(moves_rhs.encode().iter()) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
+    #[test]
+    fn test_golden_vectors() {
+        let golden_vectors: [(u16, &[GoldenVector]); 1] = [
+            (1, &GOLDEN_VECTORS_V1),
+        ];
+        assert_eq!(golden_vectors.len(), RULES_VERSION as usize);
+
+        for (rules_version, vectors) in golden_vectors.iter() {
+            let engine = FightEngine::new(*rules_version);
+            for vector in vectors.iter() {
+                let (result, moves_lhs, moves_rhs) = engine.fight(vector.seed, true,
+                    prepare_ships(), vector.selection_lhs, vector.selection_rhs,
+                    vector.modules_lhs, vector.modules_rhs, vector.targeting_lhs,
+                    vector.targeting_rhs, FightSettings::default());
+                let moves_lhs: Vec<Move> = moves_lhs.unwrap();
+                let moves_rhs: Vec<Move> = moves_rhs.unwrap();
+
+                assert_eq!(result.rules_version, *rules_version);
+                assert_eq!(result.lhs_dead, vector.lhs_dead, "seed {}", vector.seed);
+                assert_eq!(result.rhs_dead, vector.rhs_dead, "seed {}", vector.seed);
+                assert_eq!(result.rounds, vector.rounds, "seed {}", vector.seed);
+                assert_eq!(result.ships_lost_lhs, vector.ships_lost_lhs, "seed {}", vector.seed);
+                assert_eq!(result.ships_lost_rhs, vector.ships_lost_rhs, "seed {}", vector.seed);
+                assert_eq!(moves_lhs.len() + moves_rhs.len(), vector.moves, "seed {}", vector.seed);
+                assert_eq!(moves_checksum(&moves_lhs, &moves_rhs), vector.moves_checksum,
+                    "seed {}", vector.seed);
+            }
+        }
+    }
+
+    /// Xorshift generator for the property tests, so that every run checks the same cases
+    struct Random {
+        state: u64,
+    }
+
+    impl Random {
+        fn next(&mut self) -> u64 {
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            self.state
+        }
+
+        /// Edge values are picked often, as that is where the arithmetic breaks
+        fn next_u16(&mut self) -> u16 {
+            match self.next() % 8 {
+                0 => 0,
+                1 | 2 => u16::MAX,
+                3..=5 => (self.next() % 500) as u16,
+                _ => self.next() as u16,
+            }
+        }
+
+        fn next_u8(&mut self) -> u8 {
+            match self.next() % 8 {
+                0 => 0,
+                1 | 2 => u8::MAX,
+                3..=5 => (self.next() % 30) as u8,
+                _ => self.next() as u8,
+            }
+        }
+
+        fn next_ship(&mut self) -> Ship {
+            Ship {
+                cp: self.next_u16(),
+                hp: self.next_u16(),
+                attack_base: self.next_u16(),
+                attack_variable: self.next_u16(),
+                defence: self.next_u16(),
+                speed: self.next_u8(),
+                range: self.next_u8(),
+            }
+        }
+
+        fn next_module(&mut self) -> ShipModule {
+            ShipModule {
+                snare: self.next_u8(),
+                root: self.next_u8(),
+                blind: self.next_u8(),
+                attack_debuff: self.next_u8(),
+                defence_debuff: self.next_u8(),
+                range_debuff: self.next_u8(),
+            }
+        }
+
+        fn next_targeting(&mut self) -> TargetingType {
+            let targeting_types: [TargetingType; 10] = [TargetingType::Furthest,
+                TargetingType::Closest, TargetingType::HighestHp, TargetingType::LowestHp,
+                TargetingType::HighestSpeed, TargetingType::LowestSpeed,
+                TargetingType::HighestDefence, TargetingType::LowestDefence,
+                TargetingType::HighestAttack, TargetingType::LowestAttack];
+            targeting_types[(self.next() % 10) as usize]
+        }
+
+        fn next_settings(&mut self) -> FightSettings {
+            let tie_breaks: [TieBreak; 3] = [TieBreak::None, TieBreak::HpPercentage,
+                TieBreak::CpPercentage];
+            FightSettings {
+                max_rounds: 1 + (self.next() % 60) as u8,
+                tie_break: tie_breaks[(self.next() % 3) as usize],
+                hp_bonus_rhs: 0,
+            }
+        }
+    }
+
+    /// Checks the statistics of one side against the fight result and its logged moves.
+    fn check_stats(ships: &[Ship], selection: [u8; MAX_SHIPS], ships_lost: [u8; MAX_SHIPS],
+        stats: &FleetStats, moves: &[Move], ships_lost_enemy: [u8; MAX_SHIPS],
+        stats_enemy: &FleetStats) {
+
+        let mut shots_fired: [u16; MAX_SHIPS] = [0; MAX_SHIPS];
+        let mut damage_dealt: [u32; MAX_SHIPS] = [0; MAX_SHIPS];
+        for fight_move in moves.iter().filter(|m| m.move_type == MOVE_TYPE_SHOOT) {
+            shots_fired[fight_move.source as usize] += 1;
+            damage_dealt[fight_move.source as usize] += fight_move.damage;
+        }
+        assert_eq!(stats.shots_fired, shots_fired);
+        assert_eq!(stats.damage_dealt, damage_dealt);
+        assert_eq!(stats.damage_dealt.iter().sum::<u32>(),
+            stats_enemy.damage_received.iter().sum::<u32>());
+
+        for i in 0..MAX_SHIPS {
+            let kills: u16 = stats.kills.iter().map(|k| k[i]).sum();
+            assert_eq!(kills, ships_lost_enemy[i] as u16);
+
+            let hp: u32 = ships[i].hp as u32;
+            // u32::div_ceil is newer than the contracts' toolchain
+            #[allow(unknown_lints, clippy::manual_div_ceil)]
+            let ships_left: u32 = (stats.hp_remaining[i] + hp - 1) / hp;
+            assert!(stats.hp_remaining[i] <= selection[i] as u32 * hp);
+            assert_eq!(selection[i] as u32 - ships_left, ships_lost[i] as u32);
+        }
+    }
+
+    #[test]
+    fn test_fight_properties() {
+        let engine = FightEngine::default();
+        let mut random = Random { state: 0x2545f4914f6cdd1d };
+        let mut fights: u32 = 0;
+
+        for _ in 0..5000 {
+            let ships_count: u64 = MAX_SHIPS as u64 - 1 + random.next() % 3;
+            let ships: Vec<Ship> = (0..ships_count).map(|_| random.next_ship()).collect();
+            let mut selection_lhs: [u8; MAX_SHIPS] = [0; MAX_SHIPS];
+            let mut selection_rhs: [u8; MAX_SHIPS] = [0; MAX_SHIPS];
+            let mut modules_lhs: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+            let mut modules_rhs: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+            for i in 0..MAX_SHIPS {
+                selection_lhs[i] = random.next_u8();
+                selection_rhs[i] = random.next_u8();
+                modules_lhs[i] = random.next_module();
+                modules_rhs[i] = random.next_module();
+            }
+            let targeting_lhs: TargetingType = random.next_targeting();
+            let targeting_rhs: TargetingType = random.next_targeting();
+            let seed: u64 = random.next();
+            let settings: FightSettings = random.next_settings();
+
+            let fight = engine.try_fight(seed, true, ships.clone(), selection_lhs, selection_rhs,
+                modules_lhs, modules_rhs, targeting_lhs, targeting_rhs, settings);
+            let (result, moves_lhs, moves_rhs) = match validate_ships(&ships) {
+                Err(error) => {
+                    assert_eq!(fight, Err(error));
+                    continue;
+                },
+                Ok(()) => fight.unwrap(),
+            };
+            fights += 1;
+
+            assert!(result.rounds <= settings.max_rounds);
+            assert_eq!(result.settings, settings);
+            if result.lhs_dead || result.rhs_dead {
+                assert_eq!(result.outcome, Outcome::from_dead(result.lhs_dead, result.rhs_dead));
+            } else {
+                // Only a fight reaching the maximum rounds goes to the tie-break
+                assert_eq!(result.rounds, settings.max_rounds);
+                if settings.tie_break == TieBreak::None {
+                    assert_eq!(result.outcome, Outcome::Draw);
+                }
+            }
+            for i in 0..MAX_SHIPS {
+                assert!(result.ships_lost_lhs[i] <= selection_lhs[i]);
+                assert!(result.ships_lost_rhs[i] <= selection_rhs[i]);
+            }
+            if result.lhs_dead {
+                assert_eq!(result.ships_lost_lhs, selection_lhs);
+            }
+            if result.rhs_dead {
+                assert_eq!(result.ships_lost_rhs, selection_rhs);
+            }
+            let moves_lhs: Vec<Move> = moves_lhs.unwrap();
+            let moves_rhs: Vec<Move> = moves_rhs.unwrap();
+            check_stats(&ships, selection_lhs, result.ships_lost_lhs, &result.stats_lhs, &moves_lhs,
+                result.ships_lost_rhs, &result.stats_rhs);
+            check_stats(&ships, selection_rhs, result.ships_lost_rhs, &result.stats_rhs, &moves_rhs,
+                result.ships_lost_lhs, &result.stats_lhs);
+            for fight_move in moves_lhs.iter().chain(moves_rhs.iter()) {
+                assert!(fight_move.round < result.rounds);
+                assert!((fight_move.source as usize) < MAX_SHIPS);
+                assert!((fight_move.target as usize) < MAX_SHIPS);
+            }
+
+            // Fights are deterministic, whether the moves are logged or not
+            let (replayed, _, _) = engine.fight(seed, false, ships, selection_lhs, selection_rhs,
+                modules_lhs, modules_rhs, targeting_lhs, targeting_rhs, settings);
+            assert_eq!(replayed, result);
+        }
+
+        // Most of the random ships have to be valid, for the invariants to be checked at all
+        assert!(fights > 500);
+    }
+
+    #[test]
+    fn test_fight_stats() {
+        let engine = FightEngine::default();
+        let ships: Vec<Ship> = prepare_ships();
+        let selection_lhs: [u8; MAX_SHIPS] = [20, 20, 20, 20];
+        let selection_rhs: [u8; MAX_SHIPS] = [5, 5, 5, 5];
+        let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+
+        let (result, moves_lhs, moves_rhs) = engine.fight(1337, true, ships.clone(),
+            selection_lhs, selection_rhs, modules, modules, TargetingType::Closest,
+            TargetingType::Closest, FightSettings::default());
+
+        // The defender is wiped out: nothing left, and every ship accounted for by a kill
+        assert!(result.rhs_dead);
+        assert_eq!(result.stats_rhs.hp_remaining, [0; MAX_SHIPS]);
+        let kills: u16 = result.stats_lhs.kills.iter().flatten().sum();
+        assert_eq!(kills, selection_rhs.iter().map(|s| *s as u16).sum::<u16>());
+        // No modules, no effects
+        assert_eq!(result.stats_lhs.effects_applied, [0; MAX_SHIPS]);
+        assert_eq!(result.stats_rhs.effects_applied, [0; MAX_SHIPS]);
+
+        check_stats(&ships, selection_lhs, result.ships_lost_lhs, &result.stats_lhs,
+            &moves_lhs.unwrap(), result.ships_lost_rhs, &result.stats_rhs);
+        check_stats(&ships, selection_rhs, result.ships_lost_rhs, &result.stats_rhs,
+            &moves_rhs.unwrap(), result.ships_lost_lhs, &result.stats_lhs);
+
+        // The statistics are the same, whether the moves are logged or not
+        let (replayed, _, _) = engine.fight(1337, false, ships, selection_lhs, selection_rhs,
+            modules, modules, TargetingType::Closest, TargetingType::Closest,
+            FightSettings::default());
+        assert_eq!(replayed.stats_lhs, result.stats_lhs);
+        assert_eq!(replayed.stats_rhs, result.stats_rhs);
+    }
+
+    #[test]
+    fn test_validate_ships() {
+        let mut ships: Vec<Ship> = prepare_ships();
+        assert_eq!(validate_ships(&ships), Ok(()));
+
+        ships[1].attack_variable = 0;
+        assert_eq!(validate_ships(&ships),
+            Err(FightError::InvalidShip(1, ShipError::ZeroAttackVariable)));
+
+        ships[1] = prepare_ships()[1];
+        ships[2].hp = 0;
+        assert_eq!(validate_ships(&ships), Err(FightError::InvalidShip(2, ShipError::ZeroHp)));
+
+        ships[2] = prepare_ships()[2];
+        ships[3].cp = 0;
+        assert_eq!(validate_ships(&ships),
+            Err(FightError::InvalidShip(3, ShipError::ZeroCommandPower)));
+
+        ships.truncate(MAX_SHIPS - 1);
+        assert_eq!(validate_ships(&ships), Err(FightError::NotEnoughShips));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fight_invalid_ships() {
+        let mut ships: Vec<Ship> = prepare_ships();
+        ships[0].hp = 0;
+        let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+
+        FightEngine::default().fight(1337, false, ships, [1, 1, 1, 1], [1, 1, 1, 1],
+            modules, modules, TargetingType::Closest, TargetingType::Closest,
+            FightSettings::default());
+    }
+
+    #[test]
+    fn test_fight_zero_max_rounds() {
+        let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+        let settings: FightSettings = FightSettings { max_rounds: 0, ..FightSettings::default() };
+
+        let fight = FightEngine::default().try_fight(1337, false, prepare_ships(), [1, 1, 1, 1],
+            [1, 1, 1, 1], modules, modules, TargetingType::Closest, TargetingType::Closest,
+            settings);
+
+        assert_eq!(fight, Err(FightError::ZeroMaxRounds));
+    }
+
+    #[test]
+    fn test_outcome_from_dead() {
+        assert_eq!(Outcome::from_dead(false, true), Outcome::AttackerWin);
+        assert_eq!(Outcome::from_dead(true, false), Outcome::DefenderWin);
+        assert_eq!(Outcome::from_dead(false, false), Outcome::Draw);
+        assert_eq!(Outcome::from_dead(true, true), Outcome::MutualDestruction);
+    }
+
+    #[test]
+    fn test_fight_max_rounds() {
+        let engine = FightEngine::default();
+        let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+        let strong: [u8; MAX_SHIPS] = [20, 20, 20, 20];
+        let weak: [u8; MAX_SHIPS] = [5, 5, 5, 5];
+        let fight = |selection_lhs: [u8; MAX_SHIPS], selection_rhs: [u8; MAX_SHIPS],
+            settings: FightSettings| -> FightResult {
+            engine.fight(1337, false, prepare_ships(), selection_lhs, selection_rhs, modules,
+                modules, TargetingType::Closest, TargetingType::Closest, settings).0
+        };
+
+        // Decided before the maximum rounds, the tie-break does not matter
+        let result: FightResult = fight(strong, weak, FightSettings::default());
+        assert!(result.rhs_dead && result.rounds > 1);
+        assert_eq!(result.outcome, Outcome::AttackerWin);
+
+        // Cut short, both fleets survive
+        let settings: FightSettings = FightSettings { max_rounds: 1, ..FightSettings::default() };
+        let result: FightResult = fight(strong, weak, settings);
+        assert!(!result.lhs_dead && !result.rhs_dead);
+        assert_eq!(result.rounds, 1);
+        assert_eq!(result.outcome, Outcome::Draw);
+
+        // The side with more of its fleet left wins the tie-break, whichever side it is
+        for tie_break in [TieBreak::HpPercentage, TieBreak::CpPercentage].iter() {
+            let settings: FightSettings = FightSettings { max_rounds: 1, tie_break: *tie_break, ..FightSettings::default() };
+            assert_eq!(fight(strong, weak, settings).outcome, Outcome::AttackerWin);
+            assert_eq!(fight(weak, strong, settings).outcome, Outcome::DefenderWin);
+        }
+
+        // Extra Health Points make the defender last longer, and cost the attacker more ships
+        let settings: FightSettings = FightSettings { hp_bonus_rhs: 100, ..FightSettings::default() };
+        let result: FightResult = fight(strong, weak, FightSettings::default());
+        let result_bonus: FightResult = fight(strong, weak, settings);
+        assert_eq!(result_bonus.settings, settings);
+        assert!(result_bonus.rounds > result.rounds);
+        assert!(result_bonus.ships_lost_lhs.iter().sum::<u8>() > result.ships_lost_lhs.iter().sum::<u8>());
+    }
+
+    #[test]
+    fn test_break_tie() {
+        let engine = FightEngine::default();
+
+        assert_eq!(engine.break_tie(TieBreak::None, (100, 100), (0, 100)), Outcome::Draw);
+        assert_eq!(engine.break_tie(TieBreak::HpPercentage, (60, 100), (50, 100)),
+            Outcome::AttackerWin);
+        assert_eq!(engine.break_tie(TieBreak::HpPercentage, (60, 200), (50, 100)),
+            Outcome::DefenderWin);
+        // Percentages, not absolute amounts, are compared
+        assert_eq!(engine.break_tie(TieBreak::CpPercentage, (50, 100), (1, 2)), Outcome::Draw);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unsupported_rules_version() {
+        FightEngine::new(RULES_VERSION + 1);
+    }
+
+    #[test]
+    fn test_fight_end_to_end() {
+        let engine = FightEngine::default();
+        let ships: Vec<Ship> = prepare_ships();
+        let seed: u64 = 1337;
+        let log_moves: bool = true;
+        let selection_lhs: [u8; MAX_SHIPS] = [20, 20, 20, 20];
+        let selection_rhs: [u8; MAX_SHIPS] = [5, 5, 5, 5];
+        let modules_lhs: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+        let modules_rhs: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+        let targeting_lhs: TargetingType = TargetingType::Closest;
+        let targeting_rhs: TargetingType = TargetingType::Closest;
+
+        let (result, _moves_lhs, _moves_rhs) = engine.fight(seed, log_moves, ships,
+            selection_lhs, selection_rhs, modules_lhs, modules_rhs, targeting_lhs,
+            targeting_rhs, FightSettings::default());
+
+        assert!(result.rhs_dead);
+    }
+
+    #[test]
+    fn test_damage_calculation() {
+        let engine = FightEngine::default();
+        let ships: Vec<Ship> = prepare_ships();
+        let effects_source: [RunningEffect; MAX_SHIPS] = [RunningEffect::default(); MAX_SHIPS];
+        let effects_target: [RunningEffect; MAX_SHIPS] = [RunningEffect::default(); MAX_SHIPS];
+        let variables: [u16; MAX_SHIPS] = [0, 1, 2, 3];
+        let source: u8 = 0;
+        let target: u8 = 0;
+        let source_hp: u32 = ships[source as usize].hp as u32;
+        let damage: u32 = engine.calculate_damage(variables, &effects_source,
+            &effects_target, &ships, source, target, source_hp, 0);
+
+        let source_hp_damaged: u32 = source_hp - 1;
+        let damage_damaged: u32 = engine.calculate_damage(variables, &effects_source,
+            &effects_target, &ships, source, target, source_hp_damaged, 0);
+
+        let source_hp_bigstack: u32 = source_hp * 32;
+        let damage_bigstack: u32 = engine.calculate_damage(variables, &effects_source,
+            &effects_source, &ships, source, target, source_hp_bigstack, 0);
+
+        // Doubled Health Points of the target halve the damage it takes
+        let damage_hp_bonus: u32 = engine.calculate_damage(variables, &effects_source,
+            &effects_target, &ships, source, target, source_hp_bigstack, 100);
+
+        assert_eq!(damage, 60);
+        assert_eq!(damage_damaged, 60);
+        assert_eq!(damage_bigstack, 60 * 32);
+        assert_eq!(damage_hp_bonus, 30 * 32);
+    }
+
+    #[test]
+    fn test_is_stat_better_for_targeting() {
+        let engine = FightEngine::default();
+
+        assert!(engine.is_stat_better_for_targeting(
+            TargetingType::Closest, 5, 7));
+        assert!(engine.is_stat_better_for_targeting(
+            TargetingType::Furthest, 7, 5));
+        assert!(engine.is_stat_better_for_targeting(
+            TargetingType::HighestHp, 7, 5));
+        assert!(engine.is_stat_better_for_targeting(
+            TargetingType::LowestSpeed, 2, 5));
+    }
+
+    #[test]
+    fn test_get_stat_for_targeting() {
+        let engine = FightEngine::default();
+        let ships: Vec<Ship> = prepare_ships();
+        let effects_target: [RunningEffect; MAX_SHIPS] = [RunningEffect::default(); MAX_SHIPS];
+
+        let delta: u8 = 11;
+        assert_eq!(engine.get_stat_for_targeting(
+            TargetingType::Closest, 0, delta, &ships, &effects_target), delta as u16);
+        assert_eq!(engine.get_stat_for_targeting(
+            TargetingType::Furthest, 0, delta, &ships, &effects_target), delta as u16);
+        assert_eq!(engine.get_stat_for_targeting(
+            TargetingType::HighestHp, 0, delta, &ships, &effects_target), ships[0].hp);
+        assert_eq!(engine.get_stat_for_targeting(
+            TargetingType::LowestSpeed, 0, delta, &ships, &effects_target), ships[0].speed as u16);
+    }
+
+    #[test]
+    fn test_get_target() {
+        let engine = FightEngine::default();
+        let ships: Vec<Ship> = prepare_ships();
+        let effects_source: [RunningEffect; MAX_SHIPS] = [RunningEffect::default(); MAX_SHIPS];
+        let effects_target: [RunningEffect; MAX_SHIPS] = [RunningEffect::default(); MAX_SHIPS];
+        let ship_positions_lhs: [i16; MAX_SHIPS] = [10, 11, 12, 13];
+        let ship_positions_rhs: [i16; MAX_SHIPS] = [-10, -11, -12, -13];
+        let ship_hps_enemy: [i32; MAX_SHIPS] = [1000, 1000, 1000, 1000];
+        let current_ship: u8 = MAX_SHIPS as u8 - 1;
+
+        let (has_target, target, _delta_move): (bool, u8, u8) = engine.get_target(&ships,
+            current_ship, ship_positions_lhs, ship_positions_rhs,
+            ship_hps_enemy, &effects_source, &effects_target, TargetingType::Closest);
+
+        assert!(has_target);
+        assert_eq!(target, 0);
+
+        let (has_target, target, _delta_move): (bool, u8, u8) = engine.get_target(&ships,
+            current_ship, ship_positions_lhs, ship_positions_rhs,
+            ship_hps_enemy, &effects_source, &effects_target, TargetingType::Furthest);
+
+        assert!(has_target);
+        assert_eq!(target, current_ship);
+    }
+
+    #[test]
+    fn test_isdead() {
+        let engine = FightEngine::default();
+        let alive_ship_hps: [i32; MAX_SHIPS] = [20, -20, 0, 0];
+        let is_dead_first: bool = engine.is_dead(alive_ship_hps);
+
+        assert!(!is_dead_first);
+
+        let dead_ship_hps: [i32; MAX_SHIPS] = [-100, -20, 0, 0];
+        let is_dead_second: bool = engine.is_dead(dead_ship_hps);
+
+        assert!(is_dead_second);
+    }
+}
@@ -1,53 +1,224 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+// The ink! constructors take the place of Default, and the messages take the full ship and
+// fight definitions (the generated call forwarders do not carry an item level allow)
+#![allow(clippy::new_without_default, clippy::too_many_arguments)]
+// Built as a dependency, ink! leaves out the contract implementation and what it uses
+#![cfg_attr(feature = "ink-as-dependency", allow(unused_imports, dead_code))]
 
 use ink_lang as ink;
 pub use self::newomegagame::NewOmegaGame;
+pub use self::newomegagame::ShipClass;
+pub use self::newomegagame::ShipDetails;
+pub use self::newomegagame::ShipCatalogueEntry;
 
 /// Wraps the Game Engine with a bit of storage, which contains the definition of
 /// ships (their statistics). The allows for separation of the Engine logic from ships,
 /// which is useful because it allows the Engine to remain pure.
+/// The Engine (newomegaengine) is linked in directly, so fights do not need a cross-contract call.
+/// Every version of the ship table is kept, so that older fights can be replayed with the
+/// ships (and the fight rules) they were fought with.
+#[newomegaaccess::access_control(NewOmegaGame)]
 #[ink::contract]
 mod newomegagame {
-    use newomega::NewOmega;
-    use newomega::Ship;
-    use newomega::MAX_SHIPS;
-    use newomega::FightResult;
-    use newomega::Move;
-    use newomega::ShipModule;
-    use newomega::TargetingType;
+    use newomegaaccess::AccessControl;
+    use newomegaaccess::Role;
+    use newomegaengine::FightEngine;
+    use newomegaengine::Ship;
+    use newomegaengine::MAX_SHIPS;
+    use newomegaengine::FightResult;
+    use newomegaengine::FightSettings;
+    use newomegaengine::Move;
+    use newomegaengine::ShipModule;
+    use newomegaengine::TargetingType;
+    use newomegaengine::RULES_VERSION;
+    use newomegalayout::Packed;
     use ink_prelude::vec::Vec;
+    use ink_prelude::string::String;
+    use ink_storage::{
+        collections::{
+            HashMap as StorageHashMap,
+        },
+        traits::{
+            PackedLayout,
+            SpreadLayout,
+        },
+    };
+
+    /// Maximum number of ships in the ship table, ship identifiers being a u8
+    /// (fleets only select from the first MAX_SHIPS of them)
+    pub const MAX_SHIP_TYPES: usize = u8::MAX as usize + 1;
+
+    /// Role of a ship type in the fleet
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Copy, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub enum ShipClass {
+        Fighter,
+        Frigate,
+        Cruiser,
+        Capital,
+    }
+
+    /// Describes a ship type, in addition to its statistics (which are kept in the ship table)
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct ShipDetails {
+        /// Name of the ship type
+        pub name: String,
+        /// Class of the ship type
+        pub class: ShipClass,
+        /// Identifier of the ship icon, in the client assets
+        pub icon_id: u16,
+        /// Retired ships can no longer be produced or selected, but stay in the ship table for replays
+        pub retired: bool,
+    }
+
+    /// A ship type, as listed in the ship catalogue
+    #[derive(scale::Encode, scale::Decode, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo
+        )
+    )]
+    pub struct ShipCatalogueEntry {
+        /// Ship identifier (index in the ship table)
+        pub ship_id: u8,
+        /// Current statistics of the ship
+        pub ship: Ship,
+        /// Name, class, icon and retirement of the ship
+        pub details: ShipDetails,
+    }
+
+    impl ShipDetails {
+        /// Gets the details of the default ships (see prepare_ships)
+        ///
+        /// # Returns
+        ///
+        /// * `details` - A Vec of the details, in the order of the default ships
+        pub fn defaults() -> Vec<ShipDetails> {
+            ink_prelude::vec![
+                ShipDetails {
+                    name: String::from("Stinger"),
+                    class: ShipClass::Fighter,
+                    icon_id: 0,
+                    retired: false,
+                },
+                ShipDetails {
+                    name: String::from("Icarus"),
+                    class: ShipClass::Frigate,
+                    icon_id: 1,
+                    retired: false,
+                },
+                ShipDetails {
+                    name: String::from("Scorpio"),
+                    class: ShipClass::Cruiser,
+                    icon_id: 2,
+                    retired: false,
+                },
+                ShipDetails {
+                    name: String::from("Hyperion"),
+                    class: ShipClass::Capital,
+                    icon_id: 3,
+                    retired: false,
+                },
+            ]
+        }
+    }
 
     #[ink(storage)]
     pub struct NewOmegaGame {
-        creator: AccountId,
-        owner: Option<AccountId>,
-        new_omega: NewOmega,
-        ships: Vec<Ship>,
+        access: AccessControl,
+        /// The current ship table
+        ships: Packed<Vec<Ship>>,
+        /// Current version of the ship table
+        ships_version: u16,
+        /// The previous versions of the ship table, each in its own storage cell,
+        /// so only the table a fight is replayed with gets loaded
+        ship_tables: StorageHashMap<u16, Packed<Vec<Ship>>>,
+        /// Details of the ships in the current ship table
+        ship_details: Vec<ShipDetails>,
+    }
+
+    /// Emitted when a ship definition is added
+    #[ink(event)]
+    pub struct ShipAdded {
+        ship_id: u8,
+        ship: Ship,
+        ships_version: u16,
+    }
+
+    /// Emitted when the statistics of a ship are updated
+    #[ink(event)]
+    pub struct ShipUpdated {
+        ship_id: u8,
+        ship: Ship,
+        ships_version: u16,
+    }
+
+    /// Emitted when a ship is retired
+    #[ink(event)]
+    pub struct ShipRetired {
+        ship_id: u8,
+    }
+
+    /// Emitted when the details of a ship are updated
+    #[ink(event)]
+    pub struct ShipDetailsUpdated {
+        ship_id: u8,
+        details: ShipDetails,
     }
 
     impl NewOmegaGame {
         #[ink(constructor)]
-        pub fn new(new_omega: NewOmega) -> Self {
+        pub fn new() -> Self {
             Self {
-                creator: Self::env().caller(),
-                owner: None,
-                new_omega,
-                ships: newomega::prepare_ships(),
+                access: AccessControl::new(Self::env().caller()),
+                ships: Packed::new(newomegaengine::prepare_ships()),
+                ships_version: 0,
+                ship_tables: StorageHashMap::default(),
+                ship_details: ShipDetails::defaults(),
             }
         }
 
-        /// Authorises the Delegator contract to fire methods on this one.
+        #[ink(constructor)]
+        pub fn default() -> Self {
+            Self::new()
+        }
+
+        /// Authorises the Delegator contract to fire methods on this one
+        /// (grants it the Delegator role).
         ///
         /// # Arguments
         ///
         /// * `delegator_address` - AccountId of the Delegator contract
         #[ink(message)]
         pub fn authorise_delegator(&mut self, delegator_address: AccountId) {
-            assert_eq!(self.env().caller(), self.creator);
-            self.owner = Some(delegator_address);
+            self.grant_role(Role::Delegator, delegator_address);
         }
 
-        /// Adds ship to the ship definitions
+        /// Adds ship to the ship definitions, as a new version of the ship table.
+        /// The ship definition has to be valid (see Ship::validate).
         ///
         /// # Arguments
         ///
@@ -58,12 +229,20 @@ mod newomegagame {
         /// * `defence` - Ship Defence
         /// * `speed` - Ship Speed
         /// * `range` - Ship Range
+        /// * `name` - Ship name
+        /// * `class` - Ship class
+        /// * `icon_id` - Identifier of the ship icon
+        ///
+        /// # Events
+        ///
+        /// * ShipAdded - when the ship is added
         #[ink(message)]
         pub fn add_ship(&mut self, cp: u16, hp: u16, attack_base: u16, attack_variable: u16,
-            defence: u16, speed: u8, range: u8) {
+            defence: u16, speed: u8, range: u8, name: String, class: ShipClass, icon_id: u16) {
 
-            assert_eq!(self.env().caller(), self.owner.unwrap());
-            self.ships.push(Ship {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            let mut ships: Vec<Ship> = self.get_ships();
+            let ship: Ship = Ship {
                 cp,
                 hp,
                 attack_base,
@@ -71,9 +250,158 @@ mod newomegagame {
                 defence,
                 speed,
                 range,
+            };
+            assert!(ship.validate().is_ok(), "Invalid ship definition");
+            assert!(ships.len() < MAX_SHIP_TYPES, "Ship table is full");
+            let ship_id: u8 = ships.len() as u8;
+            ships.push(ship);
+            self.set_ships(ships);
+            self.ship_details.push(ShipDetails {
+                name,
+                class,
+                icon_id,
+                retired: false,
+            });
+
+            self.env().emit_event(ShipAdded {
+                ship_id,
+                ship,
+                ships_version: self.get_ships_version(),
             });
         }
 
+        /// Updates the statistics of a ship, as a new version of the ship table
+        /// (older fights are still replayed with the previous statistics).
+        /// The ship definition has to be valid (see Ship::validate).
+        ///
+        /// # Arguments
+        ///
+        /// * `ship_id` - Ship identifier
+        /// * `cp` - Ship Command Power
+        /// * `hp` - Ship Health Points
+        /// * `attack_base` - Base attack
+        /// * `attack_variable` - Variable attack (subject to random)
+        /// * `defence` - Ship Defence
+        /// * `speed` - Ship Speed
+        /// * `range` - Ship Range
+        ///
+        /// # Events
+        ///
+        /// * ShipUpdated - when the ship is updated
+        #[ink(message)]
+        pub fn update_ship(&mut self, ship_id: u8, cp: u16, hp: u16, attack_base: u16,
+            attack_variable: u16, defence: u16, speed: u8, range: u8) {
+
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            let mut ships: Vec<Ship> = self.get_ships();
+            assert!((ship_id as usize) < ships.len(), "Unknown ship");
+
+            let ship: Ship = Ship {
+                cp,
+                hp,
+                attack_base,
+                attack_variable,
+                defence,
+                speed,
+                range,
+            };
+            assert!(ship.validate().is_ok(), "Invalid ship definition");
+            ships[ship_id as usize] = ship;
+            self.set_ships(ships);
+
+            self.env().emit_event(ShipUpdated {
+                ship_id,
+                ship,
+                ships_version: self.get_ships_version(),
+            });
+        }
+
+        /// Retires a ship, so it can no longer be produced or selected for new fleets.
+        /// The ship stays in the ship table, so fights with it can still be replayed.
+        ///
+        /// # Arguments
+        ///
+        /// * `ship_id` - Ship identifier
+        ///
+        /// # Events
+        ///
+        /// * ShipRetired - when the ship is retired
+        #[ink(message)]
+        pub fn retire_ship(&mut self, ship_id: u8) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            assert!((ship_id as usize) < self.ship_details.len(), "Unknown ship");
+
+            self.ship_details[ship_id as usize].retired = true;
+
+            self.env().emit_event(ShipRetired {
+                ship_id,
+            });
+        }
+
+        /// Sets the name, class and icon of a ship.
+        ///
+        /// # Arguments
+        ///
+        /// * `ship_id` - Ship identifier
+        /// * `name` - Ship name
+        /// * `class` - Ship class
+        /// * `icon_id` - Identifier of the ship icon
+        ///
+        /// # Events
+        ///
+        /// * ShipDetailsUpdated - when the details are updated
+        #[ink(message)]
+        pub fn set_ship_details(&mut self, ship_id: u8, name: String, class: ShipClass,
+            icon_id: u16) {
+
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            assert!((ship_id as usize) < self.ship_details.len(), "Unknown ship");
+
+            let details: &mut ShipDetails = &mut self.ship_details[ship_id as usize];
+            details.name = name;
+            details.class = class;
+            details.icon_id = icon_id;
+            let details: ShipDetails = details.clone();
+
+            self.env().emit_event(ShipDetailsUpdated {
+                ship_id,
+                details,
+            });
+        }
+
+        /// Returns the ship catalogue: the current statistics and the details of every ship.
+        ///
+        /// # Returns
+        ///
+        /// * `catalogue` - A Vector containing the catalogue entries, by ship identifier
+        #[ink(message)]
+        pub fn get_ship_catalogue(&self) -> Vec<ShipCatalogueEntry> {
+            self.get_ships()
+                .iter()
+                .zip(self.ship_details.iter())
+                .enumerate()
+                .map(|(ship_id, (ship, details))| ShipCatalogueEntry {
+                    ship_id: ship_id as u8,
+                    ship: *ship,
+                    details: details.clone(),
+                })
+                .collect()
+        }
+
+        /// Checks whether a fleet selection only uses ships which are not retired.
+        ///
+        /// # Arguments
+        ///
+        /// * `selection` - Fleet composition (array with ship quantities)
+        ///
+        /// # Returns
+        ///
+        /// * `is_active` - Whether no retired ship is selected
+        #[ink(message)]
+        pub fn is_selection_active(&self, selection: [u8; MAX_SHIPS]) -> bool {
+            (0..MAX_SHIPS).all(|i| selection[i] == 0 || !self.ship_details[i].retired)
+        }
+
         /// Returns all the registered ships
         ///
         /// # Returns
@@ -81,10 +409,51 @@ mod newomegagame {
         /// * `ships` - A Vector containing the registered ships
         #[ink(message)]
         pub fn get_ships(&self) -> Vec<Ship> {
-            self.ships.clone()
+            self.ships.to_vec()
+        }
+
+        /// Returns a given version of the ship table
+        ///
+        /// # Arguments
+        ///
+        /// * `ships_version` - Version of the ship table
+        ///
+        /// # Returns
+        ///
+        /// * `ships` - A Vector containing the ships, as they were in that version
+        #[ink(message)]
+        pub fn get_ships_at(&self, ships_version: u16) -> Vec<Ship> {
+            if ships_version == self.ships_version {
+                return self.get_ships();
+            }
+
+            self.ship_tables.get(&ships_version)
+                .expect("Unknown ships version")
+                .to_vec()
+        }
+
+        /// Gets the current version of the ship table (bumped by every ship table change)
+        ///
+        /// # Returns
+        ///
+        /// * `ships_version` - Current version of the ship table
+        #[ink(message)]
+        pub fn get_ships_version(&self) -> u16 {
+            self.ships_version
+        }
+
+        /// Gets the current version of the fight rules (of the linked Engine)
+        ///
+        /// # Returns
+        ///
+        /// * `rules_version` - Current version of the fight rules
+        #[ink(message)]
+        pub fn get_rules_version(&self) -> u16 {
+            RULES_VERSION
         }
 
-        /// Calculates a fight, using registered ships.
+        /// Calculates a fight, using registered ships and the current fight rules.
+        /// The result is stamped with the versions of both, so it can be replayed later.
         ///
         /// # Arguments
         ///
@@ -94,6 +463,9 @@ mod newomegagame {
         /// * `selection_rhs` - Defender fleet composition (array with ship quantities)
         /// * `modules_lhs` - An array that holds modules of the attacker fleet
         /// * `modules_rhs` - An array that holds modules of the defender fleet
+        /// * `targeting_lhs` - Attacker targeting
+        /// * `targeting_rhs` - Defender targeting
+        /// * `settings` - Maximum number of rounds, and the tie-break (set by the game mode)
         ///
         /// # Returns
         ///
@@ -104,12 +476,231 @@ mod newomegagame {
         pub fn fight(&self, seed: u64, log_moves: bool, selection_lhs: [u8; MAX_SHIPS],
             selection_rhs: [u8; MAX_SHIPS], modules_lhs: [ShipModule; MAX_SHIPS],
             modules_rhs: [ShipModule; MAX_SHIPS], targeting_lhs: TargetingType,
-            targeting_rhs: TargetingType) -> (FightResult, Option<Vec<Move>>,
-                Option<Vec<Move>>) {
+            targeting_rhs: TargetingType, settings: FightSettings) -> (FightResult,
+                Option<Vec<Move>>, Option<Vec<Move>>) {
 
-            self.new_omega.fight(seed, log_moves, self.get_ships(),
+            self.replay(seed, log_moves, RULES_VERSION, self.get_ships_version(),
                 selection_lhs, selection_rhs, modules_lhs, modules_rhs,
-                targeting_lhs, targeting_rhs)
+                targeting_lhs, targeting_rhs, settings)
+        }
+
+        /// Recalculates a fight, using given versions of the fight rules and the ship table,
+        /// as stamped in the FightResult of the original fight.
+        ///
+        /// # Arguments
+        ///
+        /// * `seed` - Seed used to generate randomness
+        /// * `log_moves` - Whether to return a detailed fight log
+        /// * `rules_version` - Version of the fight rules
+        /// * `ships_version` - Version of the ship table
+        /// * `selection_lhs` - Attacker fleet composition (array with ship quantities)
+        /// * `selection_rhs` - Defender fleet composition (array with ship quantities)
+        /// * `modules_lhs` - An array that holds modules of the attacker fleet
+        /// * `modules_rhs` - An array that holds modules of the defender fleet
+        /// * `targeting_lhs` - Attacker targeting
+        /// * `targeting_rhs` - Defender targeting
+        /// * `settings` - Maximum number of rounds, and the tie-break (as in the FightResult)
+        ///
+        /// # Returns
+        ///
+        /// * `result` - A FightResult structure containing the result
+        /// * `moves_lhs` - Logged moves of the attacker, if requested. None if not.
+        /// * `moves_rhs` - Logged moves of the defender, if requested. None if not.
+        #[ink(message)]
+        pub fn replay(&self, seed: u64, log_moves: bool, rules_version: u16, ships_version: u16,
+            selection_lhs: [u8; MAX_SHIPS], selection_rhs: [u8; MAX_SHIPS],
+            modules_lhs: [ShipModule; MAX_SHIPS], modules_rhs: [ShipModule; MAX_SHIPS],
+            targeting_lhs: TargetingType, targeting_rhs: TargetingType,
+            settings: FightSettings) -> (FightResult, Option<Vec<Move>>, Option<Vec<Move>>) {
+
+            let (mut result, moves_lhs, moves_rhs) = FightEngine::new(rules_version).fight(
+                seed, log_moves, self.get_ships_at(ships_version), selection_lhs,
+                selection_rhs, modules_lhs, modules_rhs, targeting_lhs, targeting_rhs, settings);
+            result.ships_version = ships_version;
+
+            (result, moves_lhs, moves_rhs)
+        }
+
+        /// Replaces the current ship table, keeping the replaced one as the previous version
+        ///
+        /// # Arguments
+        ///
+        /// * `ships` - The new ship table
+        fn set_ships(&mut self, ships: Vec<Ship>) {
+            let previous: Packed<Vec<Ship>> = core::mem::replace(&mut self.ships, Packed::new(ships));
+            self.ship_tables.insert(self.ships_version, previous);
+            self.ships_version = self.ships_version.checked_add(1)
+                .expect("Ship table version overflow");
+        }
+    }
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_lang as ink;
+        use newomegaengine::Outcome;
+        use newomegaengine::TieBreak;
+
+        #[ink::test]
+        fn test_fight_stamps_versions() {
+            let contract = NewOmegaGame::new();
+            let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+
+            let (result, _, _) = contract.fight(1337, false, [20, 20, 20, 20], [5, 5, 5, 5],
+                modules, modules, TargetingType::Closest, TargetingType::Closest,
+                FightSettings::default());
+
+            assert_eq!(result.rules_version, RULES_VERSION);
+            assert_eq!(result.ships_version, 0);
+        }
+
+        #[ink::test]
+        fn test_fight_settings() {
+            let contract = NewOmegaGame::new();
+            let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+            let settings: FightSettings = FightSettings {
+                max_rounds: 1,
+                tie_break: TieBreak::HpPercentage,
+                hp_bonus_rhs: 0,
+            };
+
+            let (original, _, _) = contract.fight(1337, false, [20, 20, 20, 20], [5, 5, 5, 5],
+                modules, modules, TargetingType::Closest, TargetingType::Closest, settings);
+            let (replayed, _, _) = contract.replay(original.seed, false, original.rules_version,
+                original.ships_version, original.selection_lhs, original.selection_rhs,
+                original.modules_lhs, original.modules_rhs, original.targeting_lhs,
+                original.targeting_rhs, original.settings);
+
+            assert!(!original.lhs_dead && !original.rhs_dead);
+            assert_eq!(original.outcome, Outcome::AttackerWin);
+            assert_eq!(original.settings, settings);
+            assert_eq!(replayed, original);
+        }
+
+        #[ink::test]
+        fn test_add_ship_keeps_history() {
+            let mut contract = NewOmegaGame::new();
+            let ships: Vec<Ship> = contract.get_ships();
+
+            contract.add_ship(5, 300, 70, 10, 30, 2, 10, String::from("Nova"),
+                ShipClass::Cruiser, 4);
+
+            assert_eq!(contract.get_ships_version(), 1);
+            assert_eq!(contract.get_ships().len(), ships.len() + 1);
+            assert_eq!(contract.get_ships_at(0), ships);
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "Ship table is full")]
+        fn test_add_ship_table_full() {
+            let mut contract = NewOmegaGame::new();
+
+            for _ in contract.get_ships().len()..=MAX_SHIP_TYPES {
+                contract.add_ship(5, 300, 70, 10, 30, 2, 10, String::from("Nova"),
+                    ShipClass::Cruiser, 4);
+            }
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "Ship table version overflow")]
+        fn test_ships_version_overflow() {
+            let mut contract = NewOmegaGame::new();
+            contract.ships_version = u16::MAX;
+
+            contract.add_ship(5, 300, 70, 10, 30, 2, 10, String::from("Nova"),
+                ShipClass::Cruiser, 4);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_add_ship_invalid() {
+            let mut contract = NewOmegaGame::new();
+
+            contract.add_ship(5, 300, 70, 0, 30, 2, 10, String::from("Nova"),
+                ShipClass::Cruiser, 4);
+        }
+
+        #[ink::test]
+        fn test_replay_old_version() {
+            let mut contract = NewOmegaGame::new();
+            let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+            let (original, moves_lhs, _) = contract.fight(1337, true, [20, 20, 20, 20],
+                [5, 5, 5, 5], modules, modules, TargetingType::Closest, TargetingType::Closest,
+                FightSettings::default());
+
+            contract.update_ship(0, 1, 100, 100, 20, 20, 4, 4);
+            let (replayed, replayed_moves_lhs, _) = contract.replay(original.seed, true,
+                original.rules_version, original.ships_version, original.selection_lhs,
+                original.selection_rhs, original.modules_lhs, original.modules_rhs,
+                original.targeting_lhs, original.targeting_rhs, original.settings);
+
+            assert_eq!(replayed, original);
+            assert_eq!(replayed_moves_lhs, moves_lhs);
+        }
+
+        #[ink::test]
+        fn test_update_ship() {
+            let mut contract = NewOmegaGame::new();
+            let ships: Vec<Ship> = contract.get_ships();
+
+            contract.update_ship(1, 3, 160, 65, 20, 30, 3, 8);
+
+            assert_eq!(contract.get_ships_version(), 1);
+            assert_eq!(contract.get_ships()[1].hp, 160);
+            assert_eq!(contract.get_ships_at(0), ships);
+        }
+
+        #[ink::test]
+        fn test_ship_tables_history() {
+            let mut contract = NewOmegaGame::new();
+            let ships_first: Vec<Ship> = contract.get_ships();
+
+            contract.update_ship(1, 3, 160, 65, 20, 30, 3, 8);
+            let ships_second: Vec<Ship> = contract.get_ships();
+            contract.update_ship(2, 4, 240, 65, 20, 35, 2, 15);
+
+            assert_eq!(contract.get_ships_version(), 2);
+            assert_eq!(contract.get_ships_at(0), ships_first);
+            assert_eq!(contract.get_ships_at(1), ships_second);
+            assert_eq!(contract.get_ships_at(2), contract.get_ships());
+            assert_eq!(contract.get_ships_at(2)[2].hp, 240);
+        }
+
+        #[ink::test]
+        fn test_retire_ship() {
+            let mut contract = NewOmegaGame::new();
+
+            contract.retire_ship(2);
+
+            assert!(contract.is_selection_active([10, 10, 0, 10]));
+            assert!(!contract.is_selection_active([10, 10, 1, 10]));
+            assert!(contract.get_ship_catalogue()[2].details.retired);
+            assert_eq!(contract.get_ships().len(), MAX_SHIPS);
+        }
+
+        #[ink::test]
+        fn test_ship_catalogue() {
+            let mut contract = NewOmegaGame::new();
+
+            contract.set_ship_details(3, String::from("Leviathan"), ShipClass::Capital, 7);
+            let catalogue: Vec<ShipCatalogueEntry> = contract.get_ship_catalogue();
+
+            assert_eq!(catalogue.len(), MAX_SHIPS);
+            assert_eq!(catalogue[0].details.name, String::from("Stinger"));
+            assert_eq!(catalogue[3].ship_id, 3);
+            assert_eq!(catalogue[3].ship, contract.get_ships()[3]);
+            assert_eq!(catalogue[3].details.name, String::from("Leviathan"));
+            assert_eq!(catalogue[3].details.icon_id, 7);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_replay_unknown_version() {
+            let contract = NewOmegaGame::new();
+            let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+
+            contract.replay(1337, false, RULES_VERSION, 1, [1, 1, 1, 1], [1, 1, 1, 1],
+                modules, modules, TargetingType::Closest, TargetingType::Closest,
+                FightSettings::default());
         }
     }
 }
@@ -1,62 +1,95 @@
 #![feature(destructuring_assignment)]
-#![feature(map_into_keys_values)]
 #![cfg_attr(not(feature = "std"), no_std)]
+// The ink! constructors take the place of Default
+#![allow(clippy::new_without_default)]
+// Built as a dependency, ink! leaves out the contract implementation and what it uses
+#![cfg_attr(feature = "ink-as-dependency", allow(unused_imports, dead_code))]
 
 use ink_lang as ink;
 pub use self::newomegaindustrial::NewOmegaIndustrial;
 
+#[newomegaaccess::access_control(NewOmegaIndustrial)]
 #[ink::contract]
 mod newomegaindustrial {
+    use newomegaaccess::AccessControl;
+    use newomegaaccess::Role;
     use newomega::MAX_SHIPS;
-    use newomega::Ship;
-    use newomegastorage::NewOmegaStorage;
+    use newomegagame::ShipCatalogueEntry;
     use newomegastorage::MAX_MINERALS;
+    use newomegaparameters::GameParameters;
+    #[cfg(not(test))]
+    use newomegastorage::NewOmegaStorage;
+    #[cfg(not(test))]
     use newomegagame::NewOmegaGame;
-    use ink_prelude::vec::Vec;
-    use ink_prelude::string::String;
-    use ink_storage::{
-        collections::{
-            HashMap as StorageHashMap,
-        },
-        traits::{
-            PackedLayout,
-            SpreadLayout,
-        },
+    #[cfg(not(test))]
+    use newomegaparameters::NewOmegaParameters;
+    // Off-chain tests run against the in-memory Game, Storage and Parameters
+    #[cfg(test)]
+    use newomegainterfaces::{
+        GameInterface,
+        InMemoryGame as NewOmegaGame,
+        InMemoryParameters as NewOmegaParameters,
+        InMemoryStorage as NewOmegaStorage,
+        ParametersInterface,
+        StorageInterface,
     };
-
-    pub const SHIP_COST_PER_CP: u32 = 10;
+    use ink_prelude::vec::Vec;
 
     #[ink(storage)]
     pub struct NewOmegaIndustrial {
-        creator: AccountId,
-        owner: Option<AccountId>,
-        new_omega_game: Option<newomegagame::NewOmegaGame>,
-        new_omega_storage: Option<newomegastorage::NewOmegaStorage>,
+        access: AccessControl,
+        new_omega_game: Option<NewOmegaGame>,
+        new_omega_storage: Option<NewOmegaStorage>,
+        new_omega_parameters: Option<NewOmegaParameters>,
+    }
+
+    /// Emitted when ships are produced for a player
+    #[ink(event)]
+    pub struct ShipsProduced {
+        #[ink(topic)]
+        player: AccountId,
+        ship_id: u8,
+        amount: u32,
+        cost: u32,
     }
 
     impl NewOmegaIndustrial {
         #[ink(constructor)]
-        pub fn new(new_omega_game: NewOmegaGame, new_omega_storage: NewOmegaStorage) -> Self {
+        pub fn new(new_omega_game: NewOmegaGame, new_omega_storage: NewOmegaStorage,
+            new_omega_parameters: NewOmegaParameters) -> Self {
             Self {
-                creator: Self::env().caller(),
-                owner: None,
+                access: AccessControl::new(Self::env().caller()),
                 new_omega_game: Some(new_omega_game),
                 new_omega_storage: Some(new_omega_storage),
+                new_omega_parameters: Some(new_omega_parameters),
             }
         }
 
-        /// Authorises the Delegator contract to fire methods on this one.
+        /// Authorises the Delegator contract to fire methods on this one
+        /// (grants it the Delegator role).
         ///
         /// # Arguments
         ///
         /// * `delegator_address` - AccountId of the Delegator contract
         #[ink(message)]
         pub fn authorise_delegator(&mut self, delegator_address: AccountId) {
-            assert_eq!(self.env().caller(), self.creator);
-            self.owner = Some(delegator_address);
+            self.grant_role(Role::Delegator, delegator_address);
         }
 
-        /// Produces a given amount of a certain ship for a player
+        /// Gets the game parameters, from the Parameters contract if there is one
+        ///
+        /// # Returns
+        ///
+        /// * `parameters` - The game parameters
+        fn get_parameters(&self) -> GameParameters {
+            match &self.new_omega_parameters {
+                Some(new_omega_parameters) => new_omega_parameters.get_parameters(),
+                None => GameParameters::default(),
+            }
+        }
+
+        /// Produces a given amount of a certain ship for a player.
+        /// Retired ships can not be produced.
         ///
         /// # Arguments
         ///
@@ -65,7 +98,7 @@ mod newomegaindustrial {
         /// * `amount` - How many ships to produce
         #[ink(message)]
         pub fn produce_ships(&mut self, caller: AccountId, ship_id: u8, amount: u32) {
-            assert_eq!(self.env().caller(), self.owner.unwrap());
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
 
             let ship_id_usize: usize = ship_id as usize;
             let minerals = self
@@ -74,13 +107,15 @@ mod newomegaindustrial {
                 .unwrap()
                 .get_player_minerals(caller);
 
-            let ships: Vec<Ship> = self   
+            let catalogue: Vec<ShipCatalogueEntry> = self
                 .new_omega_game
                 .as_ref()
                 .unwrap()
-                .get_ships();
+                .get_ship_catalogue();
 
-            let cost_per_ship: u32 = (ships[ship_id_usize].cp as u32) * SHIP_COST_PER_CP;
+            assert!(!catalogue[ship_id_usize].details.retired, "Ship retired");
+
+            let cost_per_ship: u32 = (catalogue[ship_id_usize].ship.cp as u32) * self.get_parameters().ship_cost_per_cp;
             let total_cost: u32 = amount * cost_per_ship;
 
             assert!(minerals[ship_id_usize] >= total_cost);
@@ -102,6 +137,78 @@ mod newomegaindustrial {
                 .as_mut()
                 .unwrap()
                 .add_ships(caller, requested_ships);
+
+            self.env().emit_event(ShipsProduced {
+                player: caller,
+                ship_id,
+                amount,
+                cost: total_cost,
+            });
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_env::{
+            test,
+            DefaultEnvironment,
+        };
+        use ink_lang as ink;
+        type Accounts = test::DefaultAccounts<DefaultEnvironment>;
+
+        fn default_accounts() -> Accounts {
+            test::default_accounts()
+                .expect("Test environment is expected to be initialized.")
+        }
+
+        fn prepare_contract(accounts: &Accounts) -> NewOmegaIndustrial {
+            let mut contract = NewOmegaIndustrial::new(NewOmegaGame::default(),
+                NewOmegaStorage::default(), NewOmegaParameters::default());
+            contract.authorise_delegator(accounts.alice);
+            let storage = contract.new_omega_storage.as_mut().unwrap();
+            storage.ensure_minerals(accounts.alice);
+            storage.ensure_ships(accounts.alice);
+            contract
+        }
+
+        #[ink::test]
+        fn test_produce_ships() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+            let parameters: GameParameters = GameParameters::default();
+            let cost: u32 = 2 * newomega::prepare_ships()[1].cp as u32 * parameters.ship_cost_per_cp;
+
+            contract.produce_ships(accounts.alice, 1, 2);
+
+            let storage = contract.new_omega_storage.as_ref().unwrap();
+            let minerals: [u32; MAX_MINERALS] = storage.get_player_minerals(accounts.alice);
+            let ships: [u32; MAX_SHIPS] = storage.get_player_ships(accounts.alice);
+            assert_eq!(minerals[0], parameters.starting_mineral_count);
+            assert_eq!(minerals[1], parameters.starting_mineral_count - cost);
+            assert_eq!(ships[0], parameters.starting_ship_count);
+            assert_eq!(ships[1], parameters.starting_ship_count + 2);
+            // RoleGranted (authorise_delegator), then the production
+            assert_eq!(test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_produce_ships_retired() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+            contract.new_omega_game.as_mut().unwrap().retire_ship(1);
+
+            contract.produce_ships(accounts.alice, 1, 1);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_produce_ships_not_enough_minerals() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+
+            contract.produce_ships(accounts.alice, 0, GameParameters::default().starting_mineral_count);
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,525 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Interfaces of the contracts which other contracts depend on (Game, Storage and Parameters).
+//! Each interface is implemented for the on-chain contract reference, and for an in-memory
+//! counterpart, which the dependent contracts swap in for their off-chain tests, so that
+//! the full game loop (fights, production, harvesting) can be tested with ```cargo test```.
+
+use ink_env::AccountId;
+use ink_prelude::vec::Vec;
+use ink_storage::traits::{
+    PackedLayout,
+    SpreadLayout,
+};
+use newomega::FightEngine;
+use newomega::FightResult;
+use newomega::FightSettings;
+use newomega::Move;
+use newomega::Ship;
+use newomega::ShipModule;
+use newomega::TargetingType;
+use newomega::MAX_SHIPS;
+use newomegagame::NewOmegaGame;
+use newomegagame::ShipCatalogueEntry;
+use newomegagame::ShipDetails;
+use newomegastorage::NewOmegaStorage;
+use newomegastorage::MAX_MINERALS;
+use newomegaparameters::NewOmegaParameters;
+use newomegaparameters::GameParameters;
+
+/// Fight Management, as used by the other contracts
+pub trait GameInterface {
+    /// Calculates a fight between two fleets
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - Random seed of the fight
+    /// * `log_moves` - Whether to return the fight moves
+    /// * `selection_lhs` - Attacker fleet composition
+    /// * `selection_rhs` - Defender fleet composition
+    /// * `modules_lhs` - Attacker ship modules
+    /// * `modules_rhs` - Defender ship modules
+    /// * `targeting_lhs` - Attacker targeting
+    /// * `targeting_rhs` - Defender targeting
+    /// * `settings` - Maximum number of rounds, and the tie-break of the game mode
+    ///
+    /// # Returns
+    ///
+    /// * `result` - The fight result, and optionally the moves of both sides
+    #[allow(clippy::too_many_arguments)] // Same inputs as the Game contract fight
+    fn fight(&self, seed: u64, log_moves: bool, selection_lhs: [u8; MAX_SHIPS],
+        selection_rhs: [u8; MAX_SHIPS], modules_lhs: [ShipModule; MAX_SHIPS],
+        modules_rhs: [ShipModule; MAX_SHIPS], targeting_lhs: TargetingType,
+        targeting_rhs: TargetingType, settings: FightSettings)
+            -> (FightResult, Option<Vec<Move>>, Option<Vec<Move>>);
+
+    /// Gets the ship definitions
+    ///
+    /// # Returns
+    ///
+    /// * `ships` - A Vec of the ship definitions
+    fn get_ships(&self) -> Vec<Ship>;
+
+    /// Gets the ship catalogue (statistics and details of every ship)
+    ///
+    /// # Returns
+    ///
+    /// * `catalogue` - A Vec of the catalogue entries, by ship identifier
+    fn get_ship_catalogue(&self) -> Vec<ShipCatalogueEntry>;
+
+    /// Checks whether a fleet selection only uses ships which are not retired
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - The fleet composition
+    ///
+    /// # Returns
+    ///
+    /// * `is_active` - Whether no retired ship is selected
+    fn is_selection_active(&self, selection: [u8; MAX_SHIPS]) -> bool;
+}
+
+/// Player Storage, as used by the other contracts
+pub trait StorageInterface {
+    /// Makes sure the player has a minerals entry
+    fn ensure_minerals(&mut self, player: AccountId);
+    /// Makes sure the player has a trades entry
+    fn ensure_trades(&mut self, player: AccountId);
+    /// Makes sure the player has a ships entry
+    fn ensure_ships(&mut self, player: AccountId);
+    /// Gets the minerals of a player
+    fn get_player_minerals(&self, player: AccountId) -> [u32; MAX_MINERALS];
+    /// Adds minerals for a player
+    fn add_minerals(&mut self, player: AccountId, amounts: [u32; MAX_MINERALS]);
+    /// Removes minerals from a player (down to zero)
+    fn remove_minerals(&mut self, player: AccountId, amounts: [u32; MAX_MINERALS]);
+    /// Gets the ships of a player
+    fn get_player_ships(&self, player: AccountId) -> [u32; MAX_SHIPS];
+    /// Checks whether the player has enough ships for a fleet selection
+    fn has_enough_ships(&self, player: AccountId, selection: [u8; MAX_SHIPS]) -> bool;
+    /// Adds ships for a player
+    fn add_ships(&mut self, player: AccountId, amounts: [u32; MAX_SHIPS]);
+    /// Removes ships from a player (down to zero)
+    fn remove_ships(&mut self, player: AccountId, amounts: [u32; MAX_SHIPS]);
+    /// Marks a ranked win for a player
+    fn mark_ranked_win(&mut self, player: AccountId);
+    /// Marks a ranked loss for a player
+    fn mark_ranked_loss(&mut self, player: AccountId);
+}
+
+/// Game Parameters, as used by the other contracts
+pub trait ParametersInterface {
+    /// Gets the current game parameters
+    fn get_parameters(&self) -> GameParameters;
+}
+
+impl GameInterface for NewOmegaGame {
+    fn fight(&self, seed: u64, log_moves: bool, selection_lhs: [u8; MAX_SHIPS],
+        selection_rhs: [u8; MAX_SHIPS], modules_lhs: [ShipModule; MAX_SHIPS],
+        modules_rhs: [ShipModule; MAX_SHIPS], targeting_lhs: TargetingType,
+        targeting_rhs: TargetingType, settings: FightSettings)
+            -> (FightResult, Option<Vec<Move>>, Option<Vec<Move>>) {
+        NewOmegaGame::fight(self, seed, log_moves, selection_lhs, selection_rhs,
+            modules_lhs, modules_rhs, targeting_lhs, targeting_rhs, settings)
+    }
+
+    fn get_ships(&self) -> Vec<Ship> {
+        NewOmegaGame::get_ships(self)
+    }
+
+    fn get_ship_catalogue(&self) -> Vec<ShipCatalogueEntry> {
+        NewOmegaGame::get_ship_catalogue(self)
+    }
+
+    fn is_selection_active(&self, selection: [u8; MAX_SHIPS]) -> bool {
+        NewOmegaGame::is_selection_active(self, selection)
+    }
+}
+
+impl StorageInterface for NewOmegaStorage {
+    fn ensure_minerals(&mut self, player: AccountId) {
+        NewOmegaStorage::ensure_minerals(self, player)
+    }
+
+    fn ensure_trades(&mut self, player: AccountId) {
+        NewOmegaStorage::ensure_trades(self, player)
+    }
+
+    fn ensure_ships(&mut self, player: AccountId) {
+        NewOmegaStorage::ensure_ships(self, player)
+    }
+
+    fn get_player_minerals(&self, player: AccountId) -> [u32; MAX_MINERALS] {
+        NewOmegaStorage::get_player_minerals(self, player)
+    }
+
+    fn add_minerals(&mut self, player: AccountId, amounts: [u32; MAX_MINERALS]) {
+        NewOmegaStorage::add_minerals(self, player, amounts)
+    }
+
+    fn remove_minerals(&mut self, player: AccountId, amounts: [u32; MAX_MINERALS]) {
+        NewOmegaStorage::remove_minerals(self, player, amounts)
+    }
+
+    fn get_player_ships(&self, player: AccountId) -> [u32; MAX_SHIPS] {
+        NewOmegaStorage::get_player_ships(self, player)
+    }
+
+    fn has_enough_ships(&self, player: AccountId, selection: [u8; MAX_SHIPS]) -> bool {
+        NewOmegaStorage::has_enough_ships(self, player, selection)
+    }
+
+    fn add_ships(&mut self, player: AccountId, amounts: [u32; MAX_SHIPS]) {
+        NewOmegaStorage::add_ships(self, player, amounts)
+    }
+
+    fn remove_ships(&mut self, player: AccountId, amounts: [u32; MAX_SHIPS]) {
+        NewOmegaStorage::remove_ships(self, player, amounts)
+    }
+
+    fn mark_ranked_win(&mut self, player: AccountId) {
+        NewOmegaStorage::mark_ranked_win(self, player)
+    }
+
+    fn mark_ranked_loss(&mut self, player: AccountId) {
+        NewOmegaStorage::mark_ranked_loss(self, player)
+    }
+}
+
+impl ParametersInterface for NewOmegaParameters {
+    fn get_parameters(&self) -> GameParameters {
+        NewOmegaParameters::get_parameters(self)
+    }
+}
+
+/// In-memory Fight Management. Runs the fight engine directly, with the default ship
+/// definitions, instead of calling the Game and NewOmega contracts.
+#[derive(scale::Encode, scale::Decode, Clone)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo
+    )
+)]
+pub struct InMemoryGame {
+    ships: Vec<Ship>,
+    details: Vec<ShipDetails>,
+}
+
+// Holds the ships, which are fight engine types
+newomegalayout::packed_layout!(InMemoryGame);
+
+impl Default for InMemoryGame {
+    fn default() -> Self {
+        Self {
+            ships: newomega::prepare_ships(),
+            details: ShipDetails::defaults(),
+        }
+    }
+}
+
+impl InMemoryGame {
+    /// Retires a ship, so it can no longer be produced or selected
+    ///
+    /// # Arguments
+    ///
+    /// * `ship_id` - Ship identifier
+    pub fn retire_ship(&mut self, ship_id: u8) {
+        self.details[ship_id as usize].retired = true;
+    }
+}
+
+impl GameInterface for InMemoryGame {
+    fn fight(&self, seed: u64, log_moves: bool, selection_lhs: [u8; MAX_SHIPS],
+        selection_rhs: [u8; MAX_SHIPS], modules_lhs: [ShipModule; MAX_SHIPS],
+        modules_rhs: [ShipModule; MAX_SHIPS], targeting_lhs: TargetingType,
+        targeting_rhs: TargetingType, settings: FightSettings)
+            -> (FightResult, Option<Vec<Move>>, Option<Vec<Move>>) {
+        FightEngine::default().fight(seed, log_moves, self.ships.clone(), selection_lhs,
+            selection_rhs, modules_lhs, modules_rhs, targeting_lhs, targeting_rhs, settings)
+    }
+
+    fn get_ships(&self) -> Vec<Ship> {
+        self.ships.clone()
+    }
+
+    fn get_ship_catalogue(&self) -> Vec<ShipCatalogueEntry> {
+        self.ships
+            .iter()
+            .zip(self.details.iter())
+            .enumerate()
+            .map(|(ship_id, (ship, details))| ShipCatalogueEntry {
+                ship_id: ship_id as u8,
+                ship: *ship,
+                details: details.clone(),
+            })
+            .collect()
+    }
+
+    fn is_selection_active(&self, selection: [u8; MAX_SHIPS]) -> bool {
+        (0..MAX_SHIPS).all(|i| selection[i] == 0 || !self.details[i].retired)
+    }
+}
+
+/// In-memory Player Storage. Players start with the default starting ships and minerals,
+/// same as in the Storage contract. Trades are not tracked.
+#[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo,
+        ink_storage::traits::StorageLayout
+    )
+)]
+pub struct InMemoryStorage {
+    minerals: Vec<(AccountId, [u32; MAX_MINERALS])>,
+    ships: Vec<(AccountId, [u32; MAX_SHIPS])>,
+    ranked: Vec<(AccountId, u32, u32)>,
+}
+
+impl InMemoryStorage {
+    /// Gets the ranked record of a player
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - AccountId of the player
+    ///
+    /// # Returns
+    ///
+    /// * `record` - A tuple of (ranked wins, ranked losses)
+    pub fn get_ranked_record(&self, player: AccountId) -> (u32, u32) {
+        self.ranked
+            .iter()
+            .find(|(account, _, _)| *account == player)
+            .map(|(_, wins, losses)| (*wins, *losses))
+            .unwrap_or((0, 0))
+    }
+
+    fn minerals_mut(&mut self, player: AccountId) -> &mut [u32; MAX_MINERALS] {
+        let index: usize = match self.minerals.iter().position(|(account, _)| *account == player) {
+            Some(index) => index,
+            None => {
+                let starting_count: u32 = GameParameters::default().starting_mineral_count;
+                self.minerals.push((player, [starting_count; MAX_MINERALS]));
+                self.minerals.len() - 1
+            },
+        };
+        &mut self.minerals[index].1
+    }
+
+    fn ships_mut(&mut self, player: AccountId) -> &mut [u32; MAX_SHIPS] {
+        let index: usize = match self.ships.iter().position(|(account, _)| *account == player) {
+            Some(index) => index,
+            None => {
+                let starting_count: u32 = GameParameters::default().starting_ship_count;
+                self.ships.push((player, [starting_count; MAX_SHIPS]));
+                self.ships.len() - 1
+            },
+        };
+        &mut self.ships[index].1
+    }
+
+    fn ranked_mut(&mut self, player: AccountId) -> &mut (AccountId, u32, u32) {
+        let index: usize = match self.ranked.iter().position(|(account, _, _)| *account == player) {
+            Some(index) => index,
+            None => {
+                self.ranked.push((player, 0, 0));
+                self.ranked.len() - 1
+            },
+        };
+        &mut self.ranked[index]
+    }
+}
+
+impl StorageInterface for InMemoryStorage {
+    fn ensure_minerals(&mut self, player: AccountId) {
+        self.minerals_mut(player);
+    }
+
+    fn ensure_trades(&mut self, _player: AccountId) {}
+
+    fn ensure_ships(&mut self, player: AccountId) {
+        self.ships_mut(player);
+    }
+
+    fn get_player_minerals(&self, player: AccountId) -> [u32; MAX_MINERALS] {
+        let minerals = self.minerals.iter().find(|(account, _)| *account == player);
+        assert!(minerals.is_some());
+        minerals.unwrap().1
+    }
+
+    fn add_minerals(&mut self, player: AccountId, amounts: [u32; MAX_MINERALS]) {
+        let minerals = self.minerals_mut(player);
+        for i in 0..MAX_MINERALS {
+            minerals[i] += amounts[i];
+        }
+    }
+
+    fn remove_minerals(&mut self, player: AccountId, amounts: [u32; MAX_MINERALS]) {
+        let minerals = self.minerals_mut(player);
+        for i in 0..MAX_MINERALS {
+            minerals[i] = minerals[i].saturating_sub(amounts[i]);
+        }
+    }
+
+    fn get_player_ships(&self, player: AccountId) -> [u32; MAX_SHIPS] {
+        let ships = self.ships.iter().find(|(account, _)| *account == player);
+        assert!(ships.is_some());
+        ships.unwrap().1
+    }
+
+    fn has_enough_ships(&self, player: AccountId, selection: [u8; MAX_SHIPS]) -> bool {
+        let ships: [u32; MAX_SHIPS] = self.get_player_ships(player);
+        (0..MAX_SHIPS).all(|i| ships[i] >= selection[i] as u32)
+    }
+
+    fn add_ships(&mut self, player: AccountId, amounts: [u32; MAX_SHIPS]) {
+        let ships = self.ships_mut(player);
+        for i in 0..MAX_SHIPS {
+            ships[i] += amounts[i];
+        }
+    }
+
+    fn remove_ships(&mut self, player: AccountId, amounts: [u32; MAX_SHIPS]) {
+        let ships = self.ships_mut(player);
+        for i in 0..MAX_SHIPS {
+            ships[i] = ships[i].saturating_sub(amounts[i]);
+        }
+    }
+
+    fn mark_ranked_win(&mut self, player: AccountId) {
+        self.ranked_mut(player).1 += 1;
+    }
+
+    fn mark_ranked_loss(&mut self, player: AccountId) {
+        self.ranked_mut(player).2 += 1;
+    }
+}
+
+/// In-memory Game Parameters
+#[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy, Default)]
+#[cfg_attr(
+    feature = "std",
+    derive(
+        Debug,
+        PartialEq,
+        Eq,
+        scale_info::TypeInfo,
+        ink_storage::traits::StorageLayout
+    )
+)]
+pub struct InMemoryParameters {
+    parameters: GameParameters,
+}
+
+impl InMemoryParameters {
+    /// Creates the in-memory parameters with the given values
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - The game parameters
+    pub fn new(parameters: GameParameters) -> Self {
+        Self {
+            parameters,
+        }
+    }
+}
+
+impl ParametersInterface for InMemoryParameters {
+    fn get_parameters(&self) -> GameParameters {
+        self.parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use newomega::Outcome;
+    use newomega::TieBreak;
+    use ink_env::{
+        test,
+        DefaultEnvironment,
+    };
+    use ink_lang as ink;
+    type Accounts = test::DefaultAccounts<DefaultEnvironment>;
+
+    fn default_accounts() -> Accounts {
+        test::default_accounts()
+            .expect("Test environment is expected to be initialized.")
+    }
+
+    #[ink::test]
+    fn test_in_memory_fight() {
+        let game = InMemoryGame::default();
+        let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+
+        let (result, _, _) = game.fight(1, false, [20, 20, 20, 20], [10, 10, 10, 10],
+            modules, modules, TargetingType::default(), TargetingType::default(),
+            FightSettings::default());
+        assert!(!result.lhs_dead && result.rhs_dead);
+        assert_eq!(result.outcome, Outcome::AttackerWin);
+        assert_eq!(result.ships_lost_rhs, [10, 10, 10, 10]);
+
+        // Same result as the fight engine
+        let (expected, _, _) = FightEngine::default().fight(1, false, newomega::prepare_ships(),
+            [20, 20, 20, 20], [10, 10, 10, 10], modules, modules, TargetingType::default(),
+            TargetingType::default(), FightSettings::default());
+        assert_eq!(result, expected);
+
+        let (result, _, _) = game.fight(1, false, [5, 5, 5, 5], [10, 10, 10, 10],
+            modules, modules, TargetingType::default(), TargetingType::default(),
+            FightSettings::default());
+        assert!(result.lhs_dead && !result.rhs_dead);
+        assert_eq!(result.outcome, Outcome::DefenderWin);
+
+        // A single round is too short to finish the fight
+        let settings: FightSettings = FightSettings { max_rounds: 1, tie_break: TieBreak::None, hp_bonus_rhs: 0 };
+        let (result, _, _) = game.fight(1, false, [20, 20, 20, 20], [10, 10, 10, 10],
+            modules, modules, TargetingType::default(), TargetingType::default(), settings);
+        assert!(!result.lhs_dead && !result.rhs_dead);
+        assert_eq!(result.outcome, Outcome::Draw);
+        assert_eq!(result.settings, settings);
+
+        let settings: FightSettings = FightSettings { max_rounds: 1, tie_break: TieBreak::HpPercentage, hp_bonus_rhs: 0 };
+        let (result, _, _) = game.fight(1, false, [20, 20, 20, 20], [10, 10, 10, 10],
+            modules, modules, TargetingType::default(), TargetingType::default(), settings);
+        assert!(!result.lhs_dead && !result.rhs_dead);
+        assert_eq!(result.outcome, Outcome::AttackerWin);
+    }
+
+    #[ink::test]
+    fn test_in_memory_retire_ship() {
+        let mut game = InMemoryGame::default();
+
+        game.retire_ship(1);
+
+        assert!(game.get_ship_catalogue()[1].details.retired);
+        assert!(game.is_selection_active([10, 0, 10, 10]));
+        assert!(!game.is_selection_active([10, 1, 10, 10]));
+    }
+
+    #[ink::test]
+    fn test_in_memory_storage() {
+        let accounts = default_accounts();
+        let mut storage = InMemoryStorage::default();
+        let starting_ships: u32 = GameParameters::default().starting_ship_count;
+
+        storage.ensure_ships(accounts.alice);
+        storage.add_ships(accounts.alice, [1, 2, 3, 4]);
+        storage.remove_ships(accounts.alice, [starting_ships + 10, 0, 0, 0]);
+        assert_eq!(storage.get_player_ships(accounts.alice),
+            [0, starting_ships + 2, starting_ships + 3, starting_ships + 4]);
+        assert!(!storage.has_enough_ships(accounts.alice, [1, 0, 0, 0]));
+
+        storage.mark_ranked_win(accounts.alice);
+        storage.mark_ranked_loss(accounts.bob);
+        assert_eq!(storage.get_ranked_record(accounts.alice), (1, 0));
+        assert_eq!(storage.get_ranked_record(accounts.bob), (0, 1));
+    }
+}
@@ -0,0 +1,233 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Storage layouts for the fight engine types, which are kept in contract storage.
+//! The engine is a plain Rust library without ink!, so its types only implement the SCALE codec.
+//! The contracts keep them in storage through this library, either wrapped in `Packed`
+//! (when held directly in the contract storage), or within a contract type given its
+//! layouts by `packed_layout!`. Both keep the value SCALE encoded in a single storage cell.
+
+use core::ops::{
+    Deref,
+    DerefMut,
+};
+use ink_primitives::Key;
+use ink_storage::traits::{
+    forward_clear_packed,
+    forward_pull_packed,
+    forward_push_packed,
+    KeyPtr,
+    PackedLayout,
+    SpreadLayout,
+};
+
+#[doc(hidden)]
+pub mod private {
+    pub use ink_primitives::Key;
+    pub use ink_storage::traits::{
+        forward_clear_packed,
+        forward_pull_packed,
+        forward_push_packed,
+        KeyPtr,
+        PackedLayout,
+        SpreadLayout,
+    };
+    #[cfg(feature = "std")]
+    pub use ink_metadata::layout::{
+        CellLayout,
+        Layout,
+        LayoutKey,
+    };
+    #[cfg(feature = "std")]
+    pub use ink_storage::traits::{
+        ExtKeyPtr,
+        StorageLayout,
+    };
+}
+
+/// Holds a value in contract storage, SCALE encoded in a single cell.
+/// Encodes the same as the value itself.
+#[derive(scale::Encode, scale::Decode, Clone, Copy, Default)]
+#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
+pub struct Packed<T>(T);
+
+impl<T> Packed<T> {
+    /// Wraps a value
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to keep in storage
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Unwraps the value
+    ///
+    /// # Returns
+    ///
+    /// * `value` - The value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Packed<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for Packed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Packed<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> SpreadLayout for Packed<T>
+where
+    T: scale::Encode + scale::Decode,
+{
+    const FOOTPRINT: u64 = 1;
+    const REQUIRES_DEEP_CLEAN_UP: bool = false;
+
+    fn pull_spread(ptr: &mut KeyPtr) -> Self {
+        forward_pull_packed::<Self>(ptr)
+    }
+
+    fn push_spread(&self, ptr: &mut KeyPtr) {
+        forward_push_packed::<Self>(self, ptr)
+    }
+
+    fn clear_spread(&self, ptr: &mut KeyPtr) {
+        forward_clear_packed::<Self>(self, ptr)
+    }
+}
+
+impl<T> PackedLayout for Packed<T>
+where
+    T: scale::Encode + scale::Decode,
+{
+    fn pull_packed(&mut self, _at: &Key) {}
+    fn push_packed(&self, _at: &Key) {}
+    fn clear_packed(&self, _at: &Key) {}
+}
+
+// Same as the value itself in the metadata, like the encoding
+#[cfg(feature = "std")]
+impl<T> scale_info::TypeInfo for Packed<T>
+where
+    T: scale_info::TypeInfo + 'static,
+{
+    type Identity = T;
+
+    fn type_info() -> scale_info::Type {
+        T::type_info()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> private::StorageLayout for Packed<T>
+where
+    T: scale::Encode + scale::Decode + scale_info::TypeInfo + 'static,
+{
+    fn layout(key_ptr: &mut KeyPtr) -> private::Layout {
+        use private::ExtKeyPtr;
+
+        private::Layout::Cell(private::CellLayout::new::<T>(
+            private::LayoutKey::from(key_ptr.next_for::<Self>())))
+    }
+}
+
+/// Implements the storage layouts (SpreadLayout, PackedLayout, and StorageLayout with the
+/// `std` feature) for types which hold fight engine types, keeping them SCALE encoded in
+/// a single storage cell. Takes the place of deriving the layouts, which requires the layouts
+/// of every field.
+#[macro_export]
+macro_rules! packed_layout {
+    ( $($ty:ty),* $(,)? ) => {
+        $(
+            impl $crate::private::SpreadLayout for $ty {
+                const FOOTPRINT: u64 = 1;
+                const REQUIRES_DEEP_CLEAN_UP: bool = false;
+
+                fn pull_spread(ptr: &mut $crate::private::KeyPtr) -> Self {
+                    $crate::private::forward_pull_packed::<Self>(ptr)
+                }
+
+                fn push_spread(&self, ptr: &mut $crate::private::KeyPtr) {
+                    $crate::private::forward_push_packed::<Self>(self, ptr)
+                }
+
+                fn clear_spread(&self, ptr: &mut $crate::private::KeyPtr) {
+                    $crate::private::forward_clear_packed::<Self>(self, ptr)
+                }
+            }
+
+            impl $crate::private::PackedLayout for $ty {
+                fn pull_packed(&mut self, _at: &$crate::private::Key) {}
+                fn push_packed(&self, _at: &$crate::private::Key) {}
+                fn clear_packed(&self, _at: &$crate::private::Key) {}
+            }
+
+            #[cfg(feature = "std")]
+            impl $crate::private::StorageLayout for $ty {
+                fn layout(key_ptr: &mut $crate::private::KeyPtr) -> $crate::private::Layout {
+                    use $crate::private::ExtKeyPtr;
+
+                    $crate::private::Layout::Cell($crate::private::CellLayout::new::<$ty>(
+                        $crate::private::LayoutKey::from(key_ptr.next_for::<Self>())))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink_lang as ink;
+    use ink_storage::traits::{
+        pull_spread_root,
+        push_spread_root,
+    };
+
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+    struct Garrison {
+        selection: [u8; 4],
+        name: ink_prelude::string::String,
+    }
+
+    packed_layout!(Garrison);
+
+    #[ink::test]
+    fn test_packed_round_trip() {
+        let key: Key = Key::from([0x01; 32]);
+        let value: Packed<(u8, u32)> = Packed::new((7, 1337));
+
+        push_spread_root(&value, &key);
+
+        assert_eq!(pull_spread_root::<Packed<(u8, u32)>>(&key), value);
+        assert_eq!(scale::Encode::encode(&value), scale::Encode::encode(&(7u8, 1337u32)));
+    }
+
+    #[ink::test]
+    fn test_packed_layout_round_trip() {
+        let key: Key = Key::from([0x02; 32]);
+        let garrison: Garrison = Garrison {
+            selection: [1, 2, 3, 4],
+            name: "Garrison".into(),
+        };
+
+        push_spread_root(&garrison, &key);
+
+        assert_eq!(pull_spread_root::<Garrison>(&key), garrison);
+        assert_eq!(<Garrison as SpreadLayout>::FOOTPRINT, 1);
+    }
+}
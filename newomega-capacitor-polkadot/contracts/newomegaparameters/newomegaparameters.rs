@@ -0,0 +1,317 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+// The ink! constructors take the place of Default
+#![allow(clippy::new_without_default)]
+// Built as a dependency, ink! leaves out the contract implementation and what it uses
+#![cfg_attr(feature = "ink-as-dependency", allow(unused_imports, dead_code))]
+
+use ink_lang as ink;
+pub use self::newomegaparameters::NewOmegaParameters;
+pub use self::newomegaparameters::GameParameters;
+
+/// Central store for the game economy values. All the other contracts read their fees,
+/// rewards and timings from here, so the economy can be tuned without an upgrade,
+/// and the client can display the real prices.
+#[newomegaaccess::access_control(NewOmegaParameters)]
+#[ink::contract]
+mod newomegaparameters {
+    use newomegaaccess::AccessControl;
+    use newomegaaccess::Role;
+    use ink_storage::{
+        traits::{
+            PackedLayout,
+            SpreadLayout,
+        },
+    };
+
+    /// Smallest unit of the native token, times 10^12 (1 Unit).
+    pub const UNIT: Balance = 1000000 * 1000000;
+
+    /// Upper bound for any single fee or reward.
+    pub const MAX_FEE: Balance = 1000 * UNIT;
+
+    /// Upper bound for the starting ship and mineral counts.
+    pub const MAX_STARTING_COUNT: u32 = 1000000;
+
+    /// Upper bound for any block based duration.
+    pub const MAX_DURATION_BLOCKS: BlockNumber = 1000000;
+
+    /// Upper bound for the number of mineral generation periods a Planet can accumulate
+    /// (max_harvestable_blocks / mineral_generation_blocks), so that a harvest at the highest
+    /// Planet level, mineral proof and harvest multiplier still fits the mineral amounts.
+    pub const MAX_HARVEST_PERIODS: BlockNumber = 5000;
+
+    /// Holds all the tunable game economy values.
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct GameParameters {
+        /// Amount granted to newly registered Universe players
+        pub registration_grant: Balance,
+        /// Fee for discovering a System (when the free discovery is not available)
+        pub discovery_fee: Balance,
+        /// Fee for harvesting minerals
+        pub harvest_fee: Balance,
+        /// Fee for renaming a Planet
+        pub rename_fee: Balance,
+        /// Fee for upgrading a Planet, charged on top of the mineral cost of the upgrade
+        /// (see the Universe upgrade cost table), same as the other action fees
+        pub upgrade_fee: Balance,
+        /// Fee for scouting a System
+        pub scout_fee: Balance,
+        /// Reward for capturing a Planet
+        pub planet_capture_reward: Balance,
+        /// How often a free System discovery is available
+        pub free_discovery_frequency_blocks: BlockNumber,
+        /// Mineral cost of producing a ship, per each point of its Command Power
+        pub ship_cost_per_cp: u32,
+        /// How many blocks worth of minerals a Planet can accumulate
+        pub max_harvestable_blocks: BlockNumber,
+        /// How many blocks it takes a Planet to generate minerals
+        pub mineral_generation_blocks: BlockNumber,
+        /// How long scouting a System gives full visibility of it
+        pub scout_duration_blocks: BlockNumber,
+        /// Cost of a gateway, in every mineral
+        pub gateway_cost: u32,
+        /// How long a gateway stays disabled after a successful attack
+        pub gateway_disable_blocks: BlockNumber,
+        /// Ships every player starts with, of each type
+        pub starting_ship_count: u32,
+        /// Minerals every player starts with, of each type
+        pub starting_mineral_count: u32,
+    }
+
+    impl Default for GameParameters {
+        fn default() -> Self {
+            Self {
+                registration_grant: 10 * UNIT,
+                discovery_fee: UNIT,
+                harvest_fee: UNIT,
+                rename_fee: UNIT,
+                upgrade_fee: 2 * UNIT,
+                scout_fee: UNIT,
+                planet_capture_reward: UNIT / 10,
+                free_discovery_frequency_blocks: 1000,
+                ship_cost_per_cp: 10,
+                max_harvestable_blocks: 24000,
+                mineral_generation_blocks: 100,
+                scout_duration_blocks: 600,
+                gateway_cost: 250,
+                gateway_disable_blocks: 1000,
+                starting_ship_count: 50,
+                starting_mineral_count: 1000,
+            }
+        }
+    }
+
+    #[ink(storage)]
+    pub struct NewOmegaParameters {
+        access: AccessControl,
+        parameters: GameParameters,
+    }
+
+    #[ink(event)]
+    pub struct ParametersUpdated {
+        #[ink(topic)]
+        updater: AccountId,
+        parameters: GameParameters,
+    }
+
+    impl NewOmegaParameters {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                access: AccessControl::new(Self::env().caller()),
+                parameters: GameParameters::default(),
+            }
+        }
+
+        #[ink(constructor)]
+        pub fn default() -> Self {
+            Self::new()
+        }
+
+        /// Authorises the Delegator contract to fire methods on this one
+        /// (grants it the Delegator role).
+        ///
+        /// # Arguments
+        ///
+        /// * `delegator_address` - AccountId of the Delegator contract
+        #[ink(message)]
+        pub fn authorise_delegator(&mut self, delegator_address: AccountId) {
+            self.grant_role(Role::Delegator, delegator_address);
+        }
+
+        /// Updates the game parameters (Admin or the Delegator only).
+        /// Panics if any of the values is out of bounds.
+        ///
+        /// # Arguments
+        ///
+        /// * `parameters` - The new game parameters
+        #[ink(message)]
+        pub fn set_parameters(&mut self, parameters: GameParameters) {
+            let caller: AccountId = self.env().caller();
+            self.access.ensure_any_role(caller, &[Role::Admin, Role::Delegator]);
+            Self::validate_parameters(&parameters);
+
+            self.parameters = parameters;
+
+            self.env().emit_event(ParametersUpdated {
+                updater: caller,
+                parameters,
+            });
+        }
+
+        /// Gets the current game parameters.
+        ///
+        /// # Returns
+        ///
+        /// * `parameters` - The game parameters
+        #[ink(message)]
+        pub fn get_parameters(&self) -> GameParameters {
+            self.parameters
+        }
+
+        /// Checks the game parameters against their bounds, panics on invalid ones.
+        ///
+        /// # Arguments
+        ///
+        /// * `parameters` - The game parameters to validate
+        fn validate_parameters(parameters: &GameParameters) {
+            let fees: [Balance; 7] = [
+                parameters.registration_grant,
+                parameters.discovery_fee,
+                parameters.harvest_fee,
+                parameters.rename_fee,
+                parameters.upgrade_fee,
+                parameters.scout_fee,
+                parameters.planet_capture_reward,
+            ];
+            assert!(fees.iter().all(|fee| *fee <= MAX_FEE));
+
+            let durations: [BlockNumber; 5] = [
+                parameters.free_discovery_frequency_blocks,
+                parameters.max_harvestable_blocks,
+                parameters.mineral_generation_blocks,
+                parameters.scout_duration_blocks,
+                parameters.gateway_disable_blocks,
+            ];
+            assert!(durations.iter().all(|duration| *duration > 0 && *duration <= MAX_DURATION_BLOCKS));
+            assert!(parameters.max_harvestable_blocks >= parameters.mineral_generation_blocks);
+            assert!(parameters.max_harvestable_blocks / parameters.mineral_generation_blocks <= MAX_HARVEST_PERIODS);
+
+            assert!(parameters.ship_cost_per_cp > 0);
+            assert!(parameters.gateway_cost > 0);
+            assert!(parameters.starting_ship_count <= MAX_STARTING_COUNT);
+            assert!(parameters.starting_mineral_count <= MAX_STARTING_COUNT);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+        type Accounts = test::DefaultAccounts<Environment>;
+
+        fn default_accounts() -> Accounts {
+            test::default_accounts()
+                .expect("Test environment is expected to be initialized.")
+        }
+
+        fn set_sender(sender: AccountId) {
+            let callee: AccountId = test::get_current_contract_account_id::<Environment>()
+                .expect("Cannot get contract id");
+            test::push_execution_context::<Environment>(
+                sender,
+                callee,
+                1000000,
+                0,
+                test::CallData::new(call::Selector::new([0x00; 4])),
+            );
+        }
+
+        #[ink::test]
+        fn test_default_parameters() {
+            let contract = NewOmegaParameters::default();
+            let parameters = contract.get_parameters();
+
+            assert_eq!(parameters, GameParameters::default());
+            assert_eq!(parameters.registration_grant, 10 * UNIT);
+            assert_eq!(parameters.upgrade_fee, 2 * UNIT);
+            assert_eq!(parameters.ship_cost_per_cp, 10);
+        }
+
+        #[ink::test]
+        fn test_set_parameters() {
+            let mut contract = NewOmegaParameters::default();
+            let parameters = GameParameters {
+                harvest_fee: 3 * UNIT,
+                starting_ship_count: 100,
+                ..GameParameters::default()
+            };
+
+            contract.set_parameters(parameters);
+            assert_eq!(contract.get_parameters().harvest_fee, 3 * UNIT);
+            assert_eq!(contract.get_parameters().starting_ship_count, 100);
+            assert_eq!(test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_set_parameters_out_of_bounds() {
+            let mut contract = NewOmegaParameters::default();
+            let parameters = GameParameters {
+                mineral_generation_blocks: 0,
+                ..GameParameters::default()
+            };
+
+            contract.set_parameters(parameters);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_set_parameters_too_many_harvest_periods() {
+            let mut contract = NewOmegaParameters::default();
+            let parameters = GameParameters {
+                mineral_generation_blocks: 1,
+                max_harvestable_blocks: MAX_HARVEST_PERIODS + 1,
+                ..GameParameters::default()
+            };
+
+            contract.set_parameters(parameters);
+        }
+
+        #[ink::test]
+        fn test_access_control_messages() {
+            let mut contract = NewOmegaParameters::default();
+            let accounts = default_accounts();
+
+            contract.grant_role(Role::Guardian, accounts.charlie);
+            assert!(contract.has_role(accounts.charlie, Role::Guardian));
+            contract.revoke_role(Role::Guardian, accounts.charlie);
+            assert!(!contract.has_role(accounts.charlie, Role::Guardian));
+
+            // The transfer only completes once accepted
+            contract.transfer_ownership(accounts.bob);
+            assert_eq!(contract.get_owner(), accounts.alice);
+            assert_eq!(test::recorded_events().count(), 3);
+
+            set_sender(accounts.bob);
+            contract.accept_ownership();
+            assert_eq!(contract.get_owner(), accounts.bob);
+            assert!(contract.has_role(accounts.bob, Role::Admin));
+            assert_eq!(test::recorded_events().count(), 4);
+        }
+    }
+}
@@ -1,5 +1,9 @@
 #![feature(destructuring_assignment)]
 #![cfg_attr(not(feature = "std"), no_std)]
+// The ink! constructors take the place of Default
+#![allow(clippy::new_without_default)]
+// Built as a dependency, ink! leaves out the contract implementation and what it uses
+#![cfg_attr(feature = "ink-as-dependency", allow(unused_imports, dead_code))]
 
 use ink_lang as ink;
 pub use self::newomegaranked::NewOmegaRanked;
@@ -8,38 +12,47 @@ pub use self::newomegaranked::PlayerDefence;
 /// The logic for all ranked fights between players. Connected to Fight Management
 /// in order to run fights, and to Storage in order to save the results and perform
 /// actions according to their result.
+#[newomegaaccess::access_control(NewOmegaRanked)]
 #[ink::contract]
 mod newomegaranked {
+    use newomegaaccess::AccessControl;
+    use newomegaaccess::Role;
+    #[cfg(not(test))]
     use newomegagame::NewOmegaGame;
+    #[cfg(not(test))]
     use newomegastorage::NewOmegaStorage;
+    // Off-chain tests run against the in-memory Game and Storage
+    #[cfg(test)]
+    use newomegainterfaces::{
+        GameInterface,
+        InMemoryGame as NewOmegaGame,
+        InMemoryStorage as NewOmegaStorage,
+        StorageInterface,
+    };
     use newomega::MAX_SHIPS;
     use newomega::FightResult;
+    use newomega::FightSettings;
+    use newomega::Outcome;
     use newomega::ShipModule;
     use newomega::TargetingType;
+    use newomegalayout::Packed;
     use ink_prelude::vec::Vec;
     use ink_prelude::string::String;
     use ink_storage::{
         collections::{
             HashMap as StorageHashMap,
         },
-        traits::{
-            PackedLayout,
-            SpreadLayout,
-        },
     };
 
-    const XP_PER_RANKED_WIN: u32 = 1;
-
     /// Describes a registered defence of a player
-    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[derive(scale::Encode, scale::Decode, Clone)]
     #[cfg_attr(
         feature = "std",
         derive(
             Debug,
             PartialEq,
             Eq,
-            scale_info::TypeInfo,
-            ink_storage::traits::StorageLayout
+            scale_info::TypeInfo
         )
     )]
     pub struct PlayerDefence {
@@ -59,36 +72,102 @@ mod newomegaranked {
         losses: u32,
     }
 
+    // Holds the modules and targeting, which are fight engine types
+    newomegalayout::packed_layout!(PlayerDefence);
+
     #[ink(storage)]
     pub struct NewOmegaRanked {
-        creator: AccountId,
-        owner: Option<AccountId>,
-        new_omega_game: newomegagame::NewOmegaGame,
-        new_omega_storage: newomegastorage::NewOmegaStorage,
+        access: AccessControl,
+        new_omega_game: NewOmegaGame,
+        new_omega_storage: NewOmegaStorage,
         defences: StorageHashMap<AccountId, PlayerDefence>,
+        fight_settings: Packed<FightSettings>,
+    }
+
+    /// Emitted when a player registers a Ranked Defence
+    #[ink(event)]
+    pub struct DefenceRegistered {
+        #[ink(topic)]
+        player: AccountId,
+        value: Balance,
+    }
+
+    /// Emitted when a Ranked Defence changes after a fight
+    #[ink(event)]
+    pub struct DefenceUpdated {
+        #[ink(topic)]
+        player: AccountId,
+        value: Balance,
+        wins: u32,
+        losses: u32,
+    }
+
+    /// Emitted when a player withdraws a Ranked Defence (and its stake)
+    #[ink(event)]
+    pub struct DefenceWithdrawn {
+        #[ink(topic)]
+        player: AccountId,
+        value: Balance,
+    }
+
+    /// Emitted when the settings of ranked fights change
+    #[ink(event)]
+    pub struct FightSettingsChanged {
+        settings: FightSettings,
     }
 
     impl NewOmegaRanked {
         #[ink(constructor)]
         pub fn new(new_omega_game: NewOmegaGame, new_omega_storage: NewOmegaStorage) -> Self {
             Self {
-                creator: Self::env().caller(),
-                owner: None,
+                access: AccessControl::new(Self::env().caller()),
                 new_omega_game,
                 new_omega_storage,
                 defences: StorageHashMap::default(),
+                fight_settings: Packed::default(),
             }
         }
 
-        /// Authorises the Delegator contract to fire methods on this one.
+        /// Authorises the Delegator contract to fire methods on this one
+        /// (grants it the Delegator role).
         ///
         /// # Arguments
         ///
         /// * `delegator_address` - AccountId of the Delegator contract
         #[ink(message)]
         pub fn authorise_delegator(&mut self, delegator_address: AccountId) {
-            assert_eq!(self.env().caller(), self.creator);
-            self.owner = Some(delegator_address);
+            self.grant_role(Role::Delegator, delegator_address);
+        }
+
+        /// Sets the settings of ranked fights: the maximum number of rounds, and how a fight
+        /// still not finished after them is decided.
+        ///
+        /// # Arguments
+        ///
+        /// * `settings` - The new fight settings
+        ///
+        /// # Events
+        ///
+        /// * FightSettingsChanged - when the settings are set
+        #[ink(message)]
+        pub fn set_fight_settings(&mut self, settings: FightSettings) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            assert!(settings.max_rounds > 0, "Invalid max rounds");
+            self.fight_settings = Packed::new(settings);
+
+            self.env().emit_event(FightSettingsChanged {
+                settings,
+            });
+        }
+
+        /// Gets the settings of ranked fights
+        ///
+        /// # Returns
+        ///
+        /// * `settings` - The fight settings
+        #[ink(message)]
+        pub fn get_fight_settings(&self) -> FightSettings {
+            *self.fight_settings
         }
 
         /// Registers a fleet for Ranked Defence.
@@ -103,8 +182,9 @@ mod newomegaranked {
         pub fn register_defence(&mut self, caller: AccountId, selection: [u8; MAX_SHIPS],
             modules: [ShipModule; MAX_SHIPS], name: String, value: Balance, targeting: TargetingType) {
 
-            assert_eq!(self.env().caller(), self.owner.unwrap());
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
             assert!(self.defences.get(&caller).is_none());
+            assert!(self.new_omega_game.is_selection_active(selection), "Ship retired");
 
             self.defences.insert(caller, PlayerDefence {
                 selection,
@@ -115,6 +195,11 @@ mod newomegaranked {
                 wins: 0,
                 losses: 0,
             });
+
+            self.env().emit_event(DefenceRegistered {
+                player: caller,
+                value,
+            });
         }
 
         /// Unregisters a fleet for Ranked Defence.
@@ -125,7 +210,7 @@ mod newomegaranked {
         /// * `caller` - The account id of the player to register the defence for
         #[ink(message)]
         pub fn unregister_defence(&mut self, caller: AccountId) -> Balance {
-            assert_eq!(self.env().caller(), self.owner.unwrap());
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
             assert!(self.defences.get(&caller).is_some());
 
             let defence: &PlayerDefence = self.defences.get(&caller).unwrap();
@@ -133,6 +218,11 @@ mod newomegaranked {
 
             self.defences.take(&caller);
 
+            self.env().emit_event(DefenceWithdrawn {
+                player: caller,
+                value: defence_balance,
+            });
+
             defence_balance
         }
 
@@ -148,7 +238,7 @@ mod newomegaranked {
         /// * `defence` - The registered defence
         #[ink(message)]
         pub fn get_own_defence(&self, caller: AccountId) -> PlayerDefence {
-            assert_eq!(self.env().caller(), self.owner.unwrap());
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
             assert!(self.defences.get(&caller).is_some());
 
             let defence: &PlayerDefence = self.defences.get(&caller).unwrap();
@@ -173,10 +263,7 @@ mod newomegaranked {
         pub fn get_all_defenders(&self) -> Vec<(AccountId, PlayerDefence)> {
             self.defences
                 .iter()
-                .filter_map(|entry| {
-                    let (&key, value) = entry;
-                    Some((key, value.clone()))
-                })
+                .map(|(&key, value)| (key, value.clone()))
                 .collect()
         }
 
@@ -194,6 +281,8 @@ mod newomegaranked {
         }
 
         /// Calculates a ranked fight between two players.
+        /// Only a won fight (see Outcome) is paid out and recorded: a Draw or a mutual
+        /// destruction leaves both defences as they were.
         ///
         /// # Arguments
         ///
@@ -201,11 +290,17 @@ mod newomegaranked {
         /// * `target` - account id of the defender
         /// * `selection` - Attacker fleet composition (array with ship quantities)
         /// * `modules` - An array that holds modules of the attacker fleet
+        ///
+        /// # Returns
+        ///
+        /// * `result` - The fight result
+        /// * `payout` - The amount won (by either side), 0 if nobody won
         #[ink(message)]
         pub fn attack(&mut self, caller: AccountId, target: AccountId, selection: [u8; MAX_SHIPS],
             modules: [ShipModule; MAX_SHIPS], value: Balance, targeting: TargetingType) -> (FightResult, Balance) {
 
-            assert_eq!(self.env().caller(), self.owner.unwrap());
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.new_omega_game.is_selection_active(selection), "Ship retired");
             // Try to get the defence
             let target_defence: PlayerDefence = self.get_defence(target).clone();
             let caller_defence: PlayerDefence = self.get_defence(caller).clone();
@@ -221,11 +316,12 @@ mod newomegaranked {
                     modules,
                     target_defence.modules,
                     targeting,
-                    target_defence.targeting);
+                    target_defence.targeting,
+                    *self.fight_settings);
 
             let mut payout: Balance = self.min(value, target_defence.value);
 
-            if result.lhs_dead {
+            if result.outcome == Outcome::DefenderWin {
                 self.new_omega_storage.mark_ranked_win(target);
                 self.new_omega_storage.mark_ranked_loss(caller);
                 self.defences.insert(target, PlayerDefence {
@@ -237,10 +333,10 @@ mod newomegaranked {
                     losses: target_defence.losses,
                     targeting: target_defence.targeting,
                 });
-            } else if result.rhs_dead {
+            } else if result.outcome == Outcome::AttackerWin {
                 self.new_omega_storage.mark_ranked_win(caller);
                 self.new_omega_storage.mark_ranked_loss(target);
-                payout = payout / 2;
+                payout /= 2;
                 self.defences.insert(target, PlayerDefence {
                     selection: target_defence.selection,
                     modules: target_defence.modules,
@@ -259,9 +355,140 @@ mod newomegaranked {
                     losses: caller_defence.losses,
                     targeting: caller_defence.targeting,
                 });
+                self.emit_defence_updated(caller);
+            } else {
+                // Draw or mutual destruction, nobody wins
+                payout = 0;
+            }
+
+            if result.outcome == Outcome::DefenderWin || result.outcome == Outcome::AttackerWin {
+                self.emit_defence_updated(target);
             }
 
             (result, payout)
         }
+
+        /// Emits the DefenceUpdated event with the current state of a players defence
+        ///
+        /// # Arguments
+        ///
+        /// * `player` - The player whose defence changed
+        fn emit_defence_updated(&self, player: AccountId) {
+            let defence: &PlayerDefence = self.get_defence(player);
+            self.env().emit_event(DefenceUpdated {
+                player,
+                value: defence.value,
+                wins: defence.wins,
+                losses: defence.losses,
+            });
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use newomega::TieBreak;
+        use ink_env::{
+            test,
+            DefaultEnvironment,
+        };
+        use ink_lang as ink;
+        type Accounts = test::DefaultAccounts<DefaultEnvironment>;
+
+        fn default_accounts() -> Accounts {
+            test::default_accounts()
+                .expect("Test environment is expected to be initialized.")
+        }
+
+        fn prepare_contract(accounts: &Accounts) -> NewOmegaRanked {
+            let mut contract = NewOmegaRanked::new(NewOmegaGame::default(), NewOmegaStorage::default());
+            contract.authorise_delegator(accounts.alice);
+            let modules: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+            contract.register_defence(accounts.alice, [10, 10, 10, 10], modules,
+                String::from("alice"), 100, TargetingType::default());
+            contract.register_defence(accounts.bob, [10, 10, 10, 10], modules,
+                String::from("bob"), 100, TargetingType::default());
+            contract
+        }
+
+        #[ink::test]
+        fn test_attack_win() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+
+            let (result, payout) = contract.attack(accounts.bob, accounts.alice, [20, 20, 20, 20],
+                [ShipModule::default(); MAX_SHIPS], 100, TargetingType::default());
+
+            assert!(result.rhs_dead && !result.lhs_dead);
+            assert_eq!(payout, 50);
+            assert_eq!(contract.get_defence(accounts.alice).value, 50);
+            assert_eq!(contract.get_defence(accounts.alice).losses, 1);
+            assert_eq!(contract.get_defence(accounts.bob).value, 150);
+            assert_eq!(contract.new_omega_storage.get_ranked_record(accounts.bob), (1, 0));
+            assert_eq!(contract.new_omega_storage.get_ranked_record(accounts.alice), (0, 1));
+        }
+
+        #[ink::test]
+        fn test_attack_loss() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+
+            let (result, payout) = contract.attack(accounts.bob, accounts.alice, [5, 5, 5, 5],
+                [ShipModule::default(); MAX_SHIPS], 40, TargetingType::default());
+
+            assert!(result.lhs_dead && !result.rhs_dead);
+            assert_eq!(payout, 40);
+            assert_eq!(contract.get_defence(accounts.alice).value, 140);
+            assert_eq!(contract.get_defence(accounts.alice).wins, 1);
+            assert_eq!(contract.new_omega_storage.get_ranked_record(accounts.alice), (1, 0));
+            assert_eq!(contract.new_omega_storage.get_ranked_record(accounts.bob), (0, 1));
+        }
+
+        #[ink::test]
+        fn test_attack_draw() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+            let settings: FightSettings = FightSettings {
+                max_rounds: 1,
+                tie_break: TieBreak::None,
+                hp_bonus_rhs: 0,
+            };
+            contract.set_fight_settings(settings);
+
+            let (result, payout) = contract.attack(accounts.bob, accounts.alice, [20, 20, 20, 20],
+                [ShipModule::default(); MAX_SHIPS], 100, TargetingType::default());
+
+            assert_eq!(result.outcome, Outcome::Draw);
+            assert_eq!(contract.get_fight_settings(), settings);
+            assert_eq!(payout, 0);
+            assert_eq!(contract.get_defence(accounts.alice).value, 100);
+            assert_eq!(contract.get_defence(accounts.bob).value, 100);
+            assert_eq!(contract.new_omega_storage.get_ranked_record(accounts.alice), (0, 0));
+            assert_eq!(contract.new_omega_storage.get_ranked_record(accounts.bob), (0, 0));
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_set_fight_settings_zero_rounds() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+
+            contract.set_fight_settings(FightSettings {
+                max_rounds: 0,
+                tie_break: TieBreak::None,
+                hp_bonus_rhs: 0,
+            });
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_attack_retired_ship() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+            contract.new_omega_game.retire_ship(0);
+
+            contract.attack(accounts.bob, accounts.alice, [20, 20, 20, 20],
+                [ShipModule::default(); MAX_SHIPS], 100, TargetingType::default());
+        }
     }
 }
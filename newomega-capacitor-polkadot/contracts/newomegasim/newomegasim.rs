@@ -0,0 +1,560 @@
+//! Native fight simulator. Runs the New Omega fight engine locally, so ships can be
+//! balanced without deploying the contracts and calling ```replay``` over RPC.
+//! The fight is read from a JSON or TOML file, and / or from flags (flags take precedence).
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process;
+use newomegaengine::FightEngine;
+use newomegaengine::FightSettings;
+use newomegaengine::FleetStats;
+use newomegaengine::FightResult;
+use newomegaengine::Move;
+use newomegaengine::Ship;
+use newomegaengine::ShipModule;
+use newomegaengine::TargetingType;
+use newomegaengine::TieBreak;
+use newomegaengine::MAX_ROUNDS;
+use newomegaengine::MAX_SHIPS;
+use newomegaengine::MOVE_TYPE_SHOOT;
+use newomegaengine::RULES_VERSION;
+use serde::Deserialize;
+use serde_json::json;
+use serde_json::Value;
+use structopt::StructOpt;
+
+/// All the targeting types, by name
+const TARGETING_TYPES: [(&str, TargetingType); 10] = [
+    ("furthest", TargetingType::Furthest),
+    ("closest", TargetingType::Closest),
+    ("highesthp", TargetingType::HighestHp),
+    ("lowesthp", TargetingType::LowestHp),
+    ("highestspeed", TargetingType::HighestSpeed),
+    ("lowestspeed", TargetingType::LowestSpeed),
+    ("highestdefence", TargetingType::HighestDefence),
+    ("lowestdefence", TargetingType::LowestDefence),
+    ("highestattack", TargetingType::HighestAttack),
+    ("lowestattack", TargetingType::LowestAttack),
+];
+
+/// All the tie-breaks, by name
+const TIE_BREAKS: [(&str, TieBreak); 3] = [
+    ("none", TieBreak::None),
+    ("hppercentage", TieBreak::HpPercentage),
+    ("cppercentage", TieBreak::CpPercentage),
+];
+
+#[derive(StructOpt)]
+#[structopt(name = "newomegasim", about = "Simulates a New Omega fight locally")]
+struct Options {
+    /// Fight definition file (.json or .toml)
+    #[structopt(short, long, parse(from_os_str))]
+    config: Option<PathBuf>,
+    /// Seed used to generate randomness
+    #[structopt(long)]
+    seed: Option<u64>,
+    /// Version of the fight rules (the current one if not given)
+    #[structopt(long)]
+    rules_version: Option<u16>,
+    /// Attacker fleet composition, e.g. 20,20,20,20
+    #[structopt(long, parse(try_from_str = parse_selection))]
+    selection_lhs: Option<[u8; MAX_SHIPS]>,
+    /// Defender fleet composition, e.g. 5,5,5,5
+    #[structopt(long, parse(try_from_str = parse_selection))]
+    selection_rhs: Option<[u8; MAX_SHIPS]>,
+    /// Attacker targeting, e.g. Closest
+    #[structopt(long, parse(try_from_str = parse_targeting))]
+    targeting_lhs: Option<TargetingType>,
+    /// Defender targeting, e.g. HighestHp
+    #[structopt(long, parse(try_from_str = parse_targeting))]
+    targeting_rhs: Option<TargetingType>,
+    /// Maximum length of the fight, in rounds
+    #[structopt(long)]
+    max_rounds: Option<u8>,
+    /// Tie-break of a fight reaching the maximum rounds, e.g. HpPercentage (None if not given)
+    #[structopt(long, parse(try_from_str = parse_tie_break))]
+    tie_break: Option<TieBreak>,
+    /// Print the result and the moves as JSON
+    #[structopt(long)]
+    json: bool,
+}
+
+/// Fight definition, as read from a file. Everything is optional, so the flags can fill in the rest.
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FightConfig {
+    seed: Option<u64>,
+    rules_version: Option<u16>,
+    ships: Option<Vec<ShipConfig>>,
+    selection_lhs: Option<[u8; MAX_SHIPS]>,
+    selection_rhs: Option<[u8; MAX_SHIPS]>,
+    modules_lhs: Option<Vec<ModuleConfig>>,
+    modules_rhs: Option<Vec<ModuleConfig>>,
+    targeting_lhs: Option<String>,
+    targeting_rhs: Option<String>,
+    max_rounds: Option<u8>,
+    tie_break: Option<String>,
+}
+
+/// Ship definition, as read from a file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShipConfig {
+    cp: u16,
+    hp: u16,
+    attack_base: u16,
+    attack_variable: u16,
+    defence: u16,
+    speed: u8,
+    range: u8,
+}
+
+/// Ship module, as read from a file (missing effects are 0)
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ModuleConfig {
+    snare: u8,
+    root: u8,
+    blind: u8,
+    attack_debuff: u8,
+    defence_debuff: u8,
+    range_debuff: u8,
+}
+
+/// A fully defined fight, ready to be run
+struct Fight {
+    seed: u64,
+    rules_version: u16,
+    ships: Vec<Ship>,
+    selection_lhs: [u8; MAX_SHIPS],
+    selection_rhs: [u8; MAX_SHIPS],
+    modules_lhs: [ShipModule; MAX_SHIPS],
+    modules_rhs: [ShipModule; MAX_SHIPS],
+    targeting_lhs: TargetingType,
+    targeting_rhs: TargetingType,
+    settings: FightSettings,
+}
+
+/// Parses a fleet composition, given as comma separated ship quantities
+fn parse_selection(value: &str) -> Result<[u8; MAX_SHIPS], String> {
+    let quantities: Vec<u8> = value
+        .split(',')
+        .map(|quantity| quantity.trim().parse::<u8>()
+            .map_err(|_| format!("Invalid ship quantity: {}", quantity)))
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    if quantities.len() != MAX_SHIPS {
+        return Err(format!("Expected {} ship quantities, got {}", MAX_SHIPS, quantities.len()));
+    }
+
+    let mut selection: [u8; MAX_SHIPS] = [0; MAX_SHIPS];
+    selection.copy_from_slice(&quantities);
+    Ok(selection)
+}
+
+/// Parses a targeting type by name (case and underscores are ignored, e.g. highest_hp)
+fn parse_targeting(value: &str) -> Result<TargetingType, String> {
+    let name: String = value.to_lowercase().replace('_', "");
+    TARGETING_TYPES
+        .iter()
+        .find(|(targeting_name, _)| *targeting_name == name)
+        .map(|(_, targeting)| *targeting)
+        .ok_or_else(|| format!("Unknown targeting: {}", value))
+}
+
+/// Parses a tie-break by name (case and underscores are ignored, e.g. hp_percentage)
+fn parse_tie_break(value: &str) -> Result<TieBreak, String> {
+    let name: String = value.to_lowercase().replace('_', "");
+    TIE_BREAKS
+        .iter()
+        .find(|(tie_break_name, _)| *tie_break_name == name)
+        .map(|(_, tie_break)| *tie_break)
+        .ok_or_else(|| format!("Unknown tie-break: {}", value))
+}
+
+/// Converts the modules read from a file, missing modules are empty
+fn parse_modules(modules: Option<Vec<ModuleConfig>>) -> Result<[ShipModule; MAX_SHIPS], String> {
+    let mut result: [ShipModule; MAX_SHIPS] = [ShipModule::default(); MAX_SHIPS];
+    if let Some(modules) = modules {
+        if modules.len() != MAX_SHIPS {
+            return Err(format!("Expected {} modules, got {}", MAX_SHIPS, modules.len()));
+        }
+        for (i, module) in modules.iter().enumerate() {
+            result[i] = ShipModule {
+                snare: module.snare,
+                root: module.root,
+                blind: module.blind,
+                attack_debuff: module.attack_debuff,
+                defence_debuff: module.defence_debuff,
+                range_debuff: module.range_debuff,
+            };
+        }
+    }
+    Ok(result)
+}
+
+/// Converts the ships read from a file, defaulting to the game ships
+fn parse_ships(ships: Option<Vec<ShipConfig>>) -> Result<Vec<Ship>, String> {
+    let ships: Vec<Ship> = match ships {
+        Some(ships) => ships
+            .iter()
+            .map(|ship| Ship {
+                cp: ship.cp,
+                hp: ship.hp,
+                attack_base: ship.attack_base,
+                attack_variable: ship.attack_variable,
+                defence: ship.defence,
+                speed: ship.speed,
+                range: ship.range,
+            })
+            .collect(),
+        None => newomegaengine::prepare_ships(),
+    };
+
+    if ships.len() != MAX_SHIPS {
+        return Err(format!("Expected {} ships, got {}", MAX_SHIPS, ships.len()));
+    }
+    newomegaengine::validate_ships(&ships)
+        .map_err(|error| format!("Invalid ships: {:?}", error))?;
+
+    Ok(ships)
+}
+
+/// Reads the fight definition file, JSON or TOML depending on the extension
+fn read_config(path: &Path) -> Result<FightConfig, String> {
+    let contents: String = fs::read_to_string(path)
+        .map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => serde_json::from_str(&contents)
+            .map_err(|error| format!("Invalid JSON in {}: {}", path.display(), error)),
+        Some("toml") => toml::from_str(&contents)
+            .map_err(|error| format!("Invalid TOML in {}: {}", path.display(), error)),
+        _ => Err(format!("Unsupported file type: {}", path.display())),
+    }
+}
+
+/// Builds the fight from the file and the flags, the flags take precedence
+fn resolve_fight(options: &Options, config: FightConfig) -> Result<Fight, String> {
+    let targeting_lhs: Option<TargetingType> = match &config.targeting_lhs {
+        Some(name) => Some(parse_targeting(name)?),
+        None => None,
+    };
+    let targeting_rhs: Option<TargetingType> = match &config.targeting_rhs {
+        Some(name) => Some(parse_targeting(name)?),
+        None => None,
+    };
+
+    let tie_break: Option<TieBreak> = match &config.tie_break {
+        Some(name) => Some(parse_tie_break(name)?),
+        None => None,
+    };
+
+    let rules_version: u16 = options.rules_version.or(config.rules_version).unwrap_or(RULES_VERSION);
+    if rules_version == 0 || rules_version > RULES_VERSION {
+        return Err(format!("Unsupported rules version: {}", rules_version));
+    }
+    let max_rounds: u8 = options.max_rounds.or(config.max_rounds).unwrap_or(MAX_ROUNDS);
+    if max_rounds == 0 {
+        return Err(String::from("The maximum rounds have to be positive"));
+    }
+
+    Ok(Fight {
+        seed: options.seed.or(config.seed).unwrap_or(0),
+        rules_version,
+        ships: parse_ships(config.ships)?,
+        selection_lhs: options.selection_lhs.or(config.selection_lhs)
+            .ok_or("Missing the attacker selection")?,
+        selection_rhs: options.selection_rhs.or(config.selection_rhs)
+            .ok_or("Missing the defender selection")?,
+        modules_lhs: parse_modules(config.modules_lhs)?,
+        modules_rhs: parse_modules(config.modules_rhs)?,
+        targeting_lhs: options.targeting_lhs.or(targeting_lhs).unwrap_or_default(),
+        targeting_rhs: options.targeting_rhs.or(targeting_rhs).unwrap_or_default(),
+        settings: FightSettings {
+            max_rounds,
+            tie_break: options.tie_break.or(tie_break).unwrap_or_default(),
+            hp_bonus_rhs: 0,
+        },
+    })
+}
+
+/// Merges the moves of both sides into a single log, in the order the engine plays them:
+/// by round, then by ship type, with the defender moving before the attacker
+fn merge_moves(moves_lhs: Vec<Move>, moves_rhs: Vec<Move>) -> Vec<(&'static str, Move)> {
+    let mut moves: Vec<(&'static str, Move)> = moves_lhs
+        .into_iter()
+        .map(|fight_move| ("attacker", fight_move))
+        .chain(moves_rhs.into_iter().map(|fight_move| ("defender", fight_move)))
+        .collect();
+    moves.sort_by_key(|(side, fight_move)| (fight_move.round, fight_move.source, *side == "attacker"));
+    moves
+}
+
+fn move_type_name(fight_move: &Move) -> &'static str {
+    if fight_move.move_type == MOVE_TYPE_SHOOT {
+        "shoot"
+    } else {
+        "reposition"
+    }
+}
+
+fn stats_json(stats: &FleetStats) -> Value {
+    json!({
+        "damage_dealt": stats.damage_dealt,
+        "damage_received": stats.damage_received,
+        "shots_fired": stats.shots_fired,
+        "effects_applied": stats.effects_applied,
+        "kills": stats.kills,
+        "hp_remaining": stats.hp_remaining,
+    })
+}
+
+fn print_json(result: &FightResult, moves: &[(&'static str, Move)]) {
+    let moves: Vec<Value> = moves
+        .iter()
+        .map(|(side, fight_move)| json!({
+            "round": fight_move.round,
+            "side": side,
+            "move_type": move_type_name(fight_move),
+            "source": fight_move.source,
+            "target": fight_move.target,
+            "target_position": fight_move.target_position,
+            "damage": fight_move.damage,
+        }))
+        .collect();
+
+    let output: Value = json!({
+        "result": {
+            "seed": result.seed,
+            "rules_version": result.rules_version,
+            "rounds": result.rounds,
+            "selection_lhs": result.selection_lhs,
+            "selection_rhs": result.selection_rhs,
+            "targeting_lhs": format!("{:?}", result.targeting_lhs),
+            "targeting_rhs": format!("{:?}", result.targeting_rhs),
+            "lhs_dead": result.lhs_dead,
+            "rhs_dead": result.rhs_dead,
+            "outcome": format!("{:?}", result.outcome),
+            "max_rounds": result.settings.max_rounds,
+            "tie_break": format!("{:?}", result.settings.tie_break),
+            "ships_lost_lhs": result.ships_lost_lhs,
+            "ships_lost_rhs": result.ships_lost_rhs,
+            "stats_lhs": stats_json(&result.stats_lhs),
+            "stats_rhs": stats_json(&result.stats_rhs),
+        },
+        "moves": moves,
+    });
+
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+fn print_stats(side: &str, stats: &FleetStats) {
+    println!();
+    println!("{} stats, per ship type:", side);
+    println!("  Damage dealt:         {:?}", stats.damage_dealt);
+    println!("  Damage received:      {:?}", stats.damage_received);
+    println!("  Shots fired:          {:?}", stats.shots_fired);
+    println!("  Effects applied:      {:?}", stats.effects_applied);
+    println!("  Kills (by target):    {:?}", stats.kills);
+    println!("  HP remaining:         {:?}", stats.hp_remaining);
+}
+
+fn print_table(result: &FightResult, moves: &[(&'static str, Move)]) {
+    println!("Seed:                   {}", result.seed);
+    println!("Rules version:          {}", result.rules_version);
+    println!("Rounds:                 {} (of {}, tie-break {:?})", result.rounds,
+        result.settings.max_rounds, result.settings.tie_break);
+    println!("Attacker selection:     {:?} ({:?})", result.selection_lhs, result.targeting_lhs);
+    println!("Defender selection:     {:?} ({:?})", result.selection_rhs, result.targeting_rhs);
+    println!("Attacker dead:          {}", result.lhs_dead);
+    println!("Defender dead:          {}", result.rhs_dead);
+    println!("Outcome:                {:?}", result.outcome);
+    println!("Attacker ships lost:    {:?}", result.ships_lost_lhs);
+    println!("Defender ships lost:    {:?}", result.ships_lost_rhs);
+    print_stats("Attacker", &result.stats_lhs);
+    print_stats("Defender", &result.stats_rhs);
+    println!();
+    println!("{:>5}  {:<8}  {:<10}  {:>4}  {:>6}  {:>8}  {:>6}",
+        "Round", "Side", "Move", "Ship", "Target", "Position", "Damage");
+
+    for (side, fight_move) in moves.iter() {
+        let (target, damage): (String, String) = if fight_move.move_type == MOVE_TYPE_SHOOT {
+            (fight_move.target.to_string(), fight_move.damage.to_string())
+        } else {
+            (String::from("-"), String::from("-"))
+        };
+        println!("{:>5}  {:<8}  {:<10}  {:>4}  {:>6}  {:>8}  {:>6}",
+            fight_move.round, side, move_type_name(fight_move), fight_move.source,
+            target, fight_move.target_position, damage);
+    }
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let config: FightConfig = match &options.config {
+        Some(path) => read_config(path)?,
+        None => FightConfig::default(),
+    };
+    let fight: Fight = resolve_fight(&options, config)?;
+
+    let (result, moves_lhs, moves_rhs) = FightEngine::new(fight.rules_version).fight(fight.seed,
+        true, fight.ships, fight.selection_lhs, fight.selection_rhs, fight.modules_lhs,
+        fight.modules_rhs, fight.targeting_lhs, fight.targeting_rhs, fight.settings);
+    let moves: Vec<(&'static str, Move)> =
+        merge_moves(moves_lhs.unwrap_or_default(), moves_rhs.unwrap_or_default());
+
+    if options.json {
+        print_json(&result, &moves);
+    } else {
+        print_table(&result, &moves);
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(error) = run(Options::from_args()) {
+        eprintln!("Error: {}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use newomegaengine::RunningEffect;
+
+    fn options_from(args: &[&str]) -> Options {
+        Options::from_iter(["newomegasim"].iter().chain(args.iter()))
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("20,20, 5,0"), Ok([20, 20, 5, 0]));
+        assert!(parse_selection("20,20,20").is_err());
+        assert!(parse_selection("20,20,20,300").is_err());
+    }
+
+    #[test]
+    fn test_parse_targeting() {
+        assert_eq!(parse_targeting("Closest"), Ok(TargetingType::Closest));
+        assert_eq!(parse_targeting("highest_hp"), Ok(TargetingType::HighestHp));
+        assert!(parse_targeting("nearest").is_err());
+    }
+
+    #[test]
+    fn test_parse_tie_break() {
+        assert_eq!(parse_tie_break("None"), Ok(TieBreak::None));
+        assert_eq!(parse_tie_break("hp_percentage"), Ok(TieBreak::HpPercentage));
+        assert_eq!(parse_tie_break("CpPercentage"), Ok(TieBreak::CpPercentage));
+        assert!(parse_tie_break("ships").is_err());
+    }
+
+    #[test]
+    fn test_max_rounds() {
+        let config: FightConfig = toml::from_str(r#"
+            selection_lhs = [20, 20, 20, 20]
+            selection_rhs = [5, 5, 5, 5]
+            max_rounds = 10
+            tie_break = "HpPercentage"
+        "#).unwrap();
+        let fight: Fight = resolve_fight(&options_from(&["--max-rounds", "1"]), config).unwrap();
+        assert_eq!(fight.settings.max_rounds, 1);
+        assert_eq!(fight.settings.tie_break, TieBreak::HpPercentage);
+
+        let options: Options = options_from(&["--selection-lhs", "1,1,1,1", "--selection-rhs",
+            "1,1,1,1", "--max-rounds", "0"]);
+        assert!(resolve_fight(&options, FightConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        let config: FightConfig = toml::from_str(r#"
+            seed = 7
+            selection_lhs = [20, 20, 20, 20]
+            selection_rhs = [5, 5, 5, 5]
+            targeting_lhs = "Closest"
+
+            [[modules_rhs]]
+            snare = 3
+            [[modules_rhs]]
+            [[modules_rhs]]
+            [[modules_rhs]]
+            root = 1
+        "#).unwrap();
+        let options: Options = options_from(&["--seed", "1337", "--selection-rhs", "1,1,1,1"]);
+        let fight: Fight = resolve_fight(&options, config).unwrap();
+
+        assert_eq!(fight.seed, 1337);
+        assert_eq!(fight.rules_version, RULES_VERSION);
+        assert_eq!(fight.settings, FightSettings::default());
+        assert_eq!(fight.selection_lhs, [20, 20, 20, 20]);
+        assert_eq!(fight.selection_rhs, [1, 1, 1, 1]);
+        assert_eq!(fight.targeting_lhs, TargetingType::Closest);
+        assert_eq!(fight.targeting_rhs, TargetingType::Furthest);
+        assert_eq!(fight.modules_rhs[0].snare, 3);
+        assert_eq!(fight.modules_rhs[3].root, 1);
+        assert_eq!(fight.ships, newomegaengine::prepare_ships());
+    }
+
+    #[test]
+    fn test_unsupported_rules_version() {
+        let options: Options = options_from(&["--selection-lhs", "1,1,1,1", "--selection-rhs",
+            "1,1,1,1", "--rules-version", "0"]);
+        assert!(resolve_fight(&options, FightConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_missing_selection() {
+        let options: Options = options_from(&["--selection-lhs", "1,1,1,1"]);
+        assert!(resolve_fight(&options, FightConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_moves_ordered_by_round() {
+        let fight: Fight = resolve_fight(&options_from(&[
+            "--seed", "1337", "--selection-lhs", "20,20,20,20", "--selection-rhs", "5,5,5,5",
+        ]), FightConfig::default()).unwrap();
+        let (result, moves_lhs, moves_rhs) = FightEngine::default().fight(fight.seed, true,
+            fight.ships, fight.selection_lhs, fight.selection_rhs, fight.modules_lhs,
+            fight.modules_rhs, fight.targeting_lhs, fight.targeting_rhs, fight.settings);
+        let moves: Vec<(&'static str, Move)> = merge_moves(moves_lhs.unwrap(), moves_rhs.unwrap());
+
+        assert!(result.rhs_dead);
+        assert!(!moves.is_empty());
+        assert!(moves.windows(2).all(|pair| pair[0].1.round <= pair[1].1.round));
+    }
+
+    fn shoot(round: u8, source: u8) -> Move {
+        Move {
+            move_type: MOVE_TYPE_SHOOT,
+            round,
+            source,
+            target: 0,
+            target_position: 0,
+            damage: 0,
+            effects_lhs: [RunningEffect::default(); MAX_SHIPS],
+            effects_rhs: [RunningEffect::default(); MAX_SHIPS],
+        }
+    }
+
+    #[test]
+    fn test_moves_ordered_by_ship_and_side() {
+        let moves_lhs: Vec<Move> = vec![shoot(0, 0), shoot(0, 2), shoot(1, 0)];
+        let moves_rhs: Vec<Move> = vec![shoot(0, 1), shoot(0, 2), shoot(1, 0)];
+        let moves: Vec<(&'static str, u8, u8)> = merge_moves(moves_lhs, moves_rhs)
+            .iter()
+            .map(|(side, fight_move)| (*side, fight_move.round, fight_move.source))
+            .collect();
+
+        assert_eq!(moves, vec![
+            ("attacker", 0, 0),
+            ("defender", 0, 1),
+            ("defender", 0, 2),
+            ("attacker", 0, 2),
+            ("defender", 1, 0),
+            ("attacker", 1, 0),
+        ]);
+    }
+}
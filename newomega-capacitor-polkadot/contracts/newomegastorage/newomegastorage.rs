@@ -1,4 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+// The ink! constructors take the place of Default
+#![allow(clippy::new_without_default)]
+// Built as a dependency, ink! leaves out the contract implementation and what it uses
+#![cfg_attr(feature = "ink-as-dependency", allow(unused_imports, dead_code))]
 
 use ink_lang as ink;
 pub use self::newomegastorage::NewOmegaStorage;
@@ -11,13 +15,18 @@ pub use self::newomegastorage::RegisteredTrade;
 /// it should survive most upgrades of the rest of the system, preserving the Game Board
 /// (state of the game) across upgrades and bugfixes.
 /// The only logic that belongs here is accessors for the storage.
+#[newomegaaccess::access_control(NewOmegaStorage)]
 #[ink::contract]
 mod newomegastorage {
     use ink_prelude::vec::Vec;
     use newomega::MAX_SHIPS;
+    use newomegaaccess::AccessControl;
+    use newomegaaccess::Role;
+    use newomegaparameters::NewOmegaParameters;
+    use newomegaparameters::GameParameters;
+    use ink_env::call::FromAccountId;
     use ink_storage::{
         collections::{
-            Vec as StorageVec,
             HashMap as StorageHashMap,
         },
         traits::{
@@ -27,8 +36,6 @@ mod newomegastorage {
     };
 
     pub const MAX_MINERALS: usize = 4;
-    pub const STARTING_SHIP_COUNT: u32 = 50;
-    pub const STARTING_MINERAL_COUNT: u32 = 1000;
 
     /// Holds the current leaderboard standing of a player
     #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Default,
@@ -64,24 +71,44 @@ mod newomegastorage {
 
     #[ink(storage)]
     pub struct NewOmegaStorage {
-        creator: AccountId,
-        owners: StorageVec<AccountId>,
+        access: AccessControl,
         players: StorageHashMap<AccountId, PlayerData>,
         minerals: StorageHashMap<AccountId, [u32; MAX_MINERALS]>,
         trades: StorageHashMap<AccountId, [RegisteredTrade; MAX_MINERALS]>,
         ships: StorageHashMap<AccountId, [u32; MAX_SHIPS]>,
+        new_omega_parameters: Option<NewOmegaParameters>,
+    }
+
+    /// Emitted when a player registers (or updates) a trade offer
+    #[ink(event)]
+    pub struct TradeRegistered {
+        #[ink(topic)]
+        player: AccountId,
+        resource_id: u8,
+        trade: RegisteredTrade,
+    }
+
+    /// Emitted when a trade offer is (partially) filled
+    #[ink(event)]
+    pub struct TradeFilled {
+        #[ink(topic)]
+        buyer: AccountId,
+        #[ink(topic)]
+        seller: AccountId,
+        resource_id: u8,
+        trade: RegisteredTrade,
     }
 
     impl NewOmegaStorage {
         #[ink(constructor)]
         pub fn new() -> Self {
             Self {
-                creator: Self::env().caller(),
-                owners: StorageVec::default(),
+                access: AccessControl::new(Self::env().caller()),
                 players: StorageHashMap::default(),
                 minerals: StorageHashMap::default(),
                 trades: StorageHashMap::default(),
                 ships: StorageHashMap::default(),
+                new_omega_parameters: None,
             }
         }
 
@@ -90,48 +117,73 @@ mod newomegastorage {
             Self::new()
         }
 
-        /// Authorises the Delegator contract to fire methods on this one.
+        /// Authorises the Delegator contract to fire methods on this one
+        /// (grants it the Delegator role).
         ///
         /// # Arguments
         ///
         /// * `delegator_address` - AccountId of the Delegator contract
         #[ink(message)]
         pub fn authorise_delegator(&mut self, delegator_address: AccountId) {
-            assert!(self.creator == self.env().caller());
-            self.owners.push(delegator_address);
+            self.grant_role(Role::Delegator, delegator_address);
         }
 
-        /// Authorises the Ranked contract to fire methods on this one.
+        /// Authorises the Ranked contract to fire methods on this one
+        /// (grants it the GameContract role).
         ///
         /// # Arguments
         ///
         /// * `ranked_address` - AccountId of the Ranked contract
         #[ink(message)]
         pub fn authorise_ranked_contract(&mut self, ranked_address: AccountId) {
-            assert!(self.creator == self.env().caller());
-            self.owners.push(ranked_address);
+            self.grant_role(Role::GameContract, ranked_address);
         }
 
-        /// Authorises the Universe contract to fire methods on this one.
+        /// Authorises the Universe contract to fire methods on this one
+        /// (grants it the GameContract role).
         ///
         /// # Arguments
         ///
         /// * `universe_address` - AccountId of the Universe contract
         #[ink(message)]
         pub fn authorise_universe_contract(&mut self, universe_address: AccountId) {
-            assert!(self.creator == self.env().caller());
-            self.owners.push(universe_address);
+            self.grant_role(Role::GameContract, universe_address);
         }
 
-        /// Authorises the Industrial contract to fire methods on this one.
+        /// Authorises the Industrial contract to fire methods on this one
+        /// (grants it the GameContract role).
         ///
         /// # Arguments
         ///
-        /// * `industrial_address` - AccountId of the Delegator contract
+        /// * `industrial_address` - AccountId of the Industrial contract
         #[ink(message)]
         pub fn authorise_industrial_contract(&mut self, industrial_address: AccountId) {
-            assert!(self.creator == self.env().caller());
-            self.owners.push(industrial_address);
+            self.grant_role(Role::GameContract, industrial_address);
+        }
+
+        /// Sets the Parameters contract, which holds the starting ship and mineral counts.
+        /// Kept as a setter (rather than a constructor argument), so that the Storage does not
+        /// need to be redeployed when the Parameters contract is.
+        ///
+        /// # Arguments
+        ///
+        /// * `parameters_address` - AccountId of the Parameters contract
+        #[ink(message)]
+        pub fn set_parameters_contract(&mut self, parameters_address: AccountId) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            self.new_omega_parameters = Some(NewOmegaParameters::from_account_id(parameters_address));
+        }
+
+        /// Gets the game parameters, from the Parameters contract if there is one
+        ///
+        /// # Returns
+        ///
+        /// * `parameters` - The game parameters
+        fn get_parameters(&self) -> GameParameters {
+            match &self.new_omega_parameters {
+                Some(new_omega_parameters) => new_omega_parameters.get_parameters(),
+                None => GameParameters::default(),
+            }
         }
 
         /// Ensures that the player has a minerals entry
@@ -140,9 +192,10 @@ mod newomegastorage {
         ///
         /// * `caller` - AccountId of the player to ensure
         pub fn ensure_minerals_internal(&mut self, caller: AccountId) -> &mut [u32; MAX_MINERALS] {
+            let starting_count: u32 = self.get_parameters().starting_mineral_count;
             self.minerals
                 .entry(caller)
-                .or_insert([STARTING_MINERAL_COUNT; MAX_MINERALS])
+                .or_insert([starting_count; MAX_MINERALS])
         }
 
         /// Ensures that the player has a trades entry
@@ -162,9 +215,10 @@ mod newomegastorage {
         ///
         /// * `caller` - AccountId of the player to ensure
         pub fn ensure_ships_internal(&mut self, caller: AccountId) -> &mut [u32; MAX_SHIPS] {
+            let starting_count: u32 = self.get_parameters().starting_ship_count;
             self.ships
                 .entry(caller)
-                .or_insert([STARTING_SHIP_COUNT; MAX_SHIPS])
+                .or_insert([starting_count; MAX_SHIPS])
         }
 
         /// Ensures that the player has a minerals entry
@@ -174,6 +228,7 @@ mod newomegastorage {
         /// * `caller` - AccountId of the player to ensure
         #[ink(message)]
         pub fn ensure_minerals(&mut self, caller: AccountId) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             self.ensure_minerals_internal(caller);
         }
 
@@ -184,6 +239,7 @@ mod newomegastorage {
         /// * `caller` - AccountId of the player to ensure
         #[ink(message)]
         pub fn ensure_trades(&mut self, caller: AccountId) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             self.ensure_trades_internal(caller);
         }
 
@@ -194,6 +250,7 @@ mod newomegastorage {
         /// * `caller` - AccountId of the player to ensure
         #[ink(message)]
         pub fn ensure_ships(&mut self, caller: AccountId) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             self.ensure_ships_internal(caller);
         }
 
@@ -228,13 +285,11 @@ mod newomegastorage {
         /// * `amounts` - The amounts of minerals to add
         #[ink(message)]
         pub fn add_minerals(&mut self, caller: AccountId, amounts: [u32; MAX_MINERALS]) {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             let minerals = self.ensure_minerals_internal(caller);
 
             for i in 0..MAX_MINERALS {
-                minerals[i] += amounts[i];
+                minerals[i] = minerals[i].saturating_add(amounts[i]);
             }
         }
 
@@ -246,9 +301,7 @@ mod newomegastorage {
         /// * `amounts` - The amounts of minerals to remove
         #[ink(message)]
         pub fn remove_minerals(&mut self, caller: AccountId, amounts: [u32; MAX_MINERALS]) {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             let minerals = self.ensure_minerals_internal(caller);
 
             for i in 0..MAX_MINERALS {
@@ -268,9 +321,7 @@ mod newomegastorage {
         /// * `amounts` - The amounts of ships to add
         #[ink(message)]
         pub fn add_ships(&mut self, caller: AccountId, amounts: [u32; MAX_SHIPS]) {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             let ships = self.ensure_ships_internal(caller);
 
             for i in 0..MAX_SHIPS {
@@ -286,9 +337,7 @@ mod newomegastorage {
         /// * `amounts` - The amounts of ships to remove
         #[ink(message)]
         pub fn remove_ships(&mut self, caller: AccountId, amounts: [u32; MAX_SHIPS]) {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             let ships = self.ensure_ships_internal(caller);
 
             for i in 0..MAX_SHIPS {
@@ -311,9 +360,7 @@ mod newomegastorage {
         /// * `ships` - A Selection of ships the player has
         #[ink(message)]
         pub fn get_player_ships(&self, caller: AccountId) -> [u32; MAX_SHIPS] {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             let ships = self.ships.get(&caller);
             assert!(ships.is_some());
             *(ships.unwrap())
@@ -330,9 +377,7 @@ mod newomegastorage {
         /// * `ships` - A Selection of minerals the player has
         #[ink(message)]
         pub fn get_player_minerals(&self, caller: AccountId) -> [u32; MAX_MINERALS] {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             let minerals = self.minerals.get(&caller);
             assert!(minerals.is_some());
             *(minerals.unwrap())
@@ -349,9 +394,7 @@ mod newomegastorage {
         /// * `ships` - A Selection of trades the player has open
         #[ink(message)]
         pub fn get_trades(&self, caller: AccountId) -> [RegisteredTrade; MAX_MINERALS] {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             assert!(self.trades.get(&caller).is_some());
             *(self.trades.get(&caller).unwrap())
         }
@@ -366,9 +409,7 @@ mod newomegastorage {
         /// * `trade` - Trade structure containing which resource to trade for and in which amount
         #[ink(message)]
         pub fn register_trade(&mut self, caller: AccountId, resource_id: u8, trade: RegisteredTrade) {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
 
             let mut trades = self.ensure_trades_internal(caller);
             let resource_id_usize: usize = resource_id as usize;
@@ -387,6 +428,12 @@ mod newomegastorage {
                 let minerals = self.ensure_minerals_internal(caller);
                 minerals[resource_id_usize] += delta;
             }
+
+            self.env().emit_event(TradeRegistered {
+                player: caller,
+                resource_id,
+                trade,
+            });
         }
 
         /// Performs a trade.
@@ -400,9 +447,7 @@ mod newomegastorage {
         /// * `trade` - Trade structure containing which resource to trade for and in which amount
         #[ink(message)]
         pub fn trade(&mut self, caller: AccountId, target: AccountId, resource_id: u8, trade: RegisteredTrade) {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
 
             let mut target_trades = self.ensure_trades_internal(target);
             let resource_id_usize: usize = resource_id as usize;
@@ -422,6 +467,13 @@ mod newomegastorage {
 
             let minerals_target = self.ensure_minerals_internal(target);
             minerals_target[resource_id_usize] += trade.amount;
+
+            self.env().emit_event(TradeFilled {
+                buyer: caller,
+                seller: target,
+                resource_id,
+                trade,
+            });
         }
 
         /// Ensures that a player data structure is defined.
@@ -443,11 +495,9 @@ mod newomegastorage {
         /// * `caller` - The account id of the player to mark
         #[ink(message)]
         pub fn mark_ranked_win(&mut self, caller: AccountId) {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             let player_data = self.ensure_player(caller);
-            player_data.ranked_wins = player_data.ranked_wins + 1;
+            player_data.ranked_wins += 1;
         }
 
         /// Marks a ranked loss for a player
@@ -457,11 +507,9 @@ mod newomegastorage {
         /// * `caller` - The account id of the player to mark
         #[ink(message)]
         pub fn mark_ranked_loss(&mut self, caller: AccountId) {
-            if self.owners.iter().len() > 0 {
-                assert!(self.owners.iter().any(|owner| *owner == self.env().caller()));
-            }
+            self.access.ensure_any_role(self.env().caller(), &[Role::Delegator, Role::GameContract]);
             let player_data = self.ensure_player(caller);
-            player_data.ranked_losses = player_data.ranked_losses + 1;
+            player_data.ranked_losses += 1;
         }
 
         /// Gets the current ranked leaderboard.
@@ -473,10 +521,7 @@ mod newomegastorage {
         pub fn get_leaderboard(&self) -> Vec<(AccountId, PlayerData)> {
             self.players
                 .iter()
-                .filter_map(|entry| {
-                    let (&key, &value) = entry;
-                    Some((key, value))
-                })
+                .map(|(&key, &value)| (key, value))
                 .collect()
         }
 
@@ -506,11 +551,26 @@ mod newomegastorage {
                 .expect("Test environment is expected to be initialized.")
         }
 
+        fn prepare_contract(accounts: &Accounts) -> NewOmegaStorage {
+            let mut contract = NewOmegaStorage::default();
+            contract.authorise_delegator(accounts.alice);
+            contract
+        }
+
         #[ink::test]
-        fn test_ranked_marking() {
+        #[should_panic(expected = "Missing role")]
+        fn test_admin_without_grant() {
             let mut contract = NewOmegaStorage::default();
             let accounts = default_accounts();
 
+            contract.add_minerals(accounts.alice, [10, 11, 12, 13]);
+        }
+
+        #[ink::test]
+        fn test_ranked_marking() {
+            let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
+
             contract.mark_ranked_win(accounts.alice);
             contract.mark_ranked_loss(accounts.bob);
 
@@ -525,58 +585,61 @@ mod newomegastorage {
 
         #[ink::test]
         fn test_minerals() {
-            let mut contract = NewOmegaStorage::default();
+            let starting_minerals: u32 = GameParameters::default().starting_mineral_count;
             let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
 
             contract.add_minerals(accounts.alice, [10, 11, 12, 13]);
             let minerals = contract.get_player_minerals(accounts.alice);
 
-            assert_eq!(minerals[0], STARTING_MINERAL_COUNT + 10);
-            assert_eq!(minerals[1], STARTING_MINERAL_COUNT + 11);
-            assert_eq!(minerals[2], STARTING_MINERAL_COUNT + 12);
-            assert_eq!(minerals[3], STARTING_MINERAL_COUNT + 13);
+            assert_eq!(minerals[0], starting_minerals + 10);
+            assert_eq!(minerals[1], starting_minerals + 11);
+            assert_eq!(minerals[2], starting_minerals + 12);
+            assert_eq!(minerals[3], starting_minerals + 13);
 
             contract.remove_minerals(accounts.alice, [4, 5, 6, 7]);
             let minerals_post_remove = contract.get_player_minerals(accounts.alice);
 
-            assert_eq!(minerals_post_remove[0], STARTING_MINERAL_COUNT + 10 - 4);
-            assert_eq!(minerals_post_remove[1], STARTING_MINERAL_COUNT + 11 - 5);
-            assert_eq!(minerals_post_remove[2], STARTING_MINERAL_COUNT + 12 - 6);
-            assert_eq!(minerals_post_remove[3], STARTING_MINERAL_COUNT + 13 - 7);
+            assert_eq!(minerals_post_remove[0], starting_minerals + 10 - 4);
+            assert_eq!(minerals_post_remove[1], starting_minerals + 11 - 5);
+            assert_eq!(minerals_post_remove[2], starting_minerals + 12 - 6);
+            assert_eq!(minerals_post_remove[3], starting_minerals + 13 - 7);
         }
 
         #[ink::test]
         fn test_ships() {
-            let mut contract = NewOmegaStorage::default();
+            let starting_ships: u32 = GameParameters::default().starting_ship_count;
             let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
 
             contract.add_ships(accounts.alice, [10, 11, 12, 13]);
             let minerals = contract.get_player_ships(accounts.alice);
 
-            assert_eq!(minerals[0], STARTING_SHIP_COUNT + 10);
-            assert_eq!(minerals[1], STARTING_SHIP_COUNT + 11);
-            assert_eq!(minerals[2], STARTING_SHIP_COUNT + 12);
-            assert_eq!(minerals[3], STARTING_SHIP_COUNT + 13);
+            assert_eq!(minerals[0], starting_ships + 10);
+            assert_eq!(minerals[1], starting_ships + 11);
+            assert_eq!(minerals[2], starting_ships + 12);
+            assert_eq!(minerals[3], starting_ships + 13);
 
             contract.remove_ships(accounts.alice, [4, 5, 6, 7]);
             let ships_post_remove = contract.get_player_ships(accounts.alice);
 
-            assert_eq!(ships_post_remove[0], STARTING_SHIP_COUNT + 10 - 4);
-            assert_eq!(ships_post_remove[1], STARTING_SHIP_COUNT + 11 - 5);
-            assert_eq!(ships_post_remove[2], STARTING_SHIP_COUNT + 12 - 6);
-            assert_eq!(ships_post_remove[3], STARTING_SHIP_COUNT + 13 - 7);
+            assert_eq!(ships_post_remove[0], starting_ships + 10 - 4);
+            assert_eq!(ships_post_remove[1], starting_ships + 11 - 5);
+            assert_eq!(ships_post_remove[2], starting_ships + 12 - 6);
+            assert_eq!(ships_post_remove[3], starting_ships + 13 - 7);
 
             assert!(contract.has_enough_ships(accounts.alice, [1, 1, 1, 1]));
         }
 
         #[ink::test]
         fn test_trade() {
-            let mut contract = NewOmegaStorage::default();
+            let starting_minerals: u32 = GameParameters::default().starting_mineral_count;
             let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
 
             contract.add_minerals(accounts.alice, [0, 10, 0, 0]);
             let minerals_alice_before = contract.get_player_minerals(accounts.alice);
-            assert_eq!(minerals_alice_before[1], STARTING_MINERAL_COUNT + 10);
+            assert_eq!(minerals_alice_before[1], starting_minerals + 10);
 
             contract.add_minerals(accounts.bob, [0, 0, 10, 0]);
 
@@ -585,7 +648,7 @@ mod newomegastorage {
                 amount: 6,
             });
             let minerals_alice_middle = contract.get_player_minerals(accounts.alice);
-            assert_eq!(minerals_alice_middle[1], STARTING_MINERAL_COUNT + 4);
+            assert_eq!(minerals_alice_middle[1], starting_minerals + 4);
 
             contract.trade(accounts.bob, accounts.alice, 2, RegisteredTrade {
                 exchange_for: 1,
@@ -593,18 +656,18 @@ mod newomegastorage {
             });
 
             let minerals_alice = contract.get_player_minerals(accounts.alice);
-            assert_eq!(minerals_alice[1], STARTING_MINERAL_COUNT + 4);
-            assert_eq!(minerals_alice[2], STARTING_MINERAL_COUNT + 3);
+            assert_eq!(minerals_alice[1], starting_minerals + 4);
+            assert_eq!(minerals_alice[2], starting_minerals + 3);
             
             let minerals_bob = contract.get_player_minerals(accounts.bob);
-            assert_eq!(minerals_bob[1], STARTING_MINERAL_COUNT + 3);
-            assert_eq!(minerals_bob[2], STARTING_MINERAL_COUNT + 7);
+            assert_eq!(minerals_bob[1], starting_minerals + 3);
+            assert_eq!(minerals_bob[2], starting_minerals + 7);
         }
 
         #[ink::test]
         fn test_ensures() {
-            let mut contract = NewOmegaStorage::default();
             let accounts = default_accounts();
+            let mut contract = prepare_contract(&accounts);
 
             assert!(contract.minerals.get(&accounts.alice).is_none());
             assert!(contract.trades.get(&accounts.alice).is_none());
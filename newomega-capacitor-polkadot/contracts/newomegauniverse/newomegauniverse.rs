@@ -1,25 +1,68 @@
 #![feature(destructuring_assignment)]
 #![cfg_attr(not(feature = "std"), no_std)]
+// The ink! constructors take the place of Default, and attack_planet takes the full fleet setup
+// of the attack (the generated call forwarders do not carry an item level allow)
+#![allow(clippy::new_without_default, clippy::too_many_arguments)]
+// Built as a dependency, ink! leaves out the contract implementation and what it uses
+#![cfg_attr(feature = "ink-as-dependency", allow(unused_imports, dead_code))]
 
 use ink_lang as ink;
 pub use self::newomegauniverse::NewOmegaUniverse;
 pub use self::newomegauniverse::Planet;
 pub use self::newomegauniverse::System;
 pub use self::newomegauniverse::SystemCoordinate;
+pub use self::newomegauniverse::Gateway;
+pub use self::newomegauniverse::GatewayRequest;
+pub use self::newomegauniverse::LegacyGateway;
+pub use self::newomegauniverse::LegacySystem;
+pub use self::newomegauniverse::PlanetCoordinate;
+pub use self::newomegauniverse::EmpireOverview;
+pub use self::newomegauniverse::PendingHarvest;
+pub use self::newomegauniverse::PlanetHarvest;
 pub use self::newomegauniverse::PlayerAssets;
 pub use self::newomegauniverse::GameStats;
-
+pub use self::newomegauniverse::MapBounds;
+pub use self::newomegauniverse::UpgradeCostTable;
+pub use self::newomegauniverse::PlanetTypeDefinition;
+pub use self::newomegauniverse::PlanetSummary;
+pub use self::newomegauniverse::SystemSummary;
+pub use self::newomegauniverse::Visibility;
+pub use self::newomegauniverse::SystemIntel;
+
+#[newomegaaccess::access_control(NewOmegaUniverse)]
 #[ink::contract]
 mod newomegauniverse {
-    use newomegagame::NewOmegaGame;
+    use newomegaaccess::AccessControl;
+    use newomegaaccess::Role;
     use newomega::MAX_SHIPS;
+    use newomega::Ship;
     use newomega::FightResult;
+    use newomega::FightSettings;
+    use newomega::Outcome;
     use newomega::ShipModule;
     use newomega::TargetingType;
-    use newomegastorage::NewOmegaStorage;
     use newomegastorage::MAX_MINERALS;
+    use newomegalayout::Packed;
+    use newomegaparameters::GameParameters;
+    #[cfg(not(test))]
+    use newomegagame::NewOmegaGame;
+    #[cfg(not(test))]
+    use newomegastorage::NewOmegaStorage;
+    #[cfg(not(test))]
+    use newomegaparameters::NewOmegaParameters;
+    // Off-chain tests run against the in-memory Game, Storage and Parameters
+    #[cfg(test)]
+    use newomegainterfaces::{
+        GameInterface,
+        InMemoryGame as NewOmegaGame,
+        InMemoryParameters as NewOmegaParameters,
+        InMemoryStorage as NewOmegaStorage,
+        ParametersInterface,
+        StorageInterface,
+    };
     use ink_prelude::vec::Vec;
     use ink_prelude::string::String;
+    use ink_env::hash::Blake2x256;
     use ink_storage::{
         collections::{
             HashMap as StorageHashMap,
@@ -33,21 +76,39 @@ mod newomegauniverse {
     pub const MAX_PLANETS: usize = 5;
     pub const MAX_PLANET_TYPES: usize = 20;
     pub const PLANETS_FOR_CONTROL: usize = 3;
-    pub const MAX_HARVESTABLE_BLOCKS: BlockNumber = 24000;
-    pub const MINERAL_GENERATION_BLOCKS: BlockNumber = 100;
     pub const START_WITH_PLANETS: u8 = 3;
     pub const MAX_PLANET_LEVEL: u8 = 100;
+    pub const DEFAULT_MAP_SIZE: i32 = 100;
+    pub const MAX_PAGE_SIZE: u32 = 50;
+    pub const MAX_SUMMARY_PAGE_SIZE: u32 = 500;
+    pub const MAX_REGION_AREA: i64 = 100;
+    pub const CP_BUCKET_SIZE: u32 = 50;
+    pub const DEFENCE_BONUS_PER_LEVEL: u16 = 2;
+    pub const BASE_GARRISON_CAPACITY: u32 = 100;
+    pub const GARRISON_CAPACITY_PER_LEVEL: u32 = 20;
+    /// Upper bound for the planet type harvest multipliers, in percent
+    pub const MAX_HARVEST_MULTIPLIER: u16 = 1000;
+    /// Upper bound for the planet type defence bonus, in percent
+    pub const MAX_DEFENCE_BONUS: u16 = 500;
+    /// The most ships a garrison selection can hold
+    pub const MAX_GARRISON: u32 = MAX_SHIPS as u32 * u8::MAX as u32;
+    /// Planet garrison CP is not revealed without full visibility
+    pub const PLANET_ABILITY_STEALTH: u8 = 1 << 0;
+    /// Planet accumulates minerals for twice as long between harvests
+    pub const PLANET_ABILITY_DEEP_CORE: u8 = 1 << 1;
+    pub const LEVELS_PER_GATEWAY_SLOT: u8 = 10;
+    pub const MAX_GATEWAY_SLOTS: u32 = 4;
+    pub const MAX_GATEWAY_REQUESTS: usize = 20;
 
     /// Describes a registered defence of a player
-    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[derive(scale::Encode, scale::Decode, Clone)]
     #[cfg_attr(
         feature = "std",
         derive(
             Debug,
             PartialEq,
             Eq,
-            scale_info::TypeInfo,
-            ink_storage::traits::StorageLayout
+            scale_info::TypeInfo
         )
     )]
     pub struct Planet {
@@ -73,6 +134,9 @@ mod newomegauniverse {
         name: Option<String>,
     }
 
+    // Holds the garrison modules and targeting, which are fight engine types
+    newomegalayout::packed_layout!(Planet);
+
     impl Default for Planet {
         fn default() -> Self {
             Planet {
@@ -126,7 +190,18 @@ mod newomegauniverse {
         position_y: i32,
     }
 
-    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Default)]
+    /// Storage key of a System, (root, position_x, position_y)
+    pub type SystemKey = (AccountId, i32, i32);
+
+    impl SystemCoordinate {
+        /// Gets the storage key of the System at this coordinate
+        pub fn key(&self) -> SystemKey {
+            (self.root, self.position_x, self.position_y)
+        }
+    }
+
+    /// Describes the location of a single planet
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Default, Copy)]
     #[cfg_attr(
         feature = "std",
         derive(
@@ -137,12 +212,15 @@ mod newomegauniverse {
             ink_storage::traits::StorageLayout
         )
     )]
-    pub struct Gateway {
-        built: bool,
-        target: SystemCoordinate,
+    pub struct PlanetCoordinate {
+        /// Position of the system the planet is in
+        system: SystemCoordinate,
+        /// Which planet within the system (0..MAX_PLANETS)
+        planet_id: u8,
     }
 
-    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    /// Describes the bounds of a Universe map (inclusive), shared by all the players
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy)]
     #[cfg_attr(
         feature = "std",
         derive(
@@ -153,20 +231,32 @@ mod newomegauniverse {
             ink_storage::traits::StorageLayout
         )
     )]
-    pub struct System {
-        /// Position of the system
-        position: SystemCoordinate,
-        /// Gateway in
-        gateway_in: Gateway,
-        /// Gateway out
-        gateway_out: Gateway,
-        /// Planets
-        planets: Vec<Planet>,
-        /// Discoverer
-        discoverer: AccountId,
+    pub struct MapBounds {
+        /// Lowest allowed x coordinate
+        min_x: i32,
+        /// Highest allowed x coordinate
+        max_x: i32,
+        /// Lowest allowed y coordinate
+        min_y: i32,
+        /// Highest allowed y coordinate
+        max_y: i32,
     }
 
-    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    impl Default for MapBounds {
+        fn default() -> Self {
+            MapBounds {
+                min_x: 0,
+                max_x: DEFAULT_MAP_SIZE - 1,
+                min_y: 0,
+                max_y: DEFAULT_MAP_SIZE - 1,
+            }
+        }
+    }
+
+    /// Describes the mineral cost of planet upgrades. Upgrading a planet from level L costs
+    /// (base_cost + cost_per_level * L + cost_per_level_squared * L^2) minerals of the planet
+    /// mineral type, adjusted by the upgrade cost modifier of the planet type.
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy)]
     #[cfg_attr(
         feature = "std",
         derive(
@@ -177,980 +267,3643 @@ mod newomegauniverse {
             ink_storage::traits::StorageLayout
         )
     )]
-    pub struct PlayerAssets {
-        name: String,
+    pub struct UpgradeCostTable {
+        /// Flat part of the cost
+        base_cost: u32,
+        /// Part of the cost growing linearly with the level
+        cost_per_level: u32,
+        /// Part of the cost growing quadratically with the level
+        cost_per_level_squared: u32,
     }
 
-    impl PlayerAssets {
-        fn new(name: String) -> Self {
-            PlayerAssets {
-                name,
+    impl Default for UpgradeCostTable {
+        fn default() -> Self {
+            UpgradeCostTable {
+                base_cost: 100,
+                cost_per_level: 50,
+                cost_per_level_squared: 5,
             }
         }
     }
 
-    #[ink(storage)]
-    pub struct NewOmegaUniverse {
-        creator: AccountId,
-        owner: Option<AccountId>,
-        new_omega_game: Option<newomegagame::NewOmegaGame>,
-        new_omega_storage: Option<newomegastorage::NewOmegaStorage>,
-        systems: StorageHashMap<AccountId, Vec<System>>,
-        assets: StorageHashMap<AccountId, PlayerAssets>,
+    /// Describes the traits of a planet type
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct PlanetTypeDefinition {
+        /// Harvest yield of each mineral, in percent
+        harvest_multipliers: [u16; MAX_MINERALS],
+        /// Extra garrison strength in fights, in percent
+        defence_bonus: u16,
+        /// Upgrade cost modifier, in percent
+        upgrade_cost_modifier: u16,
+        /// Garrison capacity at level 0, grows by GARRISON_CAPACITY_PER_LEVEL with each level
+        garrison_capacity: u32,
+        /// Special abilities (PLANET_ABILITY_* flags)
+        special_ability: u8,
     }
 
-    impl NewOmegaUniverse {
-        #[ink(constructor)]
-        pub fn new(new_omega_game: NewOmegaGame, new_omega_storage: NewOmegaStorage) -> Self {
-            Self {
-                creator: Self::env().caller(),
-                owner: None,
-                new_omega_game: Some(new_omega_game),
-                new_omega_storage: Some(new_omega_storage),
-                systems: StorageHashMap::default(),
-                assets: StorageHashMap::default(),
-            }
-        }
-
-        #[ink(constructor)]
-        pub fn default() -> Self {
-            Self {
-                creator: Self::env().caller(),
-                owner: None,
-                new_omega_game: None,
-                new_omega_storage: None,
-                systems: StorageHashMap::default(),
-                assets: StorageHashMap::default(),
-            }
-        }
-
-        /// Authorises the Delegator contract to fire methods on this one.
-        ///
-        /// # Arguments
-        ///
-        /// * `delegator_address` - AccountId of the Delegator contract
-        #[ink(message)]
-        pub fn authorise_delegator(&mut self, delegator_address: AccountId) {
-            assert_eq!(self.env().caller(), self.creator);
-            self.owner = Some(delegator_address);
-        }
-
-        /// Gets a System according to its coordinate
+    impl PlanetTypeDefinition {
+        /// Gets the default traits of a planet type. Every type is rich in one mineral,
+        /// and the rarer the richness, the more expensive the upgrades.
         ///
         /// # Arguments
         ///
-        /// * `coord` - The SystemCoordinate of the system to get
+        /// * `planet_type` - The planet type (0..MAX_PLANET_TYPES)
         ///
         /// # Returns
         ///
-        /// * `system` - An Option containing the System, or None
-        pub fn get_system(&self, coord: SystemCoordinate) -> Option<System> {
-            assert!(self.systems.get(&coord.root).is_some());
-
-            let systems: Vec<System> =
-                self.systems
-                    .get(&coord.root)
-                    .unwrap()
-                    .to_vec();
+        /// * `definition` - The default definition of the planet type
+        fn new_default(planet_type: usize) -> Self {
+            let mut harvest_multipliers: [u16; MAX_MINERALS] = [100; MAX_MINERALS];
+            harvest_multipliers[planet_type % MAX_MINERALS] += 10 * (planet_type / MAX_MINERALS) as u16;
+
+            let special_ability: u8 = match planet_type % 10 {
+                4 => PLANET_ABILITY_DEEP_CORE,
+                9 => PLANET_ABILITY_STEALTH,
+                _ => 0,
+            };
 
-            systems
-                .iter()
-                .filter(|system| system.position.position_x == coord.position_x &&
-                    system.position.position_y == coord.position_y)
-                .cloned()
-                .last()
+            PlanetTypeDefinition {
+                harvest_multipliers,
+                defence_bonus: 5 * (planet_type % 4) as u16,
+                upgrade_cost_modifier: 100 + 5 * planet_type as u16,
+                garrison_capacity: BASE_GARRISON_CAPACITY + 20 * (planet_type % 3) as u32,
+                special_ability,
+            }
         }
 
-        /// Gets a System according to its coordinate and check whether its attackable by a given player
+        /// Checks whether the planet type has a special ability
         ///
         /// # Arguments
         ///
-        /// * `caller` - The AccountId of a player to check attackability for
-        /// * `coord` - The SystemCoordinate of the system to get
+        /// * `ability` - The PLANET_ABILITY_* flag to check
         ///
         /// # Returns
         ///
-        /// * `system` - An Option containing the System, or None
-        /// * `attackable` - Whether the System is attackable by the player `caller`
-        #[ink(message)]
-        pub fn get_system_check(&self, caller: AccountId, coord: SystemCoordinate) ->
-            (Option<System>, bool) {
-
-            let system = self.get_system(coord);
-            let mut can_attack: bool = false;
-            if system.is_some() {
-                let position = system.clone().unwrap().position;
-                can_attack = self.can_attack_planet(caller, position);
-            }
-
-            (system, can_attack)
+        /// * `has_ability` - Whether the planet type has the ability
+        pub fn has_ability(&self, ability: u8) -> bool {
+            self.special_ability & ability != 0
         }
+    }
 
-        /// Gets a mutable reference to a System, according to its coordinate
-        ///
-        /// # Arguments
-        ///
-        /// * `coord` - The SystemCoordinate of the system to get
-        ///
-        /// # Returns
-        ///
-        /// * `system` - A mutable reference to the System
-        pub fn get_system_mut(&mut self, coord: SystemCoordinate) -> &mut System {
-            assert!(self.systems.get_mut(&coord.root).is_some());
+    /// Describes one end of a gateway between two Systems
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct Gateway {
+        /// The System at the other end of the gateway
+        target: SystemCoordinate,
+        /// Block number until which the gateway is disabled
+        disabled_until: BlockNumber,
+    }
 
-            let systems: &mut Vec<System> =
-                self.systems
-                    .get_mut(&coord.root)
-                    .unwrap();
+    /// Describes a gateway waiting for the acceptance of the target player
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct GatewayRequest {
+        /// The System requesting the gateway (out)
+        source: SystemCoordinate,
+        /// Minerals paid for the gateway, refunded if rejected
+        cost: [u32; MAX_MINERALS],
+    }
 
-            systems
-                .iter_mut()
-                .filter(|system| system.position.position_x == coord.position_x &&
-                    system.position.position_y == coord.position_y)
-                .last()
-                .unwrap()
-        }
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct System {
+        /// Position of the system
+        position: SystemCoordinate,
+        /// Gateways in
+        gateways_in: Vec<Gateway>,
+        /// Gateways out
+        gateways_out: Vec<Gateway>,
+        /// Planets
+        planets: Vec<Planet>,
+        /// Discoverer
+        discoverer: AccountId,
+    }
 
-        /// Generates a random planet type
-        ///
-        /// # Arguments
-        ///
-        /// * `seed` - The random seed
-        /// * `sub_seed` - The random sub seed
-        ///
-        /// # Returns
-        ///
-        /// * `u8` - Planet type
-        pub fn generate_planet_type(&self, seed: u64, sub_seed: u64) -> u8 {
-            let final_seed: u64 = seed / (sub_seed + 1);
-            (final_seed % MAX_PLANET_TYPES as u64) as u8
-        }
+    /// Describes a gateway of a deployment with a single gateway in and out per System
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct LegacyGateway {
+        built: bool,
+        target: SystemCoordinate,
+    }
 
-        /// Generates a random mineral type
-        ///
-        /// # Arguments
-        ///
-        /// * `seed` - The random seed
-        /// * `sub_seed` - The random sub seed
-        ///
-        /// # Returns
-        ///
-        /// * `u8` - Mineral type
-        pub fn generate_mineral_type(&self, seed: u64, sub_seed: u64) -> u8 {
-            let final_seed: u64 = seed / (sub_seed + 1);
-            (final_seed % MAX_MINERALS as u64) as u8
-        }
+    /// Describes a System of a deployment with a single gateway in and out per System,
+    /// as exported by its `get_universe_map`
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct LegacySystem {
+        position: SystemCoordinate,
+        gateway_in: LegacyGateway,
+        gateway_out: LegacyGateway,
+        planets: Vec<Planet>,
+        discoverer: AccountId,
+    }
 
-        /// Generates a random mineral proof
-        ///
-        /// # Arguments
-        ///
-        /// * `seed` - The random seed
-        /// * `sub_seed` - The random sub seed
-        ///
-        /// # Returns
-        ///
-        /// * `u8` - Mineral proof
-        pub fn generate_mineral_proof(&self, seed: u64, sub_seed: u64) -> u8 {
-            let final_seed: u64 = seed / (sub_seed + 1);
-            (final_seed % 100 as u64) as u8
-        }
+    impl LegacyGateway {
+        fn into_gateways(self) -> Vec<Gateway> {
+            let mut gateways: Vec<Gateway> = Vec::new();
+            if self.built {
+                gateways.push(Gateway {
+                    target: self.target,
+                    disabled_until: 0,
+                });
+            }
 
-        /// Generates a random seed
-        ///
-        /// # Returns
-        ///
-        /// * `u64` - The random seed
-        pub fn generate_random_seed(&self) -> u64 {
-            self.env().block_timestamp() + self.env().block_number() as u64
+            gateways
         }
+    }
 
-        /// Registers a player in the Universe module. 
-        /// Performs all neccesary ensures so that the account is fully playable.
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - The player to register
-        /// * `name` - The name under which to register the player
-        #[ink(message)]
-        pub fn register_player(&mut self, caller: AccountId, name: String) {
-            if self.owner.is_some() {
-                assert_eq!(self.env().caller(), self.owner.unwrap());
+    impl LegacySystem {
+        fn into_system(self) -> System {
+            System {
+                position: self.position,
+                gateways_in: self.gateway_in.into_gateways(),
+                gateways_out: self.gateway_out.into_gateways(),
+                planets: self.planets,
+                discoverer: self.discoverer,
             }
-            assert!(self.systems.get(&caller).is_none());
+        }
+    }
 
-            let new_position = SystemCoordinate {
-                root: caller,
-                position_x: 0,
-                position_y: 0,
-            };
-            let new_planets: Vec<Planet> = self.generate_planets(new_position, START_WITH_PLANETS, Some(caller));
-            let mut new_systems: Vec<System> = Vec::default();
-            new_systems.push(System {
+    /// Describes how much a player can see of a System
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub enum Visibility {
+        /// Garrisons are not visible at all
+        Hidden,
+        /// Only the approximate strength (CP bucket) of the garrisons is visible
+        Coarse,
+        /// Garrisons are fully visible (selection, modules and targeting)
+        Full,
+    }
+
+    /// Describes what a player knows about the garrisons of a System
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct SystemIntel {
+        /// Visibility of the System for the player
+        visibility: Visibility,
+        /// Approximate Command Power of each planet garrison (in CP_BUCKET_SIZE steps), if visible
+        cp_buckets: Vec<Option<u32>>,
+        /// Block number until which the System is scouted by the player, if at all
+        scouted_until: Option<BlockNumber>,
+    }
+
+    /// Lightweight description of a planet, used for the minimap
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct PlanetSummary {
+        /// Owner
+        owner: AccountId,
+        /// Level
+        level: u8,
+    }
+
+    /// Lightweight description of a system, used for the minimap
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct SystemSummary {
+        /// Position of the system
+        position: SystemCoordinate,
+        /// Planets
+        planets: Vec<PlanetSummary>,
+    }
+
+    impl SystemSummary {
+        fn new(system: &System) -> Self {
+            SystemSummary {
+                position: system.position,
+                planets: system.planets
+                    .iter()
+                    .map(|planet| PlanetSummary {
+                        owner: planet.owner,
+                        level: planet.level,
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    /// Describes what harvesting a single planet would yield
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone, Copy)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct PlanetHarvest {
+        /// Location of the planet
+        location: PlanetCoordinate,
+        /// Mineral type the planet yields
+        mineral_type: u8,
+        /// Amount of minerals the planet would yield
+        amount: u32,
+        /// Blocks until the planet stops accumulating minerals
+        blocks_until_cap: BlockNumber,
+    }
+
+    /// Describes what harvesting all the planets of a player would yield
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct PendingHarvest {
+        /// Yield of each planet
+        planets: Vec<PlanetHarvest>,
+        /// Total yield of each mineral
+        total: [u32; MAX_MINERALS],
+        /// Blocks until the first planet stops accumulating minerals
+        blocks_until_cap: BlockNumber,
+    }
+
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct PlayerAssets {
+        name: String,
+    }
+
+    impl PlayerAssets {
+        fn new(name: String) -> Self {
+            PlayerAssets {
+                name,
+            }
+        }
+    }
+
+    /// Describes all the planets a player owns, across all the Universes
+    #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink_storage::traits::StorageLayout
+        )
+    )]
+    pub struct EmpireOverview {
+        /// The owned planets, with their locations
+        planets: Vec<(PlanetCoordinate, Planet)>,
+        /// Number of different Universes the planets are in
+        universes: u32,
+        /// Ships garrisoned on all the planets
+        total_garrison: [u32; MAX_SHIPS],
+        /// Sum of the levels of all the planets
+        total_levels: u32,
+    }
+
+    #[ink(storage)]
+    pub struct NewOmegaUniverse {
+        access: AccessControl,
+        new_omega_game: Option<NewOmegaGame>,
+        new_omega_storage: Option<NewOmegaStorage>,
+        new_omega_parameters: Option<NewOmegaParameters>,
+        systems: StorageHashMap<SystemKey, System>,
+        system_index: StorageHashMap<AccountId, Vec<SystemCoordinate>>,
+        assets: StorageHashMap<AccountId, PlayerAssets>,
+        map_bounds: MapBounds,
+        scouts: StorageHashMap<(AccountId, SystemKey), BlockNumber>,
+        upgrade_costs: UpgradeCostTable,
+        planet_types: Vec<PlanetTypeDefinition>,
+        universe_salt: u64,
+        owned_planets: StorageHashMap<AccountId, Vec<PlanetCoordinate>>,
+        gateway_requests: StorageHashMap<AccountId, Vec<GatewayRequest>>,
+        open_gateways: StorageHashMap<AccountId, bool>,
+        fight_settings: Packed<FightSettings>,
+    }
+
+    /// Emitted when a player registers in the Universe
+    #[ink(event)]
+    pub struct PlayerRegistered {
+        #[ink(topic)]
+        player: AccountId,
+        name: String,
+    }
+
+    /// Emitted when a System is discovered
+    #[ink(event)]
+    pub struct SystemDiscovered {
+        #[ink(topic)]
+        discoverer: AccountId,
+        #[ink(topic)]
+        root: AccountId,
+        coord: SystemCoordinate,
+    }
+
+    /// Emitted when a gateway between two Universes is connected
+    #[ink(event)]
+    pub struct GatewayBuilt {
+        #[ink(topic)]
+        source_root: AccountId,
+        #[ink(topic)]
+        target_root: AccountId,
+        source: SystemCoordinate,
+        target: SystemCoordinate,
+    }
+
+    /// Emitted when a gateway between two Universes is destroyed
+    #[ink(event)]
+    pub struct GatewayDestroyed {
+        #[ink(topic)]
+        source_root: AccountId,
+        #[ink(topic)]
+        target_root: AccountId,
+        source: SystemCoordinate,
+        target: SystemCoordinate,
+    }
+
+    /// Emitted when a planet changes owner. Followed by the new owner as a capture,
+    /// and by the previous owner as a loss.
+    #[ink(event)]
+    pub struct PlanetCaptured {
+        #[ink(topic)]
+        new_owner: AccountId,
+        #[ink(topic)]
+        previous_owner: AccountId,
+        location: PlanetCoordinate,
+    }
+
+    /// Emitted when a planet is renamed
+    #[ink(event)]
+    pub struct PlanetRenamed {
+        #[ink(topic)]
+        owner: AccountId,
+        location: PlanetCoordinate,
+        name: String,
+    }
+
+    /// Emitted when a planet is upgraded
+    #[ink(event)]
+    pub struct PlanetUpgraded {
+        #[ink(topic)]
+        owner: AccountId,
+        location: PlanetCoordinate,
+        level: u8,
+    }
+
+    /// Emitted when a player harvests minerals
+    #[ink(event)]
+    pub struct Harvested {
+        #[ink(topic)]
+        player: AccountId,
+        minerals: [u32; MAX_MINERALS],
+    }
+
+    /// Emitted when the map bounds change
+    #[ink(event)]
+    pub struct MapBoundsChanged {
+        bounds: MapBounds,
+    }
+
+    /// Emitted when the upgrade cost table changes
+    #[ink(event)]
+    pub struct UpgradeCostTableChanged {
+        upgrade_costs: UpgradeCostTable,
+    }
+
+    /// Emitted when the settings of Universe fights change
+    #[ink(event)]
+    pub struct FightSettingsChanged {
+        settings: FightSettings,
+    }
+
+    /// Emitted when the traits of a planet type change
+    #[ink(event)]
+    pub struct PlanetTypeChanged {
+        planet_type: u8,
+        definition: PlanetTypeDefinition,
+    }
+
+    /// Widens a fleet selection to the ship counts kept by the Storage
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - The fleet selection
+    ///
+    /// # Returns
+    ///
+    /// * `counts` - The same ship counts, as u32
+    fn widen_selection(selection: &[u8; MAX_SHIPS]) -> [u32; MAX_SHIPS] {
+        let mut counts: [u32; MAX_SHIPS] = [0; MAX_SHIPS];
+        for (count, &selected) in counts.iter_mut().zip(selection.iter()) {
+            *count = selected as u32;
+        }
+
+        counts
+    }
+
+    impl NewOmegaUniverse {
+        #[ink(constructor)]
+        pub fn new(new_omega_game: NewOmegaGame, new_omega_storage: NewOmegaStorage,
+            new_omega_parameters: NewOmegaParameters) -> Self {
+            Self {
+                access: AccessControl::new(Self::env().caller()),
+                new_omega_game: Some(new_omega_game),
+                new_omega_storage: Some(new_omega_storage),
+                new_omega_parameters: Some(new_omega_parameters),
+                systems: StorageHashMap::default(),
+                system_index: StorageHashMap::default(),
+                assets: StorageHashMap::default(),
+                map_bounds: MapBounds::default(),
+                scouts: StorageHashMap::default(),
+                upgrade_costs: UpgradeCostTable::default(),
+                planet_types: (0..MAX_PLANET_TYPES).map(PlanetTypeDefinition::new_default).collect(),
+                universe_salt: Self::env().block_timestamp(),
+                owned_planets: StorageHashMap::default(),
+                gateway_requests: StorageHashMap::default(),
+                open_gateways: StorageHashMap::default(),
+                fight_settings: Packed::default(),
+            }
+        }
+
+        #[ink(constructor)]
+        pub fn default() -> Self {
+            Self {
+                access: AccessControl::new(Self::env().caller()),
+                new_omega_game: None,
+                new_omega_storage: None,
+                new_omega_parameters: None,
+                systems: StorageHashMap::default(),
+                system_index: StorageHashMap::default(),
+                assets: StorageHashMap::default(),
+                map_bounds: MapBounds::default(),
+                scouts: StorageHashMap::default(),
+                upgrade_costs: UpgradeCostTable::default(),
+                planet_types: (0..MAX_PLANET_TYPES).map(PlanetTypeDefinition::new_default).collect(),
+                universe_salt: Self::env().block_timestamp(),
+                owned_planets: StorageHashMap::default(),
+                gateway_requests: StorageHashMap::default(),
+                open_gateways: StorageHashMap::default(),
+                fight_settings: Packed::default(),
+            }
+        }
+
+        /// Authorises the Delegator contract to fire methods on this one
+        /// (grants it the Delegator role).
+        ///
+        /// # Arguments
+        ///
+        /// * `delegator_address` - AccountId of the Delegator contract
+        #[ink(message)]
+        pub fn authorise_delegator(&mut self, delegator_address: AccountId) {
+            self.grant_role(Role::Delegator, delegator_address);
+        }
+
+        /// Gets the game parameters, from the Parameters contract if there is one
+        ///
+        /// # Returns
+        ///
+        /// * `parameters` - The game parameters
+        fn get_parameters(&self) -> GameParameters {
+            match &self.new_omega_parameters {
+                Some(new_omega_parameters) => new_omega_parameters.get_parameters(),
+                None => GameParameters::default(),
+            }
+        }
+
+        /// Sets the bounds of the Universe map. Systems outside of the bounds cannot be discovered.
+        ///
+        /// # Arguments
+        ///
+        /// * `min_x` - Lowest allowed x coordinate
+        /// * `max_x` - Highest allowed x coordinate
+        /// * `min_y` - Lowest allowed y coordinate
+        /// * `max_y` - Highest allowed y coordinate
+        #[ink(message)]
+        pub fn set_map_bounds(&mut self, min_x: i32, max_x: i32, min_y: i32, max_y: i32) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            // The root systems are always at the origin, so it has to stay within the map
+            assert!(min_x <= 0 && max_x >= 0, "Map must contain the origin");
+            assert!(min_y <= 0 && max_y >= 0, "Map must contain the origin");
+
+            self.map_bounds = MapBounds {
+                min_x,
+                max_x,
+                min_y,
+                max_y,
+            };
+
+            self.env().emit_event(MapBoundsChanged {
+                bounds: self.map_bounds,
+            });
+        }
+
+        /// Gets the bounds of the Universe map
+        ///
+        /// # Returns
+        ///
+        /// * `bounds` - The map bounds
+        #[ink(message)]
+        pub fn get_map_bounds(&self) -> MapBounds {
+            self.map_bounds
+        }
+
+        /// Sets the mineral cost table of planet upgrades
+        ///
+        /// # Arguments
+        ///
+        /// * `upgrade_costs` - The new cost table
+        #[ink(message)]
+        pub fn set_upgrade_cost_table(&mut self, upgrade_costs: UpgradeCostTable) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            self.upgrade_costs = upgrade_costs;
+
+            self.env().emit_event(UpgradeCostTableChanged {
+                upgrade_costs,
+            });
+        }
+
+        /// Gets the mineral cost table of planet upgrades
+        ///
+        /// # Returns
+        ///
+        /// * `upgrade_costs` - The cost table
+        #[ink(message)]
+        pub fn get_upgrade_cost_table(&self) -> UpgradeCostTable {
+            self.upgrade_costs
+        }
+
+        /// Sets the settings of Universe fights (gateway and planet attacks): the maximum
+        /// number of rounds, and how a fight still not finished after them is decided.
+        ///
+        /// # Arguments
+        ///
+        /// * `settings` - The new fight settings
+        #[ink(message)]
+        pub fn set_fight_settings(&mut self, settings: FightSettings) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            assert!(settings.max_rounds > 0, "Invalid max rounds");
+            self.fight_settings = Packed::new(settings);
+
+            self.env().emit_event(FightSettingsChanged {
+                settings,
+            });
+        }
+
+        /// Gets the settings of Universe fights
+        ///
+        /// # Returns
+        ///
+        /// * `settings` - The fight settings
+        #[ink(message)]
+        pub fn get_fight_settings(&self) -> FightSettings {
+            *self.fight_settings
+        }
+
+        /// Sets the traits of a planet type. The harvest multipliers are bounded by
+        /// MAX_HARVEST_MULTIPLIER, the defence bonus by MAX_DEFENCE_BONUS and the garrison
+        /// capacity by MAX_GARRISON.
+        ///
+        /// # Arguments
+        ///
+        /// * `planet_type` - The planet type to set the traits of (0..MAX_PLANET_TYPES)
+        /// * `definition` - The new traits of the planet type
+        #[ink(message)]
+        pub fn set_planet_type(&mut self, planet_type: u8, definition: PlanetTypeDefinition) {
+            self.access.ensure_any_role(self.env().caller(), &[Role::Admin, Role::Delegator]);
+            assert!((planet_type as usize) < MAX_PLANET_TYPES, "Invalid planet type");
+            assert!(definition.upgrade_cost_modifier > 0, "Invalid upgrade cost modifier");
+            assert!(definition.harvest_multipliers.iter().all(|multiplier| *multiplier <= MAX_HARVEST_MULTIPLIER),
+                "Invalid harvest multiplier");
+            assert!(definition.defence_bonus <= MAX_DEFENCE_BONUS, "Invalid defence bonus");
+            assert!(definition.garrison_capacity <= MAX_GARRISON, "Invalid garrison capacity");
+
+            self.planet_types[planet_type as usize] = definition;
+
+            self.env().emit_event(PlanetTypeChanged {
+                planet_type,
+                definition,
+            });
+        }
+
+        /// Gets the traits of all the planet types
+        ///
+        /// # Returns
+        ///
+        /// * `planet_types` - Vec of the planet type definitions, indexed by planet type
+        #[ink(message)]
+        pub fn get_planet_types(&self) -> Vec<PlanetTypeDefinition> {
+            self.planet_types.clone()
+        }
+
+        /// Gets the traits of a planet
+        ///
+        /// # Arguments
+        ///
+        /// * `planet` - The planet to get the traits of
+        ///
+        /// # Returns
+        ///
+        /// * `definition` - The definition of the planet type
+        pub fn get_planet_type(&self, planet: &Planet) -> &PlanetTypeDefinition {
+            &self.planet_types[planet.planet_type as usize]
+        }
+
+        /// Gets a System according to its coordinate
+        ///
+        /// # Arguments
+        ///
+        /// * `coord` - The SystemCoordinate of the system to get
+        ///
+        /// # Returns
+        ///
+        /// * `system` - An Option containing the System, or None
+        pub fn get_system(&self, coord: SystemCoordinate) -> Option<System> {
+            assert!(self.system_index.get(&coord.root).is_some());
+
+            self.systems
+                .get(&coord.key())
+                .cloned()
+        }
+
+        /// Gets a System according to its coordinate and check whether its attackable by a given player.
+        /// The garrisons of the System are only revealed if the player has full visibility of it.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The AccountId of a player to check attackability for
+        /// * `coord` - The SystemCoordinate of the system to get
+        ///
+        /// # Returns
+        ///
+        /// * `system` - An Option containing the System, or None
+        /// * `attackable` - Whether the System is attackable by the player `caller`
+        #[ink(message)]
+        pub fn get_system_check(&self, caller: AccountId, coord: SystemCoordinate) ->
+            (Option<System>, bool) {
+
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            let system = self.get_system(coord);
+            let mut can_attack: bool = false;
+            if system.is_some() {
+                let position = system.clone().unwrap().position;
+                can_attack = self.can_attack_planet(caller, position);
+            }
+
+            (system.map(|system| self.apply_fog_of_war(caller, system)), can_attack)
+        }
+
+        /// Checks whether a player is currently scouting a System
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to check
+        /// * `coord` - The SystemCoordinate of the System
+        ///
+        /// # Returns
+        ///
+        /// * `scouted_until` - Block number until which the System is scouted, or None
+        pub fn get_scouted_until(&self, caller: AccountId, coord: &SystemCoordinate) -> Option<BlockNumber> {
+            match self.scouts.get(&(caller, coord.key())) {
+                Some(&scouted_until) if scouted_until >= self.env().block_number() => Some(scouted_until),
+                _ => None,
+            }
+        }
+
+        /// Determines how much a player can see of a System.
+        /// Full visibility is granted to players currently scouting it. Systems in which the player
+        /// owns a planet, or which the player can reach (adjacent to their Systems, or connected by
+        /// a gateway) are visible coarsely. All the rest is hidden.
+        /// Regardless of the System visibility, players always see the garrisons of their own planets.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to determine visibility for
+        /// * `system` - The System to determine visibility of
+        ///
+        /// # Returns
+        ///
+        /// * `visibility` - The visibility of the System for the player
+        fn get_visibility(&self, caller: AccountId, system: &System) -> Visibility {
+            if self.get_scouted_until(caller, &system.position).is_some() {
+                Visibility::Full
+            } else if system.planets.iter().any(|planet| planet.owner == caller) ||
+                self.can_attack_planet(caller, system.position) {
+
+                Visibility::Coarse
+            } else {
+                Visibility::Hidden
+            }
+        }
+
+        /// Hides the garrisons of the planets the player does not own, unless the player has full
+        /// visibility of the System
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player the System is shown to
+        /// * `system` - The System to hide the garrisons of
+        ///
+        /// # Returns
+        ///
+        /// * `system` - The System, as seen by the player
+        fn apply_fog_of_war(&self, caller: AccountId, mut system: System) -> System {
+            if self.get_visibility(caller, &system) != Visibility::Full {
+                for planet in system.planets.iter_mut().filter(|planet| planet.owner != caller) {
+                    planet.selection = [0; MAX_SHIPS];
+                    planet.modules = [ShipModule::default(); MAX_SHIPS];
+                    planet.targeting = TargetingType::default();
+                }
+            }
+
+            system
+        }
+
+        /// Gets the current ship definitions, used to calculate Command Power
+        ///
+        /// # Returns
+        ///
+        /// * `ships` - Vec of the registered ships
+        fn get_ship_definitions(&self) -> Vec<Ship> {
+            match self.new_omega_game {
+                Some(ref new_omega_game) => new_omega_game.get_ships(),
+                None => newomega::prepare_ships(),
+            }
+        }
+
+        /// Checks whether a fleet selection only uses ships which are not retired
+        ///
+        /// # Arguments
+        ///
+        /// * `selection` - The fleet selection
+        ///
+        /// # Returns
+        ///
+        /// * `is_active` - Whether no retired ship is selected
+        fn is_selection_active(&self, selection: [u8; MAX_SHIPS]) -> bool {
+            match self.new_omega_game {
+                Some(ref new_omega_game) => new_omega_game.is_selection_active(selection),
+                None => true,
+            }
+        }
+
+        /// Gets what a player knows about the garrisons of a System
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to get the intel for
+        /// * `coord` - The SystemCoordinate of the System
+        ///
+        /// # Returns
+        ///
+        /// * `intel` - The SystemIntel, with CP buckets revealed if the System is not hidden
+        #[ink(message)]
+        pub fn get_system_intel(&self, caller: AccountId, coord: SystemCoordinate) -> SystemIntel {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            let system: System = self.get_system(coord).unwrap();
+            let visibility: Visibility = self.get_visibility(caller, &system);
+            let mut cp_buckets: Vec<Option<u32>> = Vec::new();
+
+            if visibility == Visibility::Hidden {
+                cp_buckets.resize(system.planets.len(), None);
+            } else {
+                let ships: Vec<Ship> = self.get_ship_definitions();
+                for planet in system.planets.iter() {
+                    if visibility != Visibility::Full && planet.owner != caller &&
+                        self.get_planet_type(planet).has_ability(PLANET_ABILITY_STEALTH) {
+
+                        cp_buckets.push(None);
+                        continue;
+                    }
+
+                    let cp: u32 = planet.selection
+                        .iter()
+                        .zip(ships.iter())
+                        .map(|(&count, ship)| count as u32 * ship.cp as u32)
+                        .sum();
+                    cp_buckets.push(Some(cp / CP_BUCKET_SIZE));
+                }
+            }
+
+            SystemIntel {
+                visibility,
+                cp_buckets,
+                scouted_until: self.get_scouted_until(caller, &coord),
+            }
+        }
+
+        /// Scouts a System, giving the player full visibility of it for the scout duration.
+        /// Only Systems which the player can already see coarsely can be scouted.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player scouting
+        /// * `target` - The SystemCoordinate of the System to scout
+        #[ink(message)]
+        pub fn scout_system(&mut self, caller: AccountId, target: SystemCoordinate) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+
+            let system: System = self.get_system(target).unwrap();
+            assert!(self.get_visibility(caller, &system) != Visibility::Hidden, "Cant scout system");
+
+            let scouted_until: BlockNumber = self.env().block_number() +
+                self.get_parameters().scout_duration_blocks;
+            self.scouts.insert((caller, target.key()), scouted_until);
+        }
+
+        /// Gets a mutable reference to a System, according to its coordinate
+        ///
+        /// # Arguments
+        ///
+        /// * `coord` - The SystemCoordinate of the system to get
+        ///
+        /// # Returns
+        ///
+        /// * `system` - A mutable reference to the System
+        pub fn get_system_mut(&mut self, coord: SystemCoordinate) -> &mut System {
+            assert!(self.systems.get(&coord.key()).is_some());
+
+            self.systems
+                .get_mut(&coord.key())
+                .unwrap()
+        }
+
+        /// Stores a newly created System, and indexes it under its root.
+        /// Planets of the System which already have an owner get indexed under the owner.
+        ///
+        /// # Arguments
+        ///
+        /// * `system` - The System to store
+        fn insert_system(&mut self, system: System) {
+            let position: SystemCoordinate = system.position;
+
+            for (planet_id, planet) in system.planets.iter().enumerate() {
+                if planet.owner != AccountId::default() {
+                    self.owned_planets
+                        .entry(planet.owner)
+                        .or_insert(Vec::default())
+                        .push(PlanetCoordinate {
+                            system: position,
+                            planet_id: planet_id as u8,
+                        });
+                }
+            }
+
+            self.system_index
+                .entry(position.root)
+                .or_insert(Vec::default())
+                .push(position);
+            self.systems
+                .insert(position.key(), system);
+        }
+
+        /// Changes the owner of a planet, keeping the owned planets index up to date
+        ///
+        /// # Arguments
+        ///
+        /// * `location` - The PlanetCoordinate of the planet
+        /// * `owner` - The new owner of the planet
+        fn set_planet_owner(&mut self, location: PlanetCoordinate, owner: AccountId) {
+            let system: &mut System = self.get_system_mut(location.system);
+            let planet: &mut Planet = &mut system.planets[location.planet_id as usize];
+            let previous_owner: AccountId = planet.owner;
+            planet.owner = owner;
+
+            if let Some(previous_owner_planets) = self.owned_planets.get_mut(&previous_owner) {
+                previous_owner_planets.retain(|owned| owned.system.key() != location.system.key() ||
+                    owned.planet_id != location.planet_id);
+            }
+
+            self.owned_planets
+                .entry(owner)
+                .or_insert(Vec::default())
+                .push(location);
+
+            self.env().emit_event(PlanetCaptured {
+                new_owner: owner,
+                previous_owner,
+                location,
+            });
+        }
+
+        /// Gets the locations of all the planets a player owns, across all the Universes
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to get the planets of
+        ///
+        /// # Returns
+        ///
+        /// * `planets` - Vec of the locations of the owned planets
+        pub fn get_owned_planets(&self, caller: AccountId) -> Vec<PlanetCoordinate> {
+            match self.owned_planets.get(&caller) {
+                Some(planets) => planets.to_vec(),
+                None => Vec::default(),
+            }
+        }
+
+        /// Gets the coordinates of all the Systems in a players Universe
+        ///
+        /// # Arguments
+        ///
+        /// * `root` - The player to get the coordinates for
+        ///
+        /// # Returns
+        ///
+        /// * `coords` - Vec of coordinates of the Systems, in order of discovery
+        pub fn get_system_coords(&self, root: AccountId) -> Vec<SystemCoordinate> {
+            assert!(self.system_index.get(&root).is_some());
+
+            self.system_index
+                .get(&root)
+                .unwrap()
+                .to_vec()
+        }
+
+        /// Generates the procedural seed of a planet, by hashing its location together with
+        /// the universe salt. The same planet always gets the same seed, no matter when the
+        /// System gets discovered.
+        ///
+        /// # Arguments
+        ///
+        /// * `target` - SystemCoordinate of the System the planet is in
+        /// * `planet_index` - Index of the planet within the System
+        ///
+        /// # Returns
+        ///
+        /// * `seed` - The planet seed
+        pub fn generate_planet_seed(&self, target: &SystemCoordinate, planet_index: u8) -> [u8; 32] {
+            self.env().hash_encoded::<Blake2x256, _>(&(
+                target.root,
+                target.position_x,
+                target.position_y,
+                planet_index,
+                self.universe_salt,
+            ))
+        }
+
+        /// Reads a random number from a part of a planet seed
+        ///
+        /// # Arguments
+        ///
+        /// * `seed` - The planet seed
+        /// * `part` - Which 8 byte part of the seed to read (0..4)
+        /// * `modulo` - Upper bound (exclusive) of the number
+        ///
+        /// # Returns
+        ///
+        /// * `u64` - The random number
+        fn read_seed(seed: &[u8; 32], part: usize, modulo: u64) -> u64 {
+            let mut bytes: [u8; 8] = [0; 8];
+            bytes.copy_from_slice(&seed[part * 8..(part + 1) * 8]);
+            u64::from_le_bytes(bytes) % modulo
+        }
+
+        /// Generates a random planet type
+        ///
+        /// # Arguments
+        ///
+        /// * `seed` - The planet seed
+        ///
+        /// # Returns
+        ///
+        /// * `u8` - Planet type
+        pub fn generate_planet_type(&self, seed: &[u8; 32]) -> u8 {
+            NewOmegaUniverse::read_seed(seed, 0, MAX_PLANET_TYPES as u64) as u8
+        }
+
+        /// Generates a random mineral type
+        ///
+        /// # Arguments
+        ///
+        /// * `seed` - The planet seed
+        ///
+        /// # Returns
+        ///
+        /// * `u8` - Mineral type
+        pub fn generate_mineral_type(&self, seed: &[u8; 32]) -> u8 {
+            NewOmegaUniverse::read_seed(seed, 1, MAX_MINERALS as u64) as u8
+        }
+
+        /// Generates a random mineral proof
+        ///
+        /// # Arguments
+        ///
+        /// * `seed` - The planet seed
+        ///
+        /// # Returns
+        ///
+        /// * `u8` - Mineral proof
+        pub fn generate_mineral_proof(&self, seed: &[u8; 32]) -> u8 {
+            NewOmegaUniverse::read_seed(seed, 2, 100) as u8
+        }
+
+        /// Generates a random seed
+        ///
+        /// # Returns
+        ///
+        /// * `u64` - The random seed
+        pub fn generate_random_seed(&self) -> u64 {
+            self.env().block_timestamp() + self.env().block_number() as u64
+        }
+
+        /// Registers a player in the Universe module. 
+        /// Performs all neccesary ensures so that the account is fully playable.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to register
+        /// * `name` - The name under which to register the player
+        #[ink(message)]
+        pub fn register_player(&mut self, caller: AccountId, name: String) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.system_index.get(&caller).is_none());
+
+            let new_position = SystemCoordinate {
+                root: caller,
+                position_x: 0,
+                position_y: 0,
+            };
+            let new_planets: Vec<Planet> = self.generate_planets(new_position, START_WITH_PLANETS, Some(caller));
+            self.insert_system(System {
                 position: new_position,
                 discoverer: caller,
                 planets: new_planets,
-                gateway_in: Gateway::default(),
-                gateway_out: Gateway::default(),
+                gateways_in: Vec::new(),
+                gateways_out: Vec::new(),
+            });
+
+            self.assets
+                .insert(caller, PlayerAssets::new(name.clone()));
+            if self.new_omega_storage.is_some() {
+                self.new_omega_storage
+                    .as_mut()
+                    .unwrap()
+                    .ensure_minerals(caller);
+                self.new_omega_storage
+                    .as_mut()
+                    .unwrap()
+                    .ensure_trades(caller);
+                self.new_omega_storage
+                    .as_mut()
+                    .unwrap()
+                    .ensure_ships(caller);
+            }
+
+            self.env().emit_event(PlayerRegistered {
+                player: caller,
+                name,
+            });
+        }
+
+        /// Imports a players Universe, as exported by `get_universe_map` of a previous deployment
+        /// (which kept all the Systems of a player in a single Vec, with a single gateway in and out
+        /// per System), into the indexed storage.
+        /// Player progress outside of the Universe is kept by the Storage contract, which
+        /// survives the redeployment, so it is not touched here.
+        ///
+        /// # Arguments
+        ///
+        /// * `root` - The player to import the Universe of
+        /// * `name` - The name under which the player was registered
+        /// * `systems` - All the Systems of the players Universe (with planets of level 1 to MAX_PLANET_LEVEL)
+        #[ink(message)]
+        pub fn import_systems(&mut self, root: AccountId, name: String, systems: Vec<LegacySystem>) {
+            self.access.ensure_role(self.env().caller(), Role::Admin);
+            assert!(self.system_index.get(&root).is_none(), "Player already registered");
+
+            for system in systems {
+                assert!(system.position.root == root, "System outside of players Universe");
+                assert!(self.systems.get(&system.position.key()).is_none(), "Duplicate system");
+                assert!(system.planets.iter().all(|planet| planet.level >= 1 && planet.level <= MAX_PLANET_LEVEL),
+                    "Invalid planet level");
+                self.insert_system(system.into_system());
+            }
+
+            assert!(self.system_index.get(&root).is_some(), "Nothing to import");
+            self.assets
+                .insert(root, PlayerAssets::new(name));
+        }
+
+        /// Translates AccountIds of players into their registered names
+        ///
+        /// # Arguments
+        ///
+        /// * `players` - The AccountIds of players to get names for
+        ///
+        /// # Returns
+        ///
+        /// * `names` - Vec of Strings containing the names
+        #[ink(message)]
+        pub fn get_player_names(&self, players: Vec<AccountId>) -> Vec<String> {
+            let mut translated: Vec<String> = Vec::new();
+            for player in players {
+                if player == AccountId::default() {
+                    translated.push(String::from("None"));
+                } else {
+                    let asset: &PlayerAssets = self.assets.get(&player).unwrap();
+                    translated.push(asset.name.clone());
+                }
+            }
+
+            translated
+        }
+
+        /// Reinforces a players owned planet with a fleet selection and sets tactics.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - AccountId of the player
+        /// * `target` - The location of the planet to reinforce
+        /// * `planet_id` - Which planet to attack (0..MAX_PLANETS)
+        /// * `selection` - The fleet selection to register
+        /// * `modules` - The modules to register with the fleet
+        /// * `targeting` - The targeting to register with the fleet
+        #[ink(message)]
+        pub fn reinforce_planet(&mut self, caller: AccountId, target: SystemCoordinate,
+            planet_id: u8, selection: [u8; MAX_SHIPS], modules: [ShipModule; MAX_SHIPS],
+            targeting: TargetingType) {
+
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+
+            let system = self.get_system(target).unwrap();
+            let planet = &system.planets[planet_id as usize];
+
+            assert_eq!(planet.owner, caller);
+            assert!(self.is_selection_active(selection), "Ship retired");
+
+            let garrison_size: u32 = selection.iter().map(|&count| count as u32).sum();
+            assert!(garrison_size <= self.get_garrison_capacity(planet), "Garrison too big");
+
+            let planet_selection: [u32; MAX_SHIPS] = widen_selection(&planet.selection);
+            let selection_u32: [u32; MAX_SHIPS] = widen_selection(&selection);
+
+            self    
+                .new_omega_storage
+                .as_mut()
+                .unwrap()
+                .add_ships(caller, planet_selection);
+
+            let has_enough = self
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .has_enough_ships(caller, selection);
+
+            assert!(has_enough);
+
+            self    
+                .new_omega_storage
+                .as_mut()
+                .unwrap()
+                .remove_ships(caller, selection_u32);
+
+            let system = self.get_system_mut(target);
+            let mut planet_mut = &mut system.planets[planet_id as usize];
+            planet_mut.selection = selection;
+            planet_mut.modules = modules;
+            planet_mut.targeting = targeting;
+        }
+
+        /// Generates a new set of planets
+        ///
+        /// # Arguments
+        ///
+        /// * `target` - SystemCoordinate where to generate planets in
+        /// * `start_ownership` - How many planets will be assigned to the player
+        /// * `owner` - When starting ownership, who will be the owner
+        ///
+        /// # Returns
+        ///
+        /// * `planets` - Vec of newly generated Planets
+        pub fn generate_planets(&self, target: SystemCoordinate, start_ownership: u8, owner: Option<AccountId>) -> Vec<Planet> {
+            let block_number = self.env().block_number();
+            let mut new_planets: Vec<Planet> = Vec::default();
+            for i in 0..MAX_PLANETS {
+                let mut new_planet = Planet::default();
+                let seed: [u8; 32] = self.generate_planet_seed(&target, i as u8);
+                new_planet.planet_type = self.generate_planet_type(&seed);
+                new_planet.mineral_type = self.generate_mineral_type(&seed);
+                new_planet.mineral_proof = self.generate_mineral_proof(&seed);
+                new_planet.last_harvested = block_number;
+                if start_ownership > 0 && i < (start_ownership as usize) {
+                    new_planet.owner = owner.unwrap();
+                }
+                new_planets.push(new_planet);
+            }
+
+            new_planets
+        }
+
+        /// Renames a planet
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player making the rename request
+        /// * `target` - SystemCoordinate of the the planet to rename
+        /// * `planet_id` - Which planet to rename (0..MAX_PLANETS)
+        /// * `name` - The name to set
+        #[ink(message)]
+        pub fn rename_planet(&mut self, caller: AccountId, target: SystemCoordinate, planet_id: u8, name: String) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.get_system(target).is_some());
+
+            let system = self.get_system_mut(target);
+            let mut planet_mut = &mut system.planets[planet_id as usize];
+
+            assert_eq!(planet_mut.owner, caller);
+
+            planet_mut.name = Some(name.clone());
+
+            self.env().emit_event(PlanetRenamed {
+                owner: caller,
+                location: PlanetCoordinate {
+                    system: target,
+                    planet_id,
+                },
+                name,
+            });
+        }
+
+        /// Discovers a given system for a player
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - AccountId of the player making the discovery request
+        /// * `target` - SystemCoordinate to discover a System in
+        #[ink(message)]
+        pub fn discover_system(&mut self, caller: AccountId, target: SystemCoordinate) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.system_index.get(&target.root).is_some());
+            assert!(self.get_system(target).is_none());
+            assert!(self.can_discover_system(caller, target), "Cant discover system");
+
+            let new_planets = self.generate_planets(target, 0, None);
+            self.insert_system(System {
+                position: target,
+                discoverer: caller,
+                planets: new_planets,
+                gateways_in: Vec::new(),
+                gateways_out: Vec::new(),
+            });
+
+            self.env().emit_event(SystemDiscovered {
+                discoverer: caller,
+                root: target.root,
+                coord: target,
+            });
+        }
+
+        /// Gets a random discoverable system for a given player
+        ///
+        /// # Arguments
+        ///
+        /// * `root` - AccountId of the player in whos Universe to find a discoverable system
+        ///
+        /// # Returns
+        ///
+        /// * `coord` - The coordinate of the system, or None
+        pub fn get_random_discoverable_system(&self, root: AccountId) -> Option<SystemCoordinate> {
+            let coords = self.system_index.get(&root).unwrap();
+            for coord in coords {
+                let adjacent_systems_coords: [SystemCoordinate; 4] =
+                    self.get_adjacent_system_coords(coord);
+                for adjacent_system_coords in adjacent_systems_coords.iter() {
+                    if self.can_discover_system(root, *adjacent_system_coords) {
+                        return Some(*adjacent_system_coords);
+                    }
+                }
+            }
+
+            None
+        }
+
+        /// Checks whether a coordinate lies within the Universe map bounds
+        ///
+        /// # Arguments
+        ///
+        /// * `coord` - The SystemCoordinate to check
+        ///
+        /// # Returns
+        ///
+        /// * `within_bounds` - Whether the coordinate is within the map bounds
+        pub fn is_within_bounds(&self, coord: &SystemCoordinate) -> bool {
+            coord.position_x >= self.map_bounds.min_x &&
+                coord.position_x <= self.map_bounds.max_x &&
+                coord.position_y >= self.map_bounds.min_y &&
+                coord.position_y <= self.map_bounds.max_y
+        }
+
+        /// Checks whether a player can discover a system at a given coordinate.
+        /// The coordinate has to be within the map bounds, not discovered yet, and adjacent
+        /// to a system the player either controls, or is the root of.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to check discoverability for
+        /// * `target` - The SystemCoordinate to check
+        ///
+        /// # Returns
+        ///
+        /// * `can_discover` - Whether the player can discover a system at the coordinate
+        pub fn can_discover_system(&self, caller: AccountId, target: SystemCoordinate) -> bool {
+            if !self.is_within_bounds(&target) || self.get_system(target).is_some() {
+                return false;
+            }
+
+            let adjacent_systems_coords: [SystemCoordinate; 4] = self.get_adjacent_system_coords(&target);
+            for adjacent_system_coords in adjacent_systems_coords.iter() {
+                if let Some(ref adjacent_system) = self.get_system(*adjacent_system_coords) {
+                    if adjacent_system.position.root == caller ||
+                        self.is_owner_of_system(caller, &adjacent_system) {
+
+                        return true;
+                    }
+                }
+            }
+
+            false
+        }
+
+        /// Gets the number of gateway slots (in each direction) of a System.
+        /// Every LEVELS_PER_GATEWAY_SLOT levels of its highest level planet add a slot.
+        ///
+        /// # Arguments
+        ///
+        /// * `system` - The System to get the slots of
+        ///
+        /// # Returns
+        ///
+        /// * `slots` - The number of gateway slots
+        pub fn get_gateway_slots(&self, system: &System) -> u32 {
+            let max_level: u8 = system.planets
+                .iter()
+                .map(|planet| planet.level)
+                .max()
+                .unwrap_or(1);
+
+            NewOmegaUniverse::min(1 + (max_level / LEVELS_PER_GATEWAY_SLOT) as u32, MAX_GATEWAY_SLOTS)
+        }
+
+        /// Checks whether a gateway is currently usable
+        ///
+        /// # Arguments
+        ///
+        /// * `gateway` - The gateway to check
+        ///
+        /// # Returns
+        ///
+        /// * `is_active` - Whether the gateway is not disabled
+        pub fn is_gateway_active(&self, gateway: &Gateway) -> bool {
+            self.env().block_number() >= gateway.disabled_until
+        }
+
+        /// Checks whether two Systems are connected by a gateway, in any direction
+        ///
+        /// # Arguments
+        ///
+        /// * `first` - SystemCoordinate of one System
+        /// * `second` - SystemCoordinate of the other System
+        ///
+        /// # Returns
+        ///
+        /// * `is_connected` - Whether a gateway connects the Systems
+        pub fn is_connected(&self, first: SystemCoordinate, second: SystemCoordinate) -> bool {
+            match self.systems.get(&first.key()) {
+                Some(system) => system.gateways_in
+                    .iter()
+                    .chain(system.gateways_out.iter())
+                    .any(|gateway| gateway.target.key() == second.key()),
+                None => false,
+            }
+        }
+
+        /// Gets a target for a new gateway in a players territory
+        ///
+        /// # Arguments
+        ///
+        /// * `root` - AccountId of the player in whos Universe to find a gateway target
+        /// * `source` - SystemCoordinate of the System the gateway will come from
+        /// * `seed` - The random seed
+        ///
+        /// # Returns
+        ///
+        /// * `coord` - The coordinate of the system, or None
+        pub fn get_player_gateway_target(&self, root: AccountId, source: SystemCoordinate,
+            seed: u64) -> Option<SystemCoordinate> {
+
+            let coords = self.system_index.get(&root).unwrap();
+            let avail_systems: Vec<SystemCoordinate> = coords
+                .iter()
+                .filter(|coord| {
+                    let system: &System = self.systems.get(&coord.key()).unwrap();
+                    (system.gateways_in.len() as u32) < self.get_gateway_slots(system) &&
+                        !self.is_connected(**coord, source)
+                })
+                .cloned()
+                .collect();
+
+            let no_systems: usize = avail_systems.len();
+            if no_systems > 0 {
+                let random_system = seed % no_systems as u64;
+                return Some(avail_systems[random_system as usize]);
+            }
+
+            self.get_random_discoverable_system(root)
+        }
+
+        /// Worker function for gateway out building
+        ///
+        /// # Arguments
+        ///
+        /// * `target` - SystemCoordinate of the System to build gateway in
+        /// * `position` - The target of the gateway
+        pub fn build_gateway_out_worker(&mut self,
+            target: SystemCoordinate, position: SystemCoordinate) {
+
+            let system = self.get_system_mut(target);
+            system.gateways_out.push(Gateway {
+                target: position,
+                disabled_until: 0,
+            });
+        }
+
+        /// Worker function for gateway in building
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - AccountId of the connecting player (building gateway out)
+        /// * `target` - SystemCoordinate of the System to build gateway in
+        /// * `position` - The target of the gateway
+        pub fn build_gateway_in_worker(&mut self, caller: AccountId,
+            target: SystemCoordinate, position: SystemCoordinate) {
+
+            let gateway = Gateway {
+                target: position,
+                disabled_until: 0,
+            };
+
+            if self.get_system(target).is_none() {
+                let new_planets = self.generate_planets(target, 0, None);
+                self.insert_system(System {
+                    position: target,
+                    discoverer: caller,
+                    planets: new_planets,
+                    gateways_in: ink_prelude::vec![gateway],
+                    gateways_out: Vec::new(),
+                });
+            } else {
+                let system = self.get_system_mut(target);
+                system.gateways_in.push(gateway);
+            }
+        }
+
+        /// Connects two Systems with a gateway, checking the gateway slots of both
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - AccountId of the connecting player (building gateway out)
+        /// * `source` - SystemCoordinate of the System to build the gateway out in
+        /// * `target` - SystemCoordinate of the System to build the gateway in in
+        fn connect_gateway(&mut self, caller: AccountId, source: SystemCoordinate, target: SystemCoordinate) {
+            let source_system: System = self.get_system(source).unwrap();
+            assert!((source_system.gateways_out.len() as u32) < self.get_gateway_slots(&source_system),
+                "No free gateway slot");
+            assert!(!self.is_connected(source, target), "Already connected");
+
+            match self.get_system(target) {
+                Some(ref target_system) => assert!(
+                    (target_system.gateways_in.len() as u32) < self.get_gateway_slots(target_system),
+                    "No free gateway slot"),
+                None => assert!(self.can_discover_system(target.root, target), "Cant discover system"),
+            }
+
+            self.build_gateway_out_worker(source, target);
+            self.build_gateway_in_worker(caller, target, source);
+
+            self.env().emit_event(GatewayBuilt {
+                source_root: source.root,
+                target_root: target.root,
+                source,
+                target,
+            });
+        }
+
+        /// Removes the gateways (both ends) between two Systems
+        ///
+        /// # Arguments
+        ///
+        /// * `first` - SystemCoordinate of one System
+        /// * `second` - SystemCoordinate of the other System
+        fn remove_gateway(&mut self, first: SystemCoordinate, second: SystemCoordinate) {
+            for (system_coord, other) in [(first, second), (second, first)].iter() {
+                let system: &mut System = self.get_system_mut(*system_coord);
+                system.gateways_in.retain(|gateway| gateway.target.key() != other.key());
+                system.gateways_out.retain(|gateway| gateway.target.key() != other.key());
+            }
+        }
+
+        /// Disables the gateways (both ends) between two Systems
+        ///
+        /// # Arguments
+        ///
+        /// * `first` - SystemCoordinate of one System
+        /// * `second` - SystemCoordinate of the other System
+        /// * `disabled_until` - Block number until which the gateways are disabled
+        fn disable_gateway(&mut self, first: SystemCoordinate, second: SystemCoordinate,
+            disabled_until: BlockNumber) {
+
+            for (system_coord, other) in [(first, second), (second, first)].iter() {
+                let system: &mut System = self.get_system_mut(*system_coord);
+                for gateway in system.gateways_in.iter_mut().chain(system.gateways_out.iter_mut()) {
+                    if gateway.target.key() == other.key() {
+                        gateway.disabled_until = disabled_until;
+                    }
+                }
+            }
+        }
+
+        /// Sets whether a player accepts gateways into their Universe without approval
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to set the preference for
+        /// * `open` - Whether to accept gateways without approval
+        #[ink(message)]
+        pub fn set_open_gateways(&mut self, caller: AccountId, open: bool) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+
+            self.open_gateways.insert(caller, open);
+        }
+
+        /// Builds a gateway (out) in a System in players universe, into the Universe of a chosen player.
+        /// The gateway costs the gateway cost parameter of every mineral. If the target player accepts open
+        /// gateways, it gets connected right away to one of their Systems. Otherwise it waits
+        /// for the target player to accept it. A player can only have one request pending with
+        /// each target player, so that a single player cannot fill the queue of requests.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - AccountId of the connecting player (building gateway out)
+        /// * `source` - SystemCoordinate of the System to build gateway in
+        /// * `target_root` - AccountId of the player to connect to
+        #[ink(message)]
+        pub fn build_gateway(&mut self, caller: AccountId, source: SystemCoordinate, target_root: AccountId) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert_eq!(caller, source.root);
+            assert!(caller != target_root, "Cant connect to own Universe");
+            assert!(self.system_index.get(&source.root).is_some());
+            assert!(self.system_index.get(&target_root).is_some());
+
+            let source_system: System = self.get_system(source).unwrap();
+            assert!((source_system.gateways_out.len() as u32) < self.get_gateway_slots(&source_system),
+                "No free gateway slot");
+
+            let cost: [u32; MAX_MINERALS] = [self.get_parameters().gateway_cost; MAX_MINERALS];
+            if self.new_omega_storage.is_some() {
+                let minerals: [u32; MAX_MINERALS] = self
+                    .new_omega_storage
+                    .as_ref()
+                    .unwrap()
+                    .get_player_minerals(caller);
+
+                for i in 0..MAX_MINERALS {
+                    assert!(minerals[i] >= cost[i], "Not enough minerals");
+                }
+
+                self.new_omega_storage
+                    .as_mut()
+                    .unwrap()
+                    .remove_minerals(caller, cost);
+            }
+
+            if *self.open_gateways.get(&target_root).unwrap_or(&false) {
+                let seed: u64 = self.generate_random_seed();
+                let target = self.get_player_gateway_target(target_root, source, seed);
+                assert!(target.is_some(), "No gateway target");
+                self.connect_gateway(caller, source, target.unwrap());
+            } else {
+                let requests: &mut Vec<GatewayRequest> = self.gateway_requests
+                    .entry(target_root)
+                    .or_insert(Vec::default());
+
+                assert!(requests.len() < MAX_GATEWAY_REQUESTS, "Too many gateway requests");
+                assert!(!requests.iter().any(|request| request.source.root == caller),
+                    "Gateway already requested");
+
+                requests.push(GatewayRequest {
+                    source,
+                    cost,
+                });
+            }
+        }
+
+        /// Gets the gateways waiting for the acceptance of a player
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to get the requests for
+        ///
+        /// # Returns
+        ///
+        /// * `requests` - Vec of the pending GatewayRequests
+        #[ink(message)]
+        pub fn get_gateway_requests(&self, caller: AccountId) -> Vec<GatewayRequest> {
+            match self.gateway_requests.get(&caller) {
+                Some(requests) => requests.to_vec(),
+                None => Vec::default(),
+            }
+        }
+
+        /// Removes a pending gateway request
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player the gateway was requested from
+        /// * `source` - SystemCoordinate of the System requesting the gateway
+        ///
+        /// # Returns
+        ///
+        /// * `request` - The removed GatewayRequest
+        fn take_gateway_request(&mut self, caller: AccountId, source: SystemCoordinate) -> GatewayRequest {
+            assert!(self.gateway_requests.get(&caller).is_some(), "No gateway request");
+
+            let requests: &mut Vec<GatewayRequest> = self.gateway_requests.get_mut(&caller).unwrap();
+            let index: Option<usize> = requests
+                .iter()
+                .position(|request| request.source.key() == source.key());
+
+            assert!(index.is_some(), "No gateway request");
+            requests.remove(index.unwrap())
+        }
+
+        /// Accepts a gateway requested into a players Universe
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player accepting the gateway
+        /// * `source` - SystemCoordinate of the System requesting the gateway
+        /// * `target` - SystemCoordinate of the System in the players Universe to connect to
+        #[ink(message)]
+        pub fn accept_gateway(&mut self, caller: AccountId, source: SystemCoordinate, target: SystemCoordinate) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert_eq!(caller, target.root);
+
+            self.take_gateway_request(caller, source);
+            self.connect_gateway(source.root, source, target);
+        }
+
+        /// Rejects a gateway requested into a players Universe, refunding the requester
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player rejecting the gateway
+        /// * `source` - SystemCoordinate of the System requesting the gateway
+        #[ink(message)]
+        pub fn reject_gateway(&mut self, caller: AccountId, source: SystemCoordinate) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+
+            let request: GatewayRequest = self.take_gateway_request(caller, source);
+            if self.new_omega_storage.is_some() {
+                self.new_omega_storage
+                    .as_mut()
+                    .unwrap()
+                    .add_minerals(source.root, request.cost);
+            }
+        }
+
+        /// Destroys the gateway between two Systems. Either of the connected players can destroy it.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player destroying the gateway
+        /// * `source` - SystemCoordinate of the System with the gateway out
+        /// * `target` - SystemCoordinate of the System with the gateway in
+        #[ink(message)]
+        pub fn destroy_gateway(&mut self, caller: AccountId, source: SystemCoordinate, target: SystemCoordinate) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(caller == source.root || caller == target.root);
+            assert!(self.get_system(source)
+                .unwrap()
+                .gateways_out
+                .iter()
+                .any(|gateway| gateway.target.key() == target.key()), "No gateway");
+
+            self.remove_gateway(source, target);
+
+            self.env().emit_event(GatewayDestroyed {
+                source_root: source.root,
+                target_root: target.root,
+                source,
+                target,
+            });
+        }
+
+        /// Gets the planet defending the gateways of a System, the one with the highest level
+        ///
+        /// # Arguments
+        ///
+        /// * `system` - The System to get the defender of
+        ///
+        /// # Returns
+        ///
+        /// * `planet_id` - Which planet defends the gateways
+        pub fn get_gateway_defender(&self, system: &System) -> u8 {
+            let mut planet_id: usize = 0;
+            for (i, planet) in system.planets.iter().enumerate() {
+                if planet.level > system.planets[planet_id].level {
+                    planet_id = i;
+                }
+            }
+
+            planet_id as u8
+        }
+
+        /// Gets the owner of a planet
+        ///
+        /// # Arguments
+        ///
+        /// * `target` - SystemCoordinate of the System the planet is in
+        /// * `planet_id` - Which planet to get the owner of
+        ///
+        /// # Returns
+        ///
+        /// * `owner` - AccountId of the planet owner
+        #[ink(message)]
+        pub fn get_planet_owner(&self, target: SystemCoordinate, planet_id: u8) -> AccountId {
+            self.get_system(target).unwrap().planets[planet_id as usize].owner
+        }
+
+        /// Gets the owner of the planet defending the gateways of a System
+        ///
+        /// # Arguments
+        ///
+        /// * `target` - SystemCoordinate of the System
+        ///
+        /// # Returns
+        ///
+        /// * `owner` - AccountId of the defending planet owner
+        #[ink(message)]
+        pub fn get_gateway_defender_owner(&self, target: SystemCoordinate) -> AccountId {
+            let system: System = self.get_system(target).unwrap();
+            system.planets[self.get_gateway_defender(&system) as usize].owner
+        }
+
+        /// Attacks a gateway, fighting the garrison of the defending planet of its System.
+        /// If the attacker wins (including by the tie-break of the fight settings), the gateway
+        /// (both ends) is disabled for the gateway disable duration.
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player initating the attack
+        /// * `target` - SystemCoordinate of the System the attacked gateway is in
+        /// * `gateway_target` - SystemCoordinate of the System at the other end of the gateway
+        /// * `selection` - Fleet selection to use in the attack
+        /// * `modules` - Fleet modules to use in the attack
+        /// * `targeting` - Fleet targeting to use in the attack
+        ///
+        /// # Returns
+        ///
+        /// * `result` - The fight result
+        #[ink(message)]
+        pub fn attack_gateway(
+            &mut self,
+            caller: AccountId,
+            target: SystemCoordinate,
+            gateway_target: SystemCoordinate,
+            selection: [u8; MAX_SHIPS],
+            modules: [ShipModule; MAX_SHIPS],
+            targeting: TargetingType) -> FightResult {
+
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.is_connected(target, gateway_target), "No gateway");
+            assert!(self.can_attack_planet(caller, target), "Cant attack gateway");
+            assert!(self
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .has_enough_ships(caller, selection), "Not enough ships");
+            assert!(self.is_selection_active(selection), "Ship retired");
+
+            // Determine the seed, in a naive way -> IMPROVEME: MOVE TO VRF
+            let seed: u64 = self.generate_random_seed();
+            let target_system: System = self.get_system(target).unwrap();
+            let planet_id: u8 = self.get_gateway_defender(&target_system);
+            let defender = &target_system.planets[planet_id as usize];
+
+            // Calculate the fight result
+            let (result, _lhs_moves, _rhs_moves) =
+                self.new_omega_game.as_ref().unwrap().fight(
+                    seed,
+                    false,
+                    selection,
+                    defender.selection,
+                    modules,
+                    defender.modules,
+                    targeting,
+                    defender.targeting,
+                    self.get_defence_settings(defender));
+
+            let survivors: [u8; MAX_SHIPS] = self.get_garrison_survivors(defender, result.ships_lost_rhs);
+            self.get_system_mut(target).planets[planet_id as usize].selection = survivors;
+
+            if result.outcome == Outcome::AttackerWin {
+                let disabled_until: BlockNumber = self.env().block_number() +
+                    self.get_parameters().gateway_disable_blocks;
+                self.disable_gateway(target, gateway_target, disabled_until);
+            }
+
+            let ships_lost_u32: [u32; MAX_SHIPS] = widen_selection(&result.ships_lost_lhs);
+
+            self
+                .new_omega_storage
+                .as_mut()
+                .unwrap()
+                .remove_ships(caller, ships_lost_u32);
+
+            result
+        }
+
+        /// Gets general game statistics
+        ///
+        /// # Returns
+        ///
+        /// * `stats` - The game statistics
+        #[ink(message)]
+        pub fn get_game_stats(&self) -> GameStats {
+            GameStats {
+                no_players: self.system_index.len() as u64,
+                no_systems: self.systems.len() as u64,
+            }
+        }
+
+        /// Checks whether a player is owner of a system
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - AccountId of the player in whos Universe to build a gateway target
+        /// * `system` - The System to check players ownership of
+        ///
+        /// # Returns
+        ///
+        /// * `is_owner` - Whether the player is owner of that system
+        pub fn is_owner_of_system(&self, caller: AccountId, system: &System) -> bool {
+            let mut caller_planet_count: u8 = 0;
+            for planet in system.planets.iter() {
+                if planet.owner == caller {
+                    caller_planet_count += 1;
+                }
+            }
+
+            caller_planet_count >= (PLANETS_FOR_CONTROL as u8)
+        }
+
+        /// Worker function to generate adjacent system coordinates for a given system
+        ///
+        /// # Arguments
+        ///
+        /// * `system` - The System to generate adjacent coordinates for 
+        ///
+        /// # Returns
+        ///
+        /// * `coords` - The list of adjacent coordinates
+        pub fn get_adjacent_system_coords(&self, system: &SystemCoordinate) -> [SystemCoordinate; 4] {
+            [
+                SystemCoordinate {
+                    root: system.root,
+                    position_x: system.position_x - 1,
+                    position_y: system.position_y,
+                },
+                SystemCoordinate {
+                    root: system.root,
+                    position_x: system.position_x + 1,
+                    position_y: system.position_y,
+                },
+                SystemCoordinate {
+                    root: system.root,
+                    position_x: system.position_x,
+                    position_y: system.position_y - 1,
+                },
+                SystemCoordinate {
+                    root: system.root,
+                    position_x: system.position_x,
+                    position_y: system.position_y + 1,
+                },
+            ]
+        }
+
+        /// Checks whether a player can attack a planet in a given system
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to check attackability for
+        /// * `target` - The System to check attackability of
+        ///
+        /// # Returns
+        ///
+        /// * `can_attack` - Whether a player can attack a system
+        pub fn can_attack_planet(&self, caller: AccountId, target: SystemCoordinate) -> bool {
+            if target.root == caller {
+                return true;
+            }
+
+            let system: System = self.get_system(target).unwrap();
+            if self.is_owner_of_system(caller, &system) {
+                return true;
+            }
+
+            let mut owner_found: bool = false;
+            let adjacent_systems_coords: [SystemCoordinate; 4] = self.get_adjacent_system_coords(&target);
+            for adjacent_system_coords in adjacent_systems_coords.iter() {
+                if let Some(ref adjacent_system) = self.get_system(*adjacent_system_coords) {
+                    owner_found |= self.is_owner_of_system(caller, &adjacent_system);
+                }
+
+            }
+
+            if !owner_found {
+                // check if the system has an active gateway, pointing to a system owned by caller
+                for gateway in system.gateways_in.iter().chain(system.gateways_out.iter()) {
+                    if self.is_gateway_active(gateway) {
+                        let gateway_system: System = self.get_system(gateway.target).unwrap();
+                        owner_found |= self.is_owner_of_system(caller, &gateway_system);
+                    }
+                }
+            }
+
+            owner_found
+        }
+
+        pub fn min(lhs: u32, rhs: u32) -> u32 {
+            if lhs < rhs {
+                lhs
+            } else {
+                rhs
+            }
+        }
+
+        /// Gets the Assets for a given player
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to get Assets for
+        ///
+        /// # Returns
+        ///
+        /// * `assets` - The Assets
+        #[ink(message)]
+        pub fn get_player_assets(&self, caller: AccountId) -> PlayerAssets {
+            assert!(self.assets.get(&caller).is_some());
+            let asset: &PlayerAssets = self.assets.get(&caller).unwrap();
+
+            PlayerAssets {
+                name: asset.name.clone(),
+            }
+        }
+
+        /// Gets the Universe Map for a given player, as seen by the caller
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player viewing the Map
+        /// * `root` - The player to get the Map for
+        ///
+        /// # Returns
+        ///
+        /// * `map` - Vec of Systems comprising the Universe Map of a player
+        #[ink(message)]
+        pub fn get_universe_map(&self, caller: AccountId, root: AccountId) -> Vec<System> {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            self.get_system_coords(root)
+                .iter()
+                .map(|coord| self.apply_fog_of_war(caller, self.systems.get(&coord.key()).unwrap().clone()))
+                .collect()
+        }
+
+        /// Gets the number of Systems in a players Universe
+        ///
+        /// # Arguments
+        ///
+        /// * `root` - The player to get the count for
+        ///
+        /// # Returns
+        ///
+        /// * `count` - Number of discovered Systems
+        #[ink(message)]
+        pub fn get_system_count(&self, root: AccountId) -> u32 {
+            assert!(self.system_index.get(&root).is_some());
+
+            self.system_index
+                .get(&root)
+                .unwrap()
+                .len() as u32
+        }
+
+        /// Gets the Systems of a players Universe within a rectangular region (inclusive),
+        /// as seen by the caller
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player viewing the region
+        /// * `root` - The player to get the region for
+        /// * `x_min` - Lowest x coordinate of the region
+        /// * `x_max` - Highest x coordinate of the region
+        /// * `y_min` - Lowest y coordinate of the region
+        /// * `y_max` - Highest y coordinate of the region
+        ///
+        /// # Returns
+        ///
+        /// * `systems` - Vec of the discovered Systems within the region
+        #[ink(message)]
+        pub fn get_universe_region(&self, caller: AccountId, root: AccountId, x_min: i32, x_max: i32,
+            y_min: i32, y_max: i32) -> Vec<System> {
+
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.system_index.get(&root).is_some());
+            assert!(x_min <= x_max && y_min <= y_max, "Invalid region");
+
+            let area: i64 = (x_max as i64 - x_min as i64 + 1) * (y_max as i64 - y_min as i64 + 1);
+            assert!(area <= MAX_REGION_AREA, "Region too big");
+
+            let mut systems: Vec<System> = Vec::new();
+            for position_x in x_min..=x_max {
+                for position_y in y_min..=y_max {
+                    if let Some(system) = self.systems.get(&(root, position_x, position_y)) {
+                        systems.push(self.apply_fog_of_war(caller, system.clone()));
+                    }
+                }
+            }
+
+            systems
+        }
+
+        /// Gets a page of the Systems of a players Universe, in order of discovery,
+        /// as seen by the caller
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player viewing the page
+        /// * `root` - The player to get the page for
+        /// * `offset` - How many Systems to skip
+        /// * `limit` - How many Systems to return at most (capped at MAX_PAGE_SIZE)
+        ///
+        /// # Returns
+        ///
+        /// * `systems` - Vec of the Systems on the page
+        #[ink(message)]
+        pub fn get_universe_page(&self, caller: AccountId, root: AccountId, offset: u32, limit: u32) -> Vec<System> {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            let limit: u32 = NewOmegaUniverse::min(limit, MAX_PAGE_SIZE);
+
+            self.get_system_coords(root)
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .map(|coord| self.apply_fog_of_war(caller, self.systems.get(&coord.key()).unwrap().clone()))
+                .collect()
+        }
+
+        /// Gets a page of lightweight System summaries (planet ownership and level only)
+        /// of a players Universe, in order of discovery
+        ///
+        /// # Arguments
+        ///
+        /// * `root` - The player to get the summaries for
+        /// * `offset` - How many Systems to skip
+        /// * `limit` - How many Systems to return at most (capped at MAX_SUMMARY_PAGE_SIZE)
+        ///
+        /// # Returns
+        ///
+        /// * `summaries` - Vec of the System summaries on the page
+        #[ink(message)]
+        pub fn get_universe_summary(&self, root: AccountId, offset: u32, limit: u32) -> Vec<SystemSummary> {
+            let limit: u32 = NewOmegaUniverse::min(limit, MAX_SUMMARY_PAGE_SIZE);
+
+            self.get_system_coords(root)
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .map(|coord| SystemSummary::new(self.systems.get(&coord.key()).unwrap()))
+                .collect()
+        }
+
+        /// Gets for how many blocks a planet accumulates minerals between harvests
+        ///
+        /// # Arguments
+        ///
+        /// * `planet_type` - The definition of the planet type
+        /// * `parameters` - The game parameters
+        ///
+        /// # Returns
+        ///
+        /// * `blocks` - The maximum number of harvestable blocks
+        fn get_harvestable_blocks(planet_type: &PlanetTypeDefinition, parameters: &GameParameters) -> BlockNumber {
+            if planet_type.has_ability(PLANET_ABILITY_DEEP_CORE) {
+                2 * parameters.max_harvestable_blocks
+            } else {
+                parameters.max_harvestable_blocks
+            }
+        }
+
+        /// Calculates the amount of minerals a planet has generated since it was last harvested
+        ///
+        /// # Arguments
+        ///
+        /// * `planet` - The planet to calculate the harvest for
+        /// * `planet_type` - The definition of the planet type
+        /// * `block_number` - The block number to harvest at
+        /// * `parameters` - The game parameters
+        ///
+        /// # Returns
+        ///
+        /// * `amount` - The amount of minerals (of the planet mineral type) generated,
+        ///   saturated at u32::MAX
+        fn calculate_harvest(planet: &Planet, planet_type: &PlanetTypeDefinition, block_number: BlockNumber,
+            parameters: &GameParameters) -> u32 {
+            let max_blocks: BlockNumber = NewOmegaUniverse::get_harvestable_blocks(planet_type, parameters);
+            let block_diff: BlockNumber = NewOmegaUniverse::min(block_number - planet.last_harvested, max_blocks);
+            let amount: u64 = ((block_diff / parameters.mineral_generation_blocks) as u64)
+                .saturating_mul(planet.level as u64)
+                .saturating_mul(planet.mineral_proof as u64)
+                .saturating_mul(planet_type.harvest_multipliers[planet.mineral_type as usize] as u64)
+                / 100;
+
+            amount.min(u32::MAX as u64) as u32
+        }
+
+        /// Harvest all planets the player owns, in all the Universes
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to harvest planets for
+        #[ink(message)]
+        pub fn harvest(&mut self, caller: AccountId) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.system_index.get(&caller).is_some());
+
+            let block_number = self.env().block_number();
+            let mut harvested: [u32; MAX_MINERALS] = [0; MAX_MINERALS];
+            let planet_types: Vec<PlanetTypeDefinition> = self.planet_types.clone();
+            let parameters: GameParameters = self.get_parameters();
+            let locations: Vec<PlanetCoordinate> = self.get_owned_planets(caller);
+            for location in locations.iter() {
+                let system: &mut System = self.get_system_mut(location.system);
+                let planet: &mut Planet = &mut system.planets[location.planet_id as usize];
+                let amount: u32 = NewOmegaUniverse::calculate_harvest(planet,
+                    &planet_types[planet.planet_type as usize], block_number, &parameters);
+                harvested[planet.mineral_type as usize] = harvested[planet.mineral_type as usize].saturating_add(amount);
+                planet.last_harvested = block_number;
+            }
+
+            self.new_omega_storage
+                .as_mut()
+                .unwrap()
+                .add_minerals(caller, harvested);
+
+            self.env().emit_event(Harvested {
+                player: caller,
+                minerals: harvested,
             });
+        }
 
-            self.systems
-                .insert(caller, new_systems);
-            self.assets
-                .insert(caller, PlayerAssets::new(name));
-            if self.new_omega_storage.is_some() {
-                self.new_omega_storage
-                    .as_mut()
-                    .unwrap()
-                    .ensure_minerals(caller);
-                self.new_omega_storage
-                    .as_mut()
+        /// Gets what harvesting all the planets a player owns would yield at the current block
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to get the pending harvest for
+        ///
+        /// # Returns
+        ///
+        /// * `pending` - The PendingHarvest, per planet and in total
+        #[ink(message)]
+        pub fn get_pending_harvest(&self, caller: AccountId) -> PendingHarvest {
+            let block_number = self.env().block_number();
+            let mut planets: Vec<PlanetHarvest> = Vec::new();
+            let mut total: [u32; MAX_MINERALS] = [0; MAX_MINERALS];
+            let parameters: GameParameters = self.get_parameters();
+            let mut blocks_until_cap: BlockNumber = 2 * parameters.max_harvestable_blocks;
+
+            for location in self.get_owned_planets(caller).iter() {
+                let planet: &Planet = &self.systems
+                    .get(&location.system.key())
                     .unwrap()
-                    .ensure_trades(caller);
-                self.new_omega_storage
-                    .as_mut()
+                    .planets[location.planet_id as usize];
+                let planet_type: &PlanetTypeDefinition = self.get_planet_type(planet);
+                let amount: u32 = NewOmegaUniverse::calculate_harvest(planet, planet_type, block_number, &parameters);
+                let max_blocks: BlockNumber = NewOmegaUniverse::get_harvestable_blocks(planet_type, &parameters);
+                let block_diff: BlockNumber = NewOmegaUniverse::min(block_number - planet.last_harvested, max_blocks);
+                let planet_blocks_until_cap: BlockNumber = max_blocks - block_diff;
+
+                total[planet.mineral_type as usize] = total[planet.mineral_type as usize].saturating_add(amount);
+                blocks_until_cap = NewOmegaUniverse::min(blocks_until_cap, planet_blocks_until_cap);
+                planets.push(PlanetHarvest {
+                    location: *location,
+                    mineral_type: planet.mineral_type,
+                    amount,
+                    blocks_until_cap: planet_blocks_until_cap,
+                });
+            }
+
+            if planets.is_empty() {
+                blocks_until_cap = 0;
+            }
+
+            PendingHarvest {
+                planets,
+                total,
+                blocks_until_cap,
+            }
+        }
+
+        /// Gets an overview of all the planets a player owns, in all the Universes
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to get the overview for
+        ///
+        /// # Returns
+        ///
+        /// * `overview` - The EmpireOverview
+        #[ink(message)]
+        pub fn get_empire_overview(&self, caller: AccountId) -> EmpireOverview {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            let mut planets: Vec<(PlanetCoordinate, Planet)> = Vec::new();
+            let mut roots: Vec<AccountId> = Vec::new();
+            let mut total_garrison: [u32; MAX_SHIPS] = [0; MAX_SHIPS];
+            let mut total_levels: u32 = 0;
+
+            for location in self.get_owned_planets(caller).iter() {
+                let planet: Planet = self.systems
+                    .get(&location.system.key())
                     .unwrap()
-                    .ensure_ships(caller);
+                    .planets[location.planet_id as usize]
+                    .clone();
+
+                for (total, &count) in total_garrison.iter_mut().zip(planet.selection.iter()) {
+                    *total += count as u32;
+                }
+                total_levels += planet.level as u32;
+                if !roots.contains(&location.system.root) {
+                    roots.push(location.system.root);
+                }
+                planets.push((*location, planet));
+            }
+
+            EmpireOverview {
+                planets,
+                universes: roots.len() as u32,
+                total_garrison,
+                total_levels,
             }
         }
 
-        /// Translates AccountIds of players into their registered names
+        /// Harvest a specific planet that the player owns
         ///
         /// # Arguments
         ///
-        /// * `players` - The AccountIds of players to get names for
+        /// * `caller` - The player to harvest planet for
+        /// * `target`- SystemCoordinate of the system the planet to harvest is in
+        /// * `planet_id` - Which planet to harvest
+        #[ink(message)]
+        pub fn harvest_planet(&mut self, caller: AccountId, target: SystemCoordinate, planet_id: u8) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.system_index.get(&caller).is_some());
+
+            let block_number = self.env().block_number();
+            let mut harvested: [u32; MAX_MINERALS] = [0; MAX_MINERALS];
+            let planet_types: Vec<PlanetTypeDefinition> = self.planet_types.clone();
+            let parameters: GameParameters = self.get_parameters();
+
+            let system: &mut System = self.get_system_mut(target);
+            let planet: &mut Planet = &mut system.planets[planet_id as usize];
+
+            assert_eq!(planet.owner, caller);
+
+            let amount: u32 = NewOmegaUniverse::calculate_harvest(planet,
+                &planet_types[planet.planet_type as usize], block_number, &parameters);
+            harvested[planet.mineral_type as usize] = amount;
+            planet.last_harvested = block_number;
+
+            self.new_omega_storage
+                .as_mut()
+                .unwrap()
+                .add_minerals(caller, harvested);
+
+            self.env().emit_event(Harvested {
+                player: caller,
+                minerals: harvested,
+            });
+        }
+
+        /// Calculates the mineral cost of upgrading a planet from its current level
+        ///
+        /// # Arguments
+        ///
+        /// * `planet` - The planet to calculate the cost for
         ///
         /// # Returns
         ///
-        /// * `names` - Vec of Strings containing the names
-        #[ink(message)]
-        pub fn get_player_names(&self, players: Vec<AccountId>) -> Vec<String> {
-            let mut translated: Vec<String> = Vec::new();
-            for player in players {
-                if player == AccountId::default() {
-                    translated.push(String::from("None"));
-                } else {
-                    let asset: &PlayerAssets = self.assets.get(&player).unwrap();
-                    translated.push(asset.name.clone());
-                }
-            }
+        /// * `costs` - The amounts of minerals the upgrade costs
+        fn calculate_upgrade_cost(&self, planet: &Planet) -> [u32; MAX_MINERALS] {
+            let level: u64 = planet.level as u64;
+            let table: &UpgradeCostTable = &self.upgrade_costs;
+            let base_cost: u64 = table.base_cost as u64 +
+                table.cost_per_level as u64 * level +
+                table.cost_per_level_squared as u64 * level * level;
+            let cost: u64 = base_cost * self.get_planet_type(planet).upgrade_cost_modifier as u64 / 100;
+
+            let mut costs: [u32; MAX_MINERALS] = [0; MAX_MINERALS];
+            costs[planet.mineral_type as usize] = if cost > u32::MAX as u64 {
+                u32::MAX
+            } else {
+                cost as u32
+            };
 
-            translated
+            costs
         }
 
-        /// Reinforces a players owned planet with a fleet selection and sets tactics.
+        /// Gets the mineral cost of the next upgrade of a planet
         ///
         /// # Arguments
         ///
-        /// * `caller` - AccountId of the player
-        /// * `target` - The location of the planet to reinforce
-        /// * `planet_id` - Which planet to attack (0..MAX_PLANETS)
-        /// * `selection` - The fleet selection to register
-        /// * `modules` - The modules to register with the fleet
-        /// * `targeting` - The targeting to register with the fleet
+        /// * `target`- SystemCoordinate of the system the planet is in
+        /// * `planet_id` - Which planet to get the upgrade cost of
+        ///
+        /// # Returns
+        ///
+        /// * `costs` - The amounts of minerals the next upgrade costs
         #[ink(message)]
-        pub fn reinforce_planet(&mut self, caller: AccountId, target: SystemCoordinate,
-            planet_id: u8, selection: [u8; MAX_SHIPS], modules: [ShipModule; MAX_SHIPS],
-            targeting: TargetingType) {
+        pub fn get_upgrade_cost(&self, target: SystemCoordinate, planet_id: u8) -> [u32; MAX_MINERALS] {
+            let system: System = self.get_system(target).unwrap();
+            let planet: &Planet = &system.planets[planet_id as usize];
+
+            assert!(planet.level < MAX_PLANET_LEVEL, "Planet at max level");
+
+            self.calculate_upgrade_cost(planet)
+        }
+
+        /// Gets the maximum number of ships a planet garrison can hold
+        ///
+        /// # Arguments
+        ///
+        /// * `planet` - The planet to get the capacity of
+        ///
+        /// # Returns
+        ///
+        /// * `capacity` - The garrison capacity
+        pub fn get_garrison_capacity(&self, planet: &Planet) -> u32 {
+            let capacity: u32 = self.get_planet_type(planet).garrison_capacity +
+                GARRISON_CAPACITY_PER_LEVEL * planet.level as u32;
+
+            NewOmegaUniverse::min(capacity, MAX_GARRISON)
+        }
+
+        /// Gets the extra Health Points a planet garrison defends with: the defence bonus of
+        /// the planet type, and DEFENCE_BONUS_PER_LEVEL percent for every level above the first.
+        ///
+        /// # Arguments
+        ///
+        /// * `planet` - The defending planet
+        ///
+        /// # Returns
+        ///
+        /// * `bonus` - The extra Health Points of the garrison, in percent
+        pub fn get_defence_bonus(&self, planet: &Planet) -> u16 {
+            self.get_planet_type(planet).defence_bonus +
+                DEFENCE_BONUS_PER_LEVEL * planet.level.saturating_sub(1) as u16
+        }
 
-            if self.owner.is_some() {
-                assert_eq!(self.env().caller(), self.owner.unwrap());
+        /// Gets the settings a planet is attacked with: the Universe fight settings,
+        /// with the defence bonus of the planet given to the garrison.
+        ///
+        /// # Arguments
+        ///
+        /// * `planet` - The defending planet
+        ///
+        /// # Returns
+        ///
+        /// * `settings` - The settings of the fight for the planet
+        pub fn get_defence_settings(&self, planet: &Planet) -> FightSettings {
+            FightSettings {
+                hp_bonus_rhs: self.get_defence_bonus(planet),
+                ..*self.fight_settings
             }
+        }
 
-            let system = self.get_system(target).unwrap();
-            let planet = &system.planets[planet_id as usize];
+        /// Upgrades a specific planet that the player owns, paying for the upgrade
+        /// in minerals of the planet mineral type
+        ///
+        /// # Arguments
+        ///
+        /// * `caller` - The player to upgrade planet for
+        /// * `target`- SystemCoordinate of the system the planet to upgrade is in
+        /// * `planet_id` - Which planet to upgrade
+        #[ink(message)]
+        pub fn upgrade_planet(&mut self, caller: AccountId, target: SystemCoordinate, planet_id: u8) {
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.system_index.get(&caller).is_some());
+
+            let system: System = self.get_system(target).unwrap();
+            let planet: &Planet = &system.planets[planet_id as usize];
 
             assert_eq!(planet.owner, caller);
+            assert!(planet.level < MAX_PLANET_LEVEL);
 
-            let mut planet_selection: [u32; MAX_SHIPS] = [0; MAX_SHIPS];
-            for i in 0..MAX_SHIPS {
-                planet_selection[i] = planet.selection[i] as u32;
-            }
-            let mut selection_u32: [u32; MAX_SHIPS] = [0; MAX_SHIPS];
-            for i in 0..MAX_SHIPS {
-                selection_u32[i] = selection[i] as u32;
-            }
+            if self.new_omega_storage.is_some() {
+                let costs: [u32; MAX_MINERALS] = self.calculate_upgrade_cost(planet);
+                let minerals: [u32; MAX_MINERALS] = self
+                    .new_omega_storage
+                    .as_ref()
+                    .unwrap()
+                    .get_player_minerals(caller);
 
-            self    
-                .new_omega_storage
-                .as_mut()
-                .unwrap()
-                .add_ships(caller, planet_selection);
+                assert!(minerals[planet.mineral_type as usize] >= costs[planet.mineral_type as usize],
+                    "Not enough minerals");
 
-            let has_enough = self
-                .new_omega_storage
-                .as_ref()
-                .unwrap()
-                .has_enough_ships(caller, selection);
+                self.new_omega_storage
+                    .as_mut()
+                    .unwrap()
+                    .remove_minerals(caller, costs);
+            }
 
-            assert!(has_enough);
+            let system_mut: &mut System = self.get_system_mut(target);
+            let planet_mut: &mut Planet = &mut system_mut.planets[planet_id as usize];
+            planet_mut.level += 1;
+            let level: u8 = planet_mut.level;
 
-            self    
-                .new_omega_storage
-                .as_mut()
-                .unwrap()
-                .remove_ships(caller, selection_u32);
+            self.env().emit_event(PlanetUpgraded {
+                owner: caller,
+                location: PlanetCoordinate {
+                    system: target,
+                    planet_id,
+                },
+                level,
+            });
+        }
 
-            let system = self.get_system_mut(target);
-            let mut planet_mut = &mut system.planets[planet_id as usize];
-            planet_mut.selection = selection;
-            planet_mut.modules = modules;
-            planet_mut.targeting = targeting;
+        /// Gets the garrison of a planet which survives a fight.
+        ///
+        /// # Arguments
+        ///
+        /// * `planet` - The defending planet
+        /// * `ships_lost` - The defender ship losses, as reported by the fight result
+        ///
+        /// # Returns
+        ///
+        /// * `selection` - The surviving garrison
+        pub fn get_garrison_survivors(&self, planet: &Planet, ships_lost: [u8; MAX_SHIPS]) -> [u8; MAX_SHIPS] {
+            let mut survivors: [u8; MAX_SHIPS] = [0; MAX_SHIPS];
+            for i in 0..MAX_SHIPS {
+                survivors[i] = planet.selection[i].saturating_sub(ships_lost[i]);
+            }
+
+            survivors
         }
 
-        /// Generates a new set of planets
+        /// Attacks a planet. The defending garrison is reduced by its losses. If the attacker wins
+        /// (including by the tie-break of the fight settings), the planet is captured: the attacker
+        /// leaves `garrison` (taken from the surviving ships) behind to defend it, with the modules
+        /// and targeting used in the attack, and the surviving defenders return to the previous owner.
         ///
         /// # Arguments
         ///
-        /// * `target` - SystemCoordinate where to generate planets in
-        /// * `start_ownership` - How many planets will be assigned to the player
-        /// * `owner` - When starting ownership, who will be the owner
+        /// * `caller` - The player initating the attack
+        /// * `target`- SystemCoordinate of the system the planet to attack is in
+        /// * `planet_id` - Which planet to attack
+        /// * `selection` - Fleet selection to use in the attack
+        /// * `modules` - Fleet modules to use in the attack
+        /// * `targeting` - Fleet targeting to use in the attack
+        /// * `garrison` - Ships to leave on the planet if captured, at most the surviving ships
         ///
         /// # Returns
         ///
-        /// * `planets` - Vec of newly generated Planets
-        pub fn generate_planets(&self, target: SystemCoordinate, start_ownership: u8, owner: Option<AccountId>) -> Vec<Planet> {
+        /// * `result` - The fight result
+        #[ink(message)]
+        pub fn attack_planet(
+            &mut self,
+            caller: AccountId,
+            target: SystemCoordinate,
+            planet_id: u8,
+            selection: [u8; MAX_SHIPS],
+            modules: [ShipModule; MAX_SHIPS],
+            targeting: TargetingType,
+            garrison: [u8; MAX_SHIPS]) -> FightResult {
+
+            self.access.ensure_role(self.env().caller(), Role::Delegator);
+            assert!(self.can_attack_planet(caller, target), "Cant attack planet");
+            assert!(self
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .has_enough_ships(caller, selection), "Not enough ships");
+            assert!(self.is_selection_active(selection), "Ship retired");
+
+            // Determine the seed, in a naive way -> IMPROVEME: MOVE TO VRF
             let seed: u64 = self.generate_random_seed();
-            let block_number = self.env().block_number();
-            let mut new_planets: Vec<Planet> = Vec::default();
-            for i in 0..MAX_PLANETS {
-                let mut new_planet = Planet::default();
-                let sub_seed: u64 =
-                    (i as u64) +
-                    (target.position_x as u64) +
-                    (target.position_y as u64);
-                new_planet.planet_type = self.generate_planet_type(seed, sub_seed);
-                new_planet.mineral_type = self.generate_mineral_type(seed, sub_seed);
-                new_planet.mineral_proof = self.generate_mineral_proof(seed, sub_seed);
-                new_planet.last_harvested = block_number;
-                if start_ownership > 0 && i < (start_ownership as usize) {
-                    new_planet.owner = owner.unwrap();
+            let target_system_wrapped: Option<System> = self.get_system(target);
+
+            assert!(target_system_wrapped.is_some(), "Target doesnt exist");
+
+            let target_system: System = target_system_wrapped.unwrap();
+            let target_planet = &target_system.planets[planet_id as usize];
+
+            // Calculate the fight result
+            let (result, _lhs_moves, _rhs_moves) =
+                self.new_omega_game.as_ref().unwrap().fight(
+                    seed,
+                    false,
+                    selection,
+                    target_planet.selection,
+                    modules,
+                    target_planet.modules,
+                    targeting,
+                    target_planet.targeting,
+                    self.get_defence_settings(target_planet));
+
+            let mut ships_lost_u32: [u32; MAX_SHIPS] = widen_selection(&result.ships_lost_lhs);
+
+            if result.outcome == Outcome::AttackerWin {
+                let garrison_size: u32 = garrison.iter().map(|&count| count as u32).sum();
+                assert!(garrison_size <= self.get_garrison_capacity(target_planet), "Garrison too big");
+                for i in 0..MAX_SHIPS {
+                    assert!(garrison[i] as u32 <= selection[i] as u32 - ships_lost_u32[i], "Garrison exceeds survivors");
+                    // The garrison leaves the hangar together with the lost ships
+                    ships_lost_u32[i] += garrison[i] as u32;
                 }
-                new_planets.push(new_planet);
+
+                // A win by the tie-break leaves part of the defending garrison alive,
+                // it retreats to the hangar of the previous owner
+                let previous_owner: AccountId = target_planet.owner;
+                if previous_owner != AccountId::default() {
+                    let survivors: [u8; MAX_SHIPS] = self.get_garrison_survivors(target_planet, result.ships_lost_rhs);
+                    let survivors_u32: [u32; MAX_SHIPS] = widen_selection(&survivors);
+
+                    self
+                        .new_omega_storage
+                        .as_mut()
+                        .unwrap()
+                        .add_ships(previous_owner, survivors_u32);
+                }
+
+                self.set_planet_owner(PlanetCoordinate {
+                    system: target,
+                    planet_id,
+                }, caller);
+
+                let target_system_mut: &mut System = self.get_system_mut(target);
+                let target_planet_mut: &mut Planet = &mut target_system_mut.planets[planet_id as usize];
+
+                target_planet_mut.selection = garrison;
+                target_planet_mut.modules = modules;
+                target_planet_mut.targeting = targeting;
+            } else {
+                let survivors: [u8; MAX_SHIPS] = self.get_garrison_survivors(target_planet, result.ships_lost_rhs);
+                let target_system_mut: &mut System = self.get_system_mut(target);
+                let target_planet_mut: &mut Planet = &mut target_system_mut.planets[planet_id as usize];
+
+                target_planet_mut.selection = survivors;
             }
 
-            new_planets
+            self
+                .new_omega_storage
+                .as_mut()
+                .unwrap()
+                .remove_ships(caller, ships_lost_u32);
+
+            result
+        }
+    }
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use newomega::TieBreak;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+        type Accounts = test::DefaultAccounts<Environment>;
+
+        fn default_accounts() -> Accounts {
+            test::default_accounts()
+                .expect("Test environment is expected to be initialized.")
+        }
+
+        fn set_sender(sender: AccountId) {
+            let callee: AccountId = test::get_current_contract_account_id::<Environment>()
+                .expect("Cannot get contract id");
+            test::push_execution_context::<Environment>(
+                sender,
+                callee,
+                1000000,
+                0,
+                test::CallData::new(call::Selector::new([0x00; 4])),
+            );
+        }
+
+        fn prepare_contract() -> NewOmegaUniverse {
+            let mut contract: NewOmegaUniverse = NewOmegaUniverse::default();
+            contract.authorise_delegator(default_accounts().alice);
+            contract
+        }
+
+        fn prepare_connected_contract() -> NewOmegaUniverse {
+            let mut contract: NewOmegaUniverse = NewOmegaUniverse::new(NewOmegaGame::default(),
+                NewOmegaStorage::default(), NewOmegaParameters::default());
+            contract.authorise_delegator(default_accounts().alice);
+            contract
+        }
+
+        #[ink::test]
+        fn test_register() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let player: AccountId = accounts.alice;
+
+            contract.register_player(player, String::from("Test"));
+
+            let system: Option<System> = contract.get_system(SystemCoordinate {
+                root: player,
+                position_x: 0,
+                position_y: 0,
+            });
+            assert!(system.is_some());
+
+            let system_unwrapped = system.unwrap();
+            assert_eq!(system_unwrapped.position.root, player);
+            assert_eq!(system_unwrapped.position.position_x, 0);
+            assert_eq!(system_unwrapped.position.position_y, 0);
+            assert_eq!(system_unwrapped.planets[0].selection, [10; 4]);
+            assert_eq!(system_unwrapped.planets[3].selection, [10; 4]);
+            assert_eq!(system_unwrapped.planets[0].owner, accounts.alice);
+            assert_eq!(system_unwrapped.planets[1].owner, accounts.alice);
+            assert_eq!(system_unwrapped.planets[2].owner, accounts.alice);
+            assert_eq!(system_unwrapped.planets[3].owner, AccountId::default());
+            assert_eq!(system_unwrapped.planets[4].owner, AccountId::default());
         }
 
-        /// Renames a planet
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - The player making the rename request
-        /// * `target` - SystemCoordinate of the the planet to rename
-        /// * `planet_id` - Which planet to rename (0..MAX_PLANETS)
-        /// * `name` - The name to set
-        #[ink(message)]
-        pub fn rename_planet(&mut self, caller: AccountId, target: SystemCoordinate, planet_id: u8, name: String) {
-            if self.owner.is_some() {
-                assert_eq!(self.env().caller(), self.owner.unwrap());
-            }
-            assert!(self.get_system(target).is_some());
+        #[ink::test]
+        fn test_systems() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let player: AccountId = accounts.alice;
 
-            let system = self.get_system_mut(target);
-            let mut planet_mut = &mut system.planets[planet_id as usize];
+            contract.register_player(player, String::from("Test"));
 
-            assert_eq!(planet_mut.owner, caller);
+            let system: Option<System> = contract.get_system(SystemCoordinate {
+                root: player,
+                position_x: 1,
+                position_y: 1,
+            });
+            assert!(system.is_none());
 
-            planet_mut.name = Some(name);
+            let map: Vec<System> = contract.get_universe_map(player, player);
+            assert_eq!(map.len(), 1);
+            let system_first: &System = &map[0];
+            assert_eq!(system_first.position.root, player);
+            assert_eq!(system_first.position.position_x, 0);
+            assert_eq!(system_first.position.position_y, 0);
         }
 
-        /// Discovers a given system for a player
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - AccountId of the player making the discovery request
-        /// * `target` - SystemCoordinate to discover a System in
-        #[ink(message)]
-        pub fn discover_system(&mut self, caller: AccountId, target: SystemCoordinate) {
-            if self.owner.is_some() {
-                assert_eq!(self.env().caller(), self.owner.unwrap());
-            }
-            assert!(self.systems.get(&target.root).is_some());
-            assert!(self.get_system(target).is_none());
+        #[ink::test]
+        fn test_discovery() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let player: AccountId = accounts.alice;
 
-            // TODO verify if player owns an adjacent system
+            contract.register_player(player, String::from("Test"));
 
-            let new_planets = self.generate_planets(target, 0, None);
-            let systems = self.systems.get_mut(&target.root).unwrap();
-            systems.push(System {
-                position: target,
-                discoverer: caller,
-                planets: new_planets,
-                gateway_in: Gateway::default(),
-                gateway_out: Gateway::default(),
+            contract.discover_system(player, SystemCoordinate {
+                root: player,
+                position_x: 1,
+                position_y: 0,
             });
         }
 
-        /// Gets a random discoverable system for a given player
-        ///
-        /// # Arguments
-        ///
-        /// * `root` - AccountId of the player in whos Universe to find a discoverable system
-        ///
-        /// # Returns
-        ///
-        /// * `coord` - The coordinate of the system, or None
-        pub fn get_random_discoverable_system(&self, root: AccountId) -> Option<SystemCoordinate> {
-            let systems = self.systems.get(&root).unwrap();
-            for system in systems {
-                let adjacent_systems_coords: [SystemCoordinate; 4] =
-                    self.get_adjacent_system_coords(&system.position);
-                for adjacent_system_coords in adjacent_systems_coords.iter() {
-                    if adjacent_system_coords.position_x >= 0 &&
-                        adjacent_system_coords.position_y >= 0 {
-
-                        match self.get_system(*adjacent_system_coords) {
-                            None => return Some(*adjacent_system_coords),
-                            _ => (),
-                        }
-                    }
-                }
-            }
+        #[ink::test]
+        fn test_discovery_rules() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let bob: AccountId = accounts.bob;
 
-            None
-        }
+            contract.register_player(alice, String::from("Alice"));
+            contract.register_player(bob, String::from("Bob"));
 
-        /// Gets a target for a new gateway in a players territory
-        ///
-        /// # Arguments
-        ///
-        /// * `root` - AccountId of the player in whos Universe to find a gateway target
-        /// * `seed` - The random seed
-        ///
-        /// # Returns
-        ///
-        /// * `coord` - The coordinate of the system, or None
-        pub fn get_player_gateway_target(&self, root: AccountId, seed: u64) -> Option<SystemCoordinate> {
-            let systems = self.systems.get(&root).unwrap();
-            let avail_systems = systems
-                .iter()
-                .filter(|system| !system.gateway_in.built);
+            let coord = |x: i32, y: i32| SystemCoordinate {
+                root: alice,
+                position_x: x,
+                position_y: y,
+            };
 
-            let no_systems: usize = avail_systems.clone().count();
-            if no_systems > 0 {
-                let random_system = seed % no_systems as u64;
-                let system = avail_systems
-                    .enumerate()
-                    .filter(|&(i, _)| i == random_system as usize)
-                    .map(|(_, e)| e)
-                    .last();
+            // Out of bounds, not adjacent, or adjacent to a system not controlled by the caller
+            assert!(!contract.can_discover_system(alice, coord(-1, 0)));
+            assert!(!contract.can_discover_system(alice, coord(2, 0)));
+            assert!(!contract.can_discover_system(bob, coord(1, 0)));
 
-                return Some(system.unwrap().position);
-            }
+            assert!(contract.can_discover_system(alice, coord(1, 0)));
+            contract.discover_system(alice, coord(1, 0));
+            assert!(!contract.can_discover_system(alice, coord(1, 0)));
+            assert!(contract.can_discover_system(alice, coord(2, 0)));
 
-            self.get_random_discoverable_system(root)
+            contract.set_map_bounds(0, 1, 0, 1);
+            assert!(!contract.can_discover_system(alice, coord(2, 0)));
+            assert!(contract.can_discover_system(alice, coord(1, 1)));
         }
 
-        /// Gets a random target for a players gateway
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - AccountId of the player in whos Universe to build a gateway target
-        ///
-        /// # Returns
-        ///
-        /// * `coord` - The coordinate of the system, or None
-        pub fn get_random_gateway_target(&self, caller: AccountId) -> Option<SystemCoordinate> {
-            let players = self.systems.keys();
-            let no_players = players.len() - 1; // Without caller
-            let seed: u64 = self.generate_random_seed();
-            let random = seed % no_players as u64;
-            let root_vec: Vec<&AccountId> = players
-                .filter(|&account| *account != caller)
-                .enumerate()
-                .filter(|&(i, _)| i == random as usize)
-                .map(|(_, e)| e)
-                .collect();
-            let root: AccountId = *root_vec[0];
+        #[ink::test]
+        #[should_panic]
+        fn test_discovery_not_adjacent() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let player: AccountId = accounts.alice;
 
-            self.get_player_gateway_target(root, seed)
+            contract.register_player(player, String::from("Test"));
+
+            contract.discover_system(player, SystemCoordinate {
+                root: player,
+                position_x: 5,
+                position_y: 5,
+            });
         }
 
-        /// Worker function for gateway out building
-        ///
-        /// # Arguments
-        ///
-        /// * `target` - SystemCoordinate of the System to build gateway in
-        /// * `position` - The target of the gateway
-        pub fn build_gateway_out_worker(&mut self,
-            target: SystemCoordinate, position: SystemCoordinate) {
+        #[ink::test]
+        fn test_map() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let player: AccountId = accounts.alice;
+
+            contract.register_player(player, String::from("Test"));
 
-            let mut system = self.get_system_mut(target);
-            system.gateway_out.built = true;
-            system.gateway_out.target = position;
+            let map: Vec<System> = contract.get_universe_map(player, player);
+            assert_eq!(map.len(), 1);
+            let system: &System = &map[0];
+            assert_eq!(system.position.root, player);
+            assert_eq!(system.position.position_x, 0);
+            assert_eq!(system.position.position_y, 0);
         }
 
-        /// Worker function for gateway in building
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - AccountId of the connecting player (building gateway out)
-        /// * `target` - SystemCoordinate of the System to build gateway in
-        /// * `position` - The target of the gateway
-        pub fn build_gateway_in_worker(&mut self, caller: AccountId,
-            target: SystemCoordinate, position: SystemCoordinate) {
+        #[ink::test]
+        fn test_universe_queries() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let player: AccountId = accounts.alice;
 
-            if self.get_system(target).is_none() {
-                let new_planets = self.generate_planets(target, 0, None);
-                let systems = self.systems.get_mut(&target.root).unwrap();
-                systems.push(System {
-                    position: target,
-                    discoverer: caller,
-                    planets: new_planets,
-                    gateway_in: Gateway {
-                        built: true,
-                        target: position,
-                    },
-                    gateway_out: Gateway::default(),
-                });
-            } else {
-                let mut system = self.get_system_mut(target);
-                system.gateway_in.built = true;
-                system.gateway_in.target = position;
-            }
+            contract.register_player(player, String::from("Test"));
+
+            let coord = |x: i32, y: i32| SystemCoordinate {
+                root: player,
+                position_x: x,
+                position_y: y,
+            };
+            contract.discover_system(player, coord(1, 0));
+            contract.discover_system(player, coord(2, 0));
+            contract.discover_system(player, coord(1, 1));
+
+            assert_eq!(contract.get_system_count(player), 4);
+
+            let first_page: Vec<System> = contract.get_universe_page(player, player, 0, 2);
+            assert_eq!(first_page.len(), 2);
+            assert_eq!(first_page[1].position, coord(1, 0));
+            let second_page: Vec<System> = contract.get_universe_page(player, player, 2, 10);
+            assert_eq!(second_page.len(), 2);
+            assert_eq!(second_page[1].position, coord(1, 1));
+            assert_eq!(contract.get_universe_page(player, player, 4, 10).len(), 0);
+
+            let region: Vec<System> = contract.get_universe_region(player, player, 1, 2, 0, 0);
+            assert_eq!(region.len(), 2);
+            assert_eq!(contract.get_universe_region(player, player, 0, 9, 0, 9).len(), 4);
+
+            let summary: Vec<SystemSummary> = contract.get_universe_summary(player, 0, 10);
+            assert_eq!(summary.len(), 4);
+            assert_eq!(summary[0].position, coord(0, 0));
+            assert_eq!(summary[0].planets.len(), MAX_PLANETS);
+            assert_eq!(summary[0].planets[0].owner, player);
+            assert_eq!(summary[0].planets[0].level, 1);
+            assert_eq!(summary[1].planets[0].owner, AccountId::default());
         }
 
-        /// Builds a gateway (out) in a System in players universe
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - AccountId of the connecting player (building gateway out)
-        /// * `source` - SystemCoordinate of the System to build gateway in
-        #[ink(message)]
-        pub fn build_gateway(&mut self, caller: AccountId, source: SystemCoordinate) {
-            if self.owner.is_some() {
-                assert_eq!(self.env().caller(), self.owner.unwrap());
-            }
-            assert_eq!(caller, source.root);
-            assert!(self.systems.get(&source.root).is_some());
-            assert!(self.get_system(source).is_some());
+        #[ink::test]
+        fn test_import_systems() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let bob: AccountId = accounts.bob;
 
-            let gateway_target_coord = self.get_random_gateway_target(source.root);
-            if gateway_target_coord.is_some() {
-                self.build_gateway_out_worker(source, gateway_target_coord.unwrap());
-                self.build_gateway_in_worker(caller, gateway_target_coord.unwrap(), source);
-            } else {
-                // Should not happen unless the user is the lone player
-            }
-        }
+            contract.register_player(alice, String::from("Alice"));
+            contract.discover_system(alice, SystemCoordinate {
+                root: alice,
+                position_x: 1,
+                position_y: 0,
+            });
 
-        /// Gets general game statistics
-        ///
-        /// # Returns
-        ///
-        /// * `stats` - The game statistics
-        #[ink(message)]
-        pub fn get_game_stats(&self) -> GameStats {
-            GameStats {
-                no_players: self.systems.keys().len() as u64,
-                no_systems: self.systems.iter().len() as u64, // TODO this doesnt work
+            let exported: Vec<System> = contract.get_system_coords(alice)
+                .iter()
+                .map(|coord| contract.get_system(*coord).unwrap())
+                .collect();
+            let mut imported_systems: Vec<LegacySystem> = Vec::new();
+            for system in exported.iter() {
+                imported_systems.push(LegacySystem {
+                    position: SystemCoordinate {
+                        root: bob,
+                        position_x: system.position.position_x,
+                        position_y: system.position.position_y,
+                    },
+                    gateway_in: LegacyGateway::default(),
+                    gateway_out: LegacyGateway::default(),
+                    planets: system.planets.clone(),
+                    discoverer: system.discoverer,
+                });
             }
-        }
+            imported_systems[0].gateway_out = LegacyGateway {
+                built: true,
+                target: SystemCoordinate {
+                    root: alice,
+                    position_x: 0,
+                    position_y: 0,
+                },
+            };
 
-        /// Checks whether a player is owner of a system
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - AccountId of the player in whos Universe to build a gateway target
-        /// * `system` - The System to check players ownership of
-        ///
-        /// # Returns
-        ///
-        /// * `is_owner` - Whether the player is owner of that system
-        pub fn is_owner_of_system(&self, caller: AccountId, system: &System) -> bool {
-            let mut caller_planet_count: u8 = 0;
-            for planet in system.planets.iter() {
-                if planet.owner == caller {
-                    caller_planet_count = caller_planet_count + 1;
-                }
-            }
+            let mut migrated: NewOmegaUniverse = prepare_contract();
+            migrated.import_systems(bob, String::from("Bob"), imported_systems);
 
-            caller_planet_count >= (PLANETS_FOR_CONTROL as u8)
-        }
+            let coords: Vec<SystemCoordinate> = migrated.get_system_coords(bob);
+            assert_eq!(coords.len(), 2);
+            assert_eq!(coords[1].position_x, 1);
+            assert_eq!(migrated.get_system(coords[1]).unwrap().planets, exported[1].planets);
+            assert_eq!(migrated.get_system(coords[0]).unwrap().gateways_out.len(), 1);
+            assert_eq!(migrated.get_system(coords[1]).unwrap().gateways_out.len(), 0);
+            assert_eq!(migrated.get_player_names(vec![bob]), vec![String::from("Bob")]);
 
-        /// Worker function to generate adjacent system coordinates for a given system
-        ///
-        /// # Arguments
-        ///
-        /// * `system` - The System to generate adjacent coordinates for 
-        ///
-        /// # Returns
-        ///
-        /// * `coords` - The list of adjacent coordinates
-        pub fn get_adjacent_system_coords(&self, system: &SystemCoordinate) -> [SystemCoordinate; 4] {
-            [
-                SystemCoordinate {
-                    root: system.root,
-                    position_x: system.position_x - 1,
-                    position_y: system.position_y,
-                },
-                SystemCoordinate {
-                    root: system.root,
-                    position_x: system.position_x + 1,
-                    position_y: system.position_y,
-                },
-                SystemCoordinate {
-                    root: system.root,
-                    position_x: system.position_x,
-                    position_y: system.position_y - 1,
-                },
-                SystemCoordinate {
-                    root: system.root,
-                    position_x: system.position_x,
-                    position_y: system.position_y + 1,
+            let stats: GameStats = migrated.get_game_stats();
+            assert_eq!(stats.no_players, 1);
+            assert_eq!(stats.no_systems, 2);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_import_systems_level_zero() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let planet: Planet = Planet {
+                level: 0,
+                ..Planet::default()
+            };
+
+            contract.import_systems(accounts.bob, String::from("Bob"), vec![LegacySystem {
+                position: SystemCoordinate {
+                    root: accounts.bob,
+                    position_x: 0,
+                    position_y: 0,
                 },
-            ]
+                gateway_in: LegacyGateway::default(),
+                gateway_out: LegacyGateway::default(),
+                planets: vec![planet],
+                discoverer: accounts.bob,
+            }]);
         }
 
-        /// Checks whether a player can attack a planet in a given system
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - The player to check attackability for
-        /// * `target` - The System to check attackability of
-        ///
-        /// # Returns
-        ///
-        /// * `can_attack` - Whether a player can attack a system
-        pub fn can_attack_planet(&self, caller: AccountId, target: SystemCoordinate) -> bool {
-            if target.root == caller {
-                return true;
-            }
+        #[ink::test]
+        fn test_gateways() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let bob: AccountId = accounts.bob;
+            let charlie: AccountId = accounts.charlie;
 
-            let system: System = self.get_system(target).unwrap();
-            if self.is_owner_of_system(caller, &system) {
-                return true;
-            }
+            contract.register_player(alice, String::from("Alice"));
+            contract.register_player(bob, String::from("Bob"));
+            contract.register_player(charlie, String::from("Charlie"));
 
-            let mut owner_found: bool = false;
-            let adjacent_systems_coords: [SystemCoordinate; 4] = self.get_adjacent_system_coords(&target);
-            for adjacent_system_coords in adjacent_systems_coords.iter() {
-                match self.get_system(*adjacent_system_coords) {
-                    Some(ref adjacent_system) =>
-                        owner_found |= self.is_owner_of_system(caller, &adjacent_system),
-                    _ => ()
-                }
+            let alice_root_coord = SystemCoordinate {
+                root: alice,
+                position_x: 0,
+                position_y: 0,
+            };
 
-                if !owner_found {
-                    // check if the system has a gateway, and does it point to a system owned by caller
-                    if system.gateway_in.built {
-                        let gateway_in_system: System = self.get_system(system.gateway_in.target).unwrap();
-                        owner_found |= self.is_owner_of_system(caller, &gateway_in_system);
-                    }
+            let bob_root_coord = SystemCoordinate {
+                root: bob,
+                position_x: 0,
+                position_y: 0,
+            };
 
-                    if !owner_found && system.gateway_out.built {
-                        let gateway_out_system: System = self.get_system(system.gateway_out.target).unwrap();
-                        owner_found |= self.is_owner_of_system(caller, &gateway_out_system);
-                    }
-                }
-            }
+            // Rejected requests are dropped
+            contract.build_gateway(alice, alice_root_coord, charlie);
+            assert_eq!(contract.get_gateway_requests(charlie).len(), 1);
+            contract.reject_gateway(charlie, alice_root_coord);
+            assert_eq!(contract.get_gateway_requests(charlie).len(), 0);
 
-            owner_found
-        }
+            // Accepted requests get connected to the chosen System
+            contract.build_gateway(alice, alice_root_coord, bob);
+            assert_eq!(contract.get_gateway_requests(bob)[0].source, alice_root_coord);
+            assert_eq!(contract.get_system(alice_root_coord).unwrap().gateways_out.len(), 0);
 
-        pub fn min(lhs: u32, rhs: u32) -> u32 {
-            if lhs < rhs {
-                lhs
-            } else {
-                rhs
-            }
-        }
+            contract.accept_gateway(bob, alice_root_coord, bob_root_coord);
+            assert_eq!(contract.get_gateway_requests(bob).len(), 0);
 
-        /// Gets the Assets for a given player
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - The player to get Assets for
-        ///
-        /// # Returns
-        ///
-        /// * `assets` - The Assets
-        #[ink(message)]
-        pub fn get_player_assets(&self, caller: AccountId) -> PlayerAssets {
-            assert!(self.assets.get(&caller).is_some());
-            let asset: &PlayerAssets = self.assets.get(&caller).unwrap();
+            let alice_root = contract.get_system(alice_root_coord).unwrap();
+            let bob_root = contract.get_system(bob_root_coord).unwrap();
 
-            PlayerAssets {
-                name: asset.name.clone(),
-            }
-        }
+            assert_eq!(alice_root.gateways_out[0].target, bob_root_coord);
+            assert_eq!(bob_root.gateways_in[0].target, alice_root_coord);
+            assert!(contract.can_attack_planet(alice, bob_root_coord));
 
-        /// Gets the Universe Map for a given player
-        ///
-        /// # Arguments
-        ///
-        /// * `root` - The player to get the Map for
-        ///
-        /// # Returns
-        ///
-        /// * `map` - Vec of Systems comprising the Universe Map of a player
-        #[ink(message)]
-        pub fn get_universe_map(&self, root: AccountId) -> Vec<System> {
-            self.systems
-                .get(&root)
-                .unwrap()
-                .to_vec()
-        }
+            // Open Universes get connected right away, to a System with a free slot
+            let alice_second_coord = SystemCoordinate {
+                root: alice,
+                position_x: 1,
+                position_y: 0,
+            };
 
-        /// Harvest all planets in a players Universe, which the player owns
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - The player to harvest planets for
-        #[ink(message)]
-        pub fn harvest(&mut self, caller: AccountId) {
-            assert_eq!(self.env().caller(), self.owner.unwrap());
-            assert!(self.systems.get(&caller).is_some());
+            contract.discover_system(alice, alice_second_coord);
+            contract.set_open_gateways(bob, true);
+            contract.build_gateway(alice, alice_second_coord, bob);
 
-            let block_number = self.env().block_number();
-            let mut harvested: [u32; MAX_MINERALS] = [0; MAX_MINERALS];
-            let systems = self.systems.get_mut(&caller).unwrap();
-            for system in systems.iter_mut() {
-                for mut planet in system.planets.iter_mut() {
-                    if planet.owner == caller {
-                        let block_diff: BlockNumber = NewOmegaUniverse::min(block_number - planet.last_harvested, MAX_HARVESTABLE_BLOCKS);
-                        let amount: u32 = (block_diff / MINERAL_GENERATION_BLOCKS) * planet.level as u32 * planet.mineral_proof as u32;
-                        harvested[planet.mineral_type as usize] += amount;
-                        planet.last_harvested = block_number;
-                    }
-                }
-            }
+            let alice_second = contract.get_system(alice_second_coord).unwrap();
+            let bob_second_coord = alice_second.gateways_out[0].target;
+            let bob_second = contract.get_system(bob_second_coord).unwrap();
 
-            self.new_omega_storage
-                .as_mut()
-                .unwrap()
-                .add_minerals(caller, harvested);
+            assert!(bob_second_coord != bob_root_coord);
+            assert_eq!(bob_second.gateways_in[0].target, alice_second_coord);
+
+            // Disabled gateways cant be used
+            contract.disable_gateway(alice_root_coord, bob_root_coord, 1);
+            assert!(!contract.can_attack_planet(alice, bob_root_coord));
+            test::advance_block::<Environment>().expect("Cannot advance block");
+            assert!(contract.can_attack_planet(alice, bob_root_coord));
+
+            // Either end can destroy a gateway
+            contract.destroy_gateway(bob, alice_root_coord, bob_root_coord);
+            assert_eq!(contract.get_system(alice_root_coord).unwrap().gateways_out.len(), 0);
+            assert_eq!(contract.get_system(bob_root_coord).unwrap().gateways_in.len(), 0);
+            assert!(!contract.can_attack_planet(alice, bob_root_coord));
         }
 
-        /// Harvest a specific planet that the player owns
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - The player to harvest planet for
-        /// * `target`- SystemCoordinate of the system the planet to harvest is in
-        /// * `planet_id` - Which planet to harvest
-        #[ink(message)]
-        pub fn harvest_planet(&mut self, caller: AccountId, target: SystemCoordinate, planet_id: u8) {
-            assert_eq!(self.env().caller(), self.owner.unwrap());
-            assert!(self.systems.get(&caller).is_some());
+        #[ink::test]
+        fn test_gateway_slots() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let bob: AccountId = accounts.bob;
 
-            let block_number = self.env().block_number();
-            let mut harvested: [u32; MAX_MINERALS] = [0; MAX_MINERALS];
+            contract.register_player(alice, String::from("Alice"));
+            contract.register_player(bob, String::from("Bob"));
+            contract.set_open_gateways(bob, true);
 
-            let system: &mut System = self.get_system_mut(target);
-            let planet: &mut Planet = &mut system.planets[planet_id as usize];
+            let alice_root_coord = SystemCoordinate {
+                root: alice,
+                position_x: 0,
+                position_y: 0,
+            };
 
-            assert_eq!(planet.owner, caller);
+            assert_eq!(contract.get_gateway_slots(&contract.get_system(alice_root_coord).unwrap()), 1);
+            contract.build_gateway(alice, alice_root_coord, bob);
 
-            let block_diff: BlockNumber = NewOmegaUniverse::min(block_number - planet.last_harvested, MAX_HARVESTABLE_BLOCKS);
-            let amount: u32 = (block_diff / MINERAL_GENERATION_BLOCKS) * planet.level as u32 * planet.mineral_proof as u32;
-            harvested[planet.mineral_type as usize] += amount;
-            planet.last_harvested = block_number;
+            contract.get_system_mut(alice_root_coord).planets[2].level = LEVELS_PER_GATEWAY_SLOT;
+            let alice_root = contract.get_system(alice_root_coord).unwrap();
+            assert_eq!(contract.get_gateway_slots(&alice_root), 2);
+            assert_eq!(contract.get_gateway_defender(&alice_root), 2);
 
-            self.new_omega_storage
-                .as_mut()
-                .unwrap()
-                .add_minerals(caller, harvested);
+            contract.build_gateway(alice, alice_root_coord, bob);
+            assert_eq!(contract.get_system(alice_root_coord).unwrap().gateways_out.len(), 2);
         }
 
-        /// Upgrades a specific planet that the player owns
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - The player to upgrade planet for
-        /// * `target`- SystemCoordinate of the system the planet to upgrade is in
-        /// * `planet_id` - Which planet to upgrade
-        #[ink(message)]
-        pub fn upgrade_planet(&mut self, caller: AccountId, target: SystemCoordinate, planet_id: u8) {
-            assert_eq!(self.env().caller(), self.owner.unwrap());
-            assert!(self.systems.get(&caller).is_some());
+        #[ink::test]
+        #[should_panic(expected = "Gateway already requested")]
+        fn test_gateway_request_per_player() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let bob: AccountId = accounts.bob;
 
-            let system: &mut System = self.get_system_mut(target);
-            let planet: &mut Planet = &mut system.planets[planet_id as usize];
+            contract.register_player(alice, String::from("Alice"));
+            contract.register_player(bob, String::from("Bob"));
 
-            assert_eq!(planet.owner, caller);
-            assert!(planet.level < MAX_PLANET_LEVEL);
+            let alice_root_coord = SystemCoordinate {
+                root: alice,
+                position_x: 0,
+                position_y: 0,
+            };
+            let alice_discovered_coord = SystemCoordinate {
+                root: alice,
+                position_x: 1,
+                position_y: 0,
+            };
+            contract.discover_system(alice, alice_discovered_coord);
 
-            planet.level = planet.level + 1;
+            // A second request from another System of the same player is refused
+            contract.build_gateway(alice, alice_root_coord, bob);
+            contract.build_gateway(alice, alice_discovered_coord, bob);
         }
 
-        /// Attacks a planet
-        ///
-        /// # Arguments
-        ///
-        /// * `caller` - The player initating the attack
-        /// * `target`- SystemCoordinate of the system the planet to attack is in
-        /// * `planet_id` - Which planet to attack
-        /// * `selection` - Fleet selection to use in the attack
-        /// * `modules` - Fleet modules to use in the attack
-        /// * `targeting` - Fleet targeting to use in the attack
-        ///
-        /// # Returns
-        ///
-        /// * `result` - The fight result
-        #[ink(message)]
-        pub fn attack_planet(
-            &mut self,
-            caller: AccountId,
-            target: SystemCoordinate,
-            planet_id: u8,
-            selection: [u8; MAX_SHIPS],
-            modules: [ShipModule; MAX_SHIPS],
-            targeting: TargetingType) -> FightResult {
+        #[ink::test]
+        #[should_panic]
+        fn test_gateway_no_free_slot() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let bob: AccountId = accounts.bob;
 
-            assert_eq!(self.env().caller(), self.owner.unwrap(), "Wrong delegator");
-            assert!(self.can_attack_planet(caller, target), "Cant attack planet");
-            assert!(self
-                .new_omega_storage
-                .as_ref()
-                .unwrap()
-                .has_enough_ships(caller, selection), "Not enough ships");
+            contract.register_player(alice, String::from("Alice"));
+            contract.register_player(bob, String::from("Bob"));
+            contract.set_open_gateways(bob, true);
 
-            // Determine the seed, in a naive way -> IMPROVEME: MOVE TO VRF
-            let seed: u64 = self.generate_random_seed();
-            let target_system_wrapped: Option<System> = self.get_system(target);
+            let alice_root_coord = SystemCoordinate {
+                root: alice,
+                position_x: 0,
+                position_y: 0,
+            };
 
-            assert!(target_system_wrapped.is_some(), "Target doesnt exist");
+            contract.build_gateway(alice, alice_root_coord, bob);
+            contract.build_gateway(alice, alice_root_coord, bob);
+        }
 
-            let target_system: System = target_system_wrapped.unwrap();
-            let target_planet = &target_system.planets[planet_id as usize];
+        #[ink::test]
+        fn test_fog_of_war() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let bob: AccountId = accounts.bob;
 
-            // Calculate the fight result
-            let (result, _lhs_moves, _rhs_moves) =
-                self.new_omega_game.as_ref().unwrap().fight(
-                    seed,
-                    false,
-                    selection,
-                    target_planet.selection,
-                    modules,
-                    target_planet.modules,
-                    targeting,
-                    target_planet.targeting);
+            contract.register_player(alice, String::from("Alice"));
+            contract.register_player(bob, String::from("Bob"));
+
+            let alice_root_coord = SystemCoordinate {
+                root: alice,
+                position_x: 0,
+                position_y: 0,
+            };
+            let bob_root_coord = SystemCoordinate {
+                root: bob,
+                position_x: 0,
+                position_y: 0,
+            };
+
+            // A stealth planet would hide its CP from coarse intel
+            contract.get_system_mut(bob_root_coord).planets[0].planet_type = 0;
+
+            // Own planets are fully visible, the rest of the own system only coarsely
+            contract.get_system_mut(alice_root_coord).planets[3].owner = bob;
+            let (own_system, _) = contract.get_system_check(alice, alice_root_coord);
+            let own_system: System = own_system.unwrap();
+            assert_eq!(own_system.planets[0].selection, [10; MAX_SHIPS]);
+            assert_eq!(own_system.planets[3].selection, [0; MAX_SHIPS]);
+            assert_eq!(contract.get_system_intel(alice, alice_root_coord).visibility, Visibility::Coarse);
+
+            // Unreachable system is hidden
+            let (hidden_system, _) = contract.get_system_check(alice, bob_root_coord);
+            assert_eq!(hidden_system.unwrap().planets[0].selection, [0; MAX_SHIPS]);
+            let hidden_intel: SystemIntel = contract.get_system_intel(alice, bob_root_coord);
+            assert_eq!(hidden_intel.visibility, Visibility::Hidden);
+            assert_eq!(hidden_intel.cp_buckets[0], None);
+
+            // Reachable system is visible coarsely
+            contract.set_open_gateways(bob, true);
+            contract.build_gateway(alice, alice_root_coord, bob);
+            let (coarse_system, can_attack) = contract.get_system_check(alice, bob_root_coord);
+            assert!(can_attack);
+            assert_eq!(coarse_system.unwrap().planets[0].selection, [0; MAX_SHIPS]);
+            let coarse_intel: SystemIntel = contract.get_system_intel(alice, bob_root_coord);
+            assert_eq!(coarse_intel.visibility, Visibility::Coarse);
+            let garrison_cp: u32 = newomega::prepare_ships()
+                .iter()
+                .map(|ship| 10 * ship.cp as u32)
+                .sum();
+            assert_eq!(coarse_intel.cp_buckets[0], Some(garrison_cp / CP_BUCKET_SIZE));
+
+            // Scouted system is fully visible, until the scout expires
+            contract.scout_system(alice, bob_root_coord);
+            let (scouted_system, _) = contract.get_system_check(alice, bob_root_coord);
+            assert_eq!(scouted_system.unwrap().planets[0].selection, [10; MAX_SHIPS]);
+            assert_eq!(contract.get_universe_map(alice, bob)[0].planets[0].selection, [10; MAX_SHIPS]);
+
+            for _ in 0..(GameParameters::default().scout_duration_blocks + 1) {
+                test::advance_block::<Environment>().expect("Cannot advance block");
+            }
+            assert_eq!(contract.get_system_intel(alice, bob_root_coord).visibility, Visibility::Coarse);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_universe_map_not_delegator() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
 
-            if result.rhs_dead && !result.lhs_dead {
-                let target_system_mut: &mut System = self.get_system_mut(target);
-                let target_planet_mut: &mut Planet = &mut target_system_mut.planets[planet_id as usize];
+            contract.register_player(accounts.alice, String::from("Alice"));
 
-                target_planet_mut.owner = caller;
-                target_planet_mut.selection = [0; MAX_SHIPS];
-                target_planet_mut.modules = [ShipModule::default(); MAX_SHIPS];
-                target_planet_mut.targeting = TargetingType::default();
-            }
+            // Only the Delegator can query on behalf of a player
+            set_sender(accounts.bob);
+            contract.get_universe_map(accounts.alice, accounts.alice);
+        }
 
-            let mut ships_lost_u32: [u32; MAX_SHIPS] = [0; MAX_SHIPS];
-            for i in 0..MAX_SHIPS {
-                ships_lost_u32[i] = result.ships_lost_lhs[i] as u32;
-            }
+        #[ink::test]
+        #[should_panic]
+        fn test_system_intel_not_delegator() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
 
-            self
-                .new_omega_storage
-                .as_mut()
-                .unwrap()
-                .remove_ships(caller, ships_lost_u32);
+            contract.register_player(accounts.alice, String::from("Alice"));
 
-            result
+            set_sender(accounts.bob);
+            contract.get_system_intel(accounts.alice, SystemCoordinate {
+                root: accounts.alice,
+                position_x: 0,
+                position_y: 0,
+            });
         }
-    }
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use ink_env::{
-            test,
-        };
-        use ink_lang as ink;
-        type Accounts = test::DefaultAccounts<Environment>;
 
-        fn default_accounts() -> Accounts {
-            test::default_accounts()
-                .expect("Test environment is expected to be initialized.")
+        #[ink::test]
+        #[should_panic]
+        fn test_empire_overview_not_delegator() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+
+            contract.register_player(accounts.alice, String::from("Alice"));
+
+            // The overview holds the garrisons, so it is behind the fog of war too
+            set_sender(accounts.bob);
+            contract.get_empire_overview(accounts.alice);
         }
 
         #[ink::test]
-        fn test_register() {
+        fn test_upgrade_planet() {
             let mut contract: NewOmegaUniverse = NewOmegaUniverse::default();
             let accounts = default_accounts();
             let player: AccountId = accounts.alice;
 
+            contract.authorise_delegator(player);
             contract.register_player(player, String::from("Test"));
 
-            let system: Option<System> = contract.get_system(SystemCoordinate {
+            let coord = SystemCoordinate {
                 root: player,
                 position_x: 0,
                 position_y: 0,
-            });
-            assert!(system.is_some());
-
-            let system_unwrapped = system.unwrap();
-            assert_eq!(system_unwrapped.position.root, player);
-            assert_eq!(system_unwrapped.position.position_x, 0);
-            assert_eq!(system_unwrapped.position.position_y, 0);
-            assert_eq!(system_unwrapped.planets[0].selection, [10; 4]);
-            assert_eq!(system_unwrapped.planets[3].selection, [10; 4]);
-            assert_eq!(system_unwrapped.planets[0].owner, accounts.alice);
-            assert_eq!(system_unwrapped.planets[1].owner, accounts.alice);
-            assert_eq!(system_unwrapped.planets[2].owner, accounts.alice);
-            assert_eq!(system_unwrapped.planets[3].owner, AccountId::default());
-            assert_eq!(system_unwrapped.planets[4].owner, AccountId::default());
+            };
+            let planet: Planet = contract.get_system(coord).unwrap().planets[0].clone();
+            let modifier: u32 = contract.get_planet_type(&planet).upgrade_cost_modifier as u32;
+
+            // Level 1 -> 2: 100 + 50 + 5
+            let costs: [u32; MAX_MINERALS] = contract.get_upgrade_cost(coord, 0);
+            assert_eq!(costs[planet.mineral_type as usize], 155 * modifier / 100);
+            assert_eq!(costs.iter().sum::<u32>(), costs[planet.mineral_type as usize]);
+
+            contract.upgrade_planet(player, coord, 0);
+            let upgraded: Planet = contract.get_system(coord).unwrap().planets[0].clone();
+            assert_eq!(upgraded.level, 2);
+
+            // Level 2 -> 3: 100 + 100 + 20
+            let costs: [u32; MAX_MINERALS] = contract.get_upgrade_cost(coord, 0);
+            assert_eq!(costs[planet.mineral_type as usize], 220 * modifier / 100);
+
+            let mut table: UpgradeCostTable = contract.get_upgrade_cost_table();
+            table.base_cost = 0;
+            table.cost_per_level = 0;
+            table.cost_per_level_squared = 1;
+            contract.set_upgrade_cost_table(table);
+            let costs: [u32; MAX_MINERALS] = contract.get_upgrade_cost(coord, 0);
+            assert_eq!(costs[planet.mineral_type as usize], 4 * modifier / 100);
         }
 
         #[ink::test]
-        fn test_systems() {
-            let mut contract: NewOmegaUniverse = NewOmegaUniverse::default();
+        fn test_upgrade_planet_connected() {
+            let mut contract: NewOmegaUniverse = prepare_connected_contract();
             let accounts = default_accounts();
             let player: AccountId = accounts.alice;
+            let starting_minerals: u32 = GameParameters::default().starting_mineral_count;
 
             contract.register_player(player, String::from("Test"));
 
-            let system: Option<System> = contract.get_system(SystemCoordinate {
+            let coord = SystemCoordinate {
                 root: player,
-                position_x: 1,
-                position_y: 1,
-            });
-            assert!(system.is_none());
+                position_x: 0,
+                position_y: 0,
+            };
+            let planet: Planet = contract.get_system(coord).unwrap().planets[0].clone();
+            let costs: [u32; MAX_MINERALS] = contract.get_upgrade_cost(coord, 0);
+            assert!(costs[planet.mineral_type as usize] > 0);
 
-            let map: Vec<System> = contract.get_universe_map(player);
-            assert_eq!(map.len(), 1);
-            let system_first: &System = &map[0];
-            assert_eq!(system_first.position.root, player);
-            assert_eq!(system_first.position.position_x, 0);
-            assert_eq!(system_first.position.position_y, 0);
+            contract.upgrade_planet(player, coord, 0);
+
+            assert_eq!(contract.get_system(coord).unwrap().planets[0].level, 2);
+            let minerals: [u32; MAX_MINERALS] = contract
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .get_player_minerals(player);
+            for i in 0..MAX_MINERALS {
+                assert_eq!(minerals[i], starting_minerals - costs[i]);
+            }
         }
 
         #[ink::test]
-        fn test_discovery() {
-            let mut contract: NewOmegaUniverse = NewOmegaUniverse::default();
+        #[should_panic]
+        fn test_upgrade_planet_not_enough_minerals_connected() {
+            let mut contract: NewOmegaUniverse = prepare_connected_contract();
             let accounts = default_accounts();
             let player: AccountId = accounts.alice;
+            let starting_minerals: u32 = GameParameters::default().starting_mineral_count;
 
             contract.register_player(player, String::from("Test"));
 
-            contract.discover_system(player, SystemCoordinate {
+            let mut table: UpgradeCostTable = contract.get_upgrade_cost_table();
+            table.base_cost = starting_minerals + 1;
+            contract.set_upgrade_cost_table(table);
+
+            contract.upgrade_planet(player, SystemCoordinate {
                 root: player,
-                position_x: 1,
+                position_x: 0,
                 position_y: 0,
-            });
+            }, 0);
         }
 
         #[ink::test]
-        fn test_map() {
-            let mut contract: NewOmegaUniverse = NewOmegaUniverse::default();
+        fn test_planet_level_effects() {
+            let contract: NewOmegaUniverse = NewOmegaUniverse::default();
+            let mut planet: Planet = Planet::default();
+
+            assert_eq!(contract.get_defence_bonus(&planet), 0);
+            assert_eq!(contract.get_garrison_capacity(&planet), 120);
+
+            planet.level = 41;
+            assert_eq!(contract.get_defence_bonus(&planet), 80);
+            assert_eq!(contract.get_defence_settings(&planet).hp_bonus_rhs, 80);
+            assert_eq!(contract.get_garrison_capacity(&planet), 920);
+
+            // The capacity stops at what a garrison selection can hold
+            planet.level = MAX_PLANET_LEVEL;
+            assert_eq!(contract.get_defence_bonus(&planet), 198);
+            assert_eq!(contract.get_garrison_capacity(&planet), MAX_GARRISON);
+
+            // An imported planet of level 0 gets no bonus, rather than underflowing
+            planet.level = 0;
+            assert_eq!(contract.get_defence_bonus(&planet), 0);
+        }
+
+        #[ink::test]
+        fn test_planet_types() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let planet_types: Vec<PlanetTypeDefinition> = contract.get_planet_types();
+            assert_eq!(planet_types.len(), MAX_PLANET_TYPES);
+            assert!(planet_types[9].has_ability(PLANET_ABILITY_STEALTH));
+            assert!(planet_types[14].has_ability(PLANET_ABILITY_DEEP_CORE));
+
+            let planet: Planet = Planet {
+                planet_type: 2,
+                mineral_type: 1,
+                mineral_proof: 10,
+                ..Planet::default()
+            };
+
+            // Type 2: +10% defence, +40 capacity
+            assert_eq!(contract.get_defence_bonus(&planet), 10);
+            assert_eq!(contract.get_garrison_capacity(&planet), 160);
+
+            let mut definition: PlanetTypeDefinition = planet_types[2];
+            definition.harvest_multipliers[1] = 200;
+            definition.defence_bonus = 100;
+            definition.garrison_capacity = 0;
+            definition.special_ability = PLANET_ABILITY_DEEP_CORE;
+            contract.set_planet_type(2, definition);
+
+            assert_eq!(contract.get_defence_bonus(&planet), 100);
+            assert_eq!(contract.get_garrison_capacity(&planet), GARRISON_CAPACITY_PER_LEVEL);
+
+            // Deep core doubles the harvestable window, the multiplier doubles the yield
+            let parameters: GameParameters = GameParameters::default();
+            let block_number: BlockNumber = 3 * parameters.max_harvestable_blocks;
+            let harvest: u32 = NewOmegaUniverse::calculate_harvest(&planet,
+                contract.get_planet_type(&planet), block_number, &parameters);
+            assert_eq!(harvest,
+                2 * (2 * parameters.max_harvestable_blocks / parameters.mineral_generation_blocks) * 10);
+        }
+
+        #[ink::test]
+        fn test_harvest_max_level() {
+            let contract: NewOmegaUniverse = NewOmegaUniverse::default();
+            let planet: Planet = Planet {
+                level: MAX_PLANET_LEVEL,
+                mineral_proof: u8::MAX,
+                ..Planet::default()
+            };
+
+            let mut definition: PlanetTypeDefinition = contract.get_planet_types()[0];
+            definition.harvest_multipliers = [MAX_HARVEST_MULTIPLIER; MAX_MINERALS];
+            definition.special_ability = PLANET_ABILITY_DEEP_CORE;
+
+            let mut parameters: GameParameters = GameParameters {
+                mineral_generation_blocks: 100,
+                max_harvestable_blocks: 100000,
+                ..GameParameters::default()
+            };
+            let harvest: u32 = NewOmegaUniverse::calculate_harvest(&planet, &definition,
+                2 * parameters.max_harvestable_blocks, &parameters);
+            assert_eq!(harvest, 2000 * MAX_PLANET_LEVEL as u32 * u8::MAX as u32 * 10);
+
+            // Past u32, the harvest saturates instead of overflowing
+            parameters.mineral_generation_blocks = 1;
+            parameters.max_harvestable_blocks = 1000000;
+            let harvest: u32 = NewOmegaUniverse::calculate_harvest(&planet, &definition,
+                2 * parameters.max_harvestable_blocks, &parameters);
+            assert_eq!(harvest, u32::MAX);
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_planet_type_invalid_multiplier() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let mut definition: PlanetTypeDefinition = contract.get_planet_types()[0];
+            definition.harvest_multipliers[0] = MAX_HARVEST_MULTIPLIER + 1;
+
+            contract.set_planet_type(0, definition);
+        }
+
+        #[ink::test]
+        fn test_garrison_survivors() {
+            let contract: NewOmegaUniverse = NewOmegaUniverse::default();
+            let mut planet: Planet = Planet::default();
+
+            assert_eq!(contract.get_garrison_survivors(&planet, [0; MAX_SHIPS]), [10; MAX_SHIPS]);
+            assert_eq!(contract.get_garrison_survivors(&planet, [0, 3, 10, 20]), [10, 7, 0, 0]);
+
+            // The level bonus is extra Health Points, the garrison itself is not scaled
+            planet.level = 51;
+            assert_eq!(contract.get_garrison_survivors(&planet, [0, 4, 9, 20]), [10, 6, 1, 0]);
+
+            // A full stack keeps its bonus, as it is not counted in ships
+            planet.selection = [u8::MAX; MAX_SHIPS];
+            assert_eq!(contract.get_defence_settings(&planet).hp_bonus_rhs, 100);
+            assert_eq!(contract.get_garrison_survivors(&planet, [5, 1, 0, 0]), [250, 254, 255, 255]);
+        }
+
+        #[ink::test]
+        fn test_procedural_generation() {
+            let contract: NewOmegaUniverse = NewOmegaUniverse::default();
+            let accounts = default_accounts();
+            let coord = SystemCoordinate {
+                root: accounts.alice,
+                position_x: 3,
+                position_y: 7,
+            };
+
+            // Contents depend on the coordinate only
+            let planets: Vec<Planet> = contract.generate_planets(coord, 0, None);
+            test::advance_block::<Environment>().expect("Cannot advance block");
+            let planets_later: Vec<Planet> = contract.generate_planets(coord, 0, None);
+            for i in 0..MAX_PLANETS {
+                assert_eq!(planets[i].planet_type, planets_later[i].planet_type);
+                assert_eq!(planets[i].mineral_type, planets_later[i].mineral_type);
+                assert_eq!(planets[i].mineral_proof, planets_later[i].mineral_proof);
+            }
+
+            // Coordinates which collided with the old sub seed (x + y) differ
+            let mirrored = SystemCoordinate {
+                root: accounts.alice,
+                position_x: 7,
+                position_y: 3,
+            };
+            assert_ne!(contract.generate_planet_seed(&coord, 0), contract.generate_planet_seed(&mirrored, 0));
+
+            let mut salted: NewOmegaUniverse = prepare_contract();
+            salted.universe_salt = 1;
+            assert_ne!(contract.generate_planet_seed(&coord, 0), salted.generate_planet_seed(&coord, 0));
+        }
+
+        #[ink::test]
+        fn test_procedural_distribution() {
+            let contract: NewOmegaUniverse = NewOmegaUniverse::default();
+            let accounts = default_accounts();
+            let mut planet_types: [u32; MAX_PLANET_TYPES] = [0; MAX_PLANET_TYPES];
+            let mut mineral_types: [u32; MAX_MINERALS] = [0; MAX_MINERALS];
+            let mut mineral_proofs: [u32; 10] = [0; 10];
+            let mut samples: u32 = 0;
+
+            for root in [accounts.alice, accounts.bob].iter() {
+                for position_x in -10..10 {
+                    for position_y in -10..10 {
+                        let coord = SystemCoordinate {
+                            root: *root,
+                            position_x,
+                            position_y,
+                        };
+                        for i in 0..MAX_PLANETS {
+                            let seed: [u8; 32] = contract.generate_planet_seed(&coord, i as u8);
+                            planet_types[contract.generate_planet_type(&seed) as usize] += 1;
+                            mineral_types[contract.generate_mineral_type(&seed) as usize] += 1;
+                            mineral_proofs[contract.generate_mineral_proof(&seed) as usize / 10] += 1;
+                            samples += 1;
+                        }
+                    }
+                }
+            }
+
+            // Every bucket within 25% of the uniform expectation
+            let expected_types: u32 = samples / MAX_PLANET_TYPES as u32;
+            for count in planet_types.iter() {
+                assert!(*count > expected_types * 3 / 4 && *count < expected_types * 5 / 4);
+            }
+            let expected_minerals: u32 = samples / MAX_MINERALS as u32;
+            for count in mineral_types.iter() {
+                assert!(*count > expected_minerals * 3 / 4 && *count < expected_minerals * 5 / 4);
+            }
+            let expected_proofs: u32 = samples / 10;
+            for count in mineral_proofs.iter() {
+                assert!(*count > expected_proofs * 3 / 4 && *count < expected_proofs * 5 / 4);
+            }
+        }
+
+        #[ink::test]
+        fn test_empire_overview() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let bob: AccountId = accounts.bob;
+
+            contract.register_player(alice, String::from("Alice"));
+            contract.register_player(bob, String::from("Bob"));
+
+            let alice_overview: EmpireOverview = contract.get_empire_overview(alice);
+            assert_eq!(alice_overview.planets.len(), START_WITH_PLANETS as usize);
+            assert_eq!(alice_overview.universes, 1);
+            assert_eq!(alice_overview.total_garrison, [10 * START_WITH_PLANETS as u32; MAX_SHIPS]);
+            assert_eq!(alice_overview.total_levels, START_WITH_PLANETS as u32);
+
+            // Alice captures a planet in Bobs Universe
+            let bob_planet = PlanetCoordinate {
+                system: SystemCoordinate {
+                    root: bob,
+                    position_x: 0,
+                    position_y: 0,
+                },
+                planet_id: 1,
+            };
+            contract.set_planet_owner(bob_planet, alice);
+
+            let alice_overview: EmpireOverview = contract.get_empire_overview(alice);
+            assert_eq!(alice_overview.planets.len(), START_WITH_PLANETS as usize + 1);
+            assert_eq!(alice_overview.universes, 2);
+            assert_eq!(alice_overview.planets[START_WITH_PLANETS as usize].0, bob_planet);
+
+            let bob_planets: Vec<PlanetCoordinate> = contract.get_owned_planets(bob);
+            assert_eq!(bob_planets.len(), START_WITH_PLANETS as usize - 1);
+            assert!(!bob_planets.contains(&bob_planet));
+
+            // Unowned planets get indexed on capture only
+            let unowned_planet = PlanetCoordinate {
+                system: bob_planet.system,
+                planet_id: MAX_PLANETS as u8 - 1,
+            };
+            contract.set_planet_owner(unowned_planet, bob);
+            assert_eq!(contract.get_owned_planets(bob).len(), START_WITH_PLANETS as usize);
+            assert_eq!(contract.get_owned_planets(AccountId::default()).len(), 0);
+        }
+
+        #[ink::test]
+        fn test_pending_harvest() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
             let accounts = default_accounts();
             let player: AccountId = accounts.alice;
 
             contract.register_player(player, String::from("Test"));
 
-            let map: Vec<System> = contract.get_universe_map(player);
-            assert_eq!(map.len(), 1);
-            let system: &System = &map[0];
-            assert_eq!(system.position.root, player);
-            assert_eq!(system.position.position_x, 0);
-            assert_eq!(system.position.position_y, 0);
+            let pending: PendingHarvest = contract.get_pending_harvest(player);
+            assert_eq!(pending.planets.len(), START_WITH_PLANETS as usize);
+            assert_eq!(pending.total, [0; MAX_MINERALS]);
+
+            let parameters: GameParameters = GameParameters::default();
+            for _ in 0..(2 * parameters.mineral_generation_blocks) {
+                test::advance_block::<Environment>().expect("Cannot advance block");
+            }
+
+            let pending: PendingHarvest = contract.get_pending_harvest(player);
+            let mut expected_total: [u32; MAX_MINERALS] = [0; MAX_MINERALS];
+            for planet_harvest in pending.planets.iter() {
+                let planet: Planet = contract
+                    .get_system(planet_harvest.location.system)
+                    .unwrap()
+                    .planets[planet_harvest.location.planet_id as usize]
+                    .clone();
+                let planet_type: &PlanetTypeDefinition = contract.get_planet_type(&planet);
+                let expected: u32 = 2 * planet.mineral_proof as u32 *
+                    planet_type.harvest_multipliers[planet.mineral_type as usize] as u32 / 100;
+
+                assert_eq!(planet_harvest.amount, expected);
+                assert_eq!(planet_harvest.blocks_until_cap,
+                    NewOmegaUniverse::get_harvestable_blocks(planet_type, &parameters) -
+                    2 * parameters.mineral_generation_blocks);
+                expected_total[planet.mineral_type as usize] += expected;
+            }
+            assert_eq!(pending.total, expected_total);
+            assert_eq!(pending.blocks_until_cap,
+                pending.planets.iter().map(|planet_harvest| planet_harvest.blocks_until_cap).min().unwrap());
+
+            assert_eq!(contract.get_pending_harvest(accounts.bob).blocks_until_cap, 0);
         }
 
         #[ink::test]
-        fn test_gateways() {
-            let mut contract: NewOmegaUniverse = NewOmegaUniverse::default();
+        fn test_attack_planet() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
             let accounts = default_accounts();
             let alice: AccountId = accounts.alice;
             let bob: AccountId = accounts.bob;
@@ -1163,50 +3916,172 @@ mod newomegauniverse {
                 position_x: 0,
                 position_y: 0,
             };
-
             let bob_root_coord = SystemCoordinate {
                 root: bob,
                 position_x: 0,
                 position_y: 0,
             };
 
-            contract.build_gateway(alice, alice_root_coord);
-            
-            let alice_root = contract.get_system(alice_root_coord).unwrap();            
-            let bob_root = contract.get_system(bob_root_coord).unwrap();
+            assert!(!contract.can_attack_planet(alice, bob_root_coord));
+            contract.set_open_gateways(bob, true);
+            contract.build_gateway(alice, alice_root_coord, bob);
+            assert!(contract.can_attack_planet(alice, bob_root_coord));
+        }
 
-            assert!(alice_root.gateway_out.built);
-            assert!(bob_root.gateway_in.built);
+        #[ink::test]
+        fn test_harvest_connected() {
+            let mut contract: NewOmegaUniverse = prepare_connected_contract();
+            let accounts = default_accounts();
+            let player: AccountId = accounts.alice;
 
-            let alice_second_coord = SystemCoordinate {
+            contract.register_player(player, String::from("Test"));
+
+            let parameters: GameParameters = GameParameters::default();
+            for _ in 0..(3 * parameters.mineral_generation_blocks) {
+                test::advance_block::<Environment>().expect("Cannot advance block");
+            }
+
+            let pending: PendingHarvest = contract.get_pending_harvest(player);
+            contract.harvest(player);
+
+            let minerals: [u32; MAX_MINERALS] = contract
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .get_player_minerals(player);
+            for (&amount, &harvested) in minerals.iter().zip(pending.total.iter()) {
+                assert_eq!(amount, parameters.starting_mineral_count + harvested);
+            }
+            assert_eq!(contract.get_pending_harvest(player).total, [0; MAX_MINERALS]);
+        }
+
+        #[ink::test]
+        fn test_capture_planet_connected() {
+            let mut contract: NewOmegaUniverse = prepare_connected_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let starting_ships: u32 = GameParameters::default().starting_ship_count;
+
+            contract.register_player(alice, String::from("Alice"));
+
+            let alice_root_coord = SystemCoordinate {
                 root: alice,
-                position_x: 1,
+                position_x: 0,
                 position_y: 0,
             };
+            let garrison: [u8; MAX_SHIPS] = [5; MAX_SHIPS];
 
-            contract.discover_system(alice, alice_second_coord);
-            contract.build_gateway(alice, alice_second_coord);
+            let result: FightResult = contract.attack_planet(alice, alice_root_coord, START_WITH_PLANETS,
+                [40; MAX_SHIPS], [ShipModule::default(); MAX_SHIPS], TargetingType::default(), garrison);
 
-            let alice_second = contract.get_system(alice_second_coord).unwrap();
+            assert!(result.rhs_dead && !result.lhs_dead);
+            assert_eq!(contract.get_planet_owner(alice_root_coord, START_WITH_PLANETS), alice);
+            assert_eq!(contract.get_owned_planets(alice).len(), START_WITH_PLANETS as usize + 1);
+            assert_eq!(contract.get_system(alice_root_coord).unwrap()
+                .planets[START_WITH_PLANETS as usize].selection, garrison);
+
+            let ships: [u32; MAX_SHIPS] = contract
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .get_player_ships(alice);
+            for i in 0..MAX_SHIPS {
+                assert_eq!(ships[i], starting_ships - result.ships_lost_lhs[i] as u32 - garrison[i] as u32);
+            }
+        }
 
-            assert!(alice_second.gateway_out.built);
+        #[ink::test]
+        fn test_capture_planet_defeat_connected() {
+            let mut contract: NewOmegaUniverse = prepare_connected_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let starting_ships: u32 = GameParameters::default().starting_ship_count;
 
-            let bob_second_coord = alice_second.gateway_out.target;
-            let bob_second = contract.get_system(bob_second_coord).unwrap();
+            contract.register_player(alice, String::from("Alice"));
+
+            let alice_root_coord = SystemCoordinate {
+                root: alice,
+                position_x: 0,
+                position_y: 0,
+            };
+
+            let result: FightResult = contract.attack_planet(alice, alice_root_coord, START_WITH_PLANETS,
+                [1; MAX_SHIPS], [ShipModule::default(); MAX_SHIPS], TargetingType::default(), [0; MAX_SHIPS]);
 
-            assert!(alice_second.gateway_out.built);
-            assert!(bob_second.gateway_in.built);
+            assert!(result.lhs_dead && !result.rhs_dead);
+            assert_eq!(contract.get_planet_owner(alice_root_coord, START_WITH_PLANETS), AccountId::default());
+            assert_eq!(contract.get_owned_planets(alice).len(), START_WITH_PLANETS as usize);
+            assert_eq!(contract
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .get_player_ships(alice), [starting_ships - 1; MAX_SHIPS]);
         }
 
         #[ink::test]
-        fn test_attack_planet() {
-            let mut contract: NewOmegaUniverse = NewOmegaUniverse::default();
+        fn test_capture_planet_draw_connected() {
+            let mut contract: NewOmegaUniverse = prepare_connected_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+            let starting_ships: u32 = GameParameters::default().starting_ship_count;
+            // A single round is too short to finish the fight
+            let settings: FightSettings = FightSettings {
+                max_rounds: 1,
+                tie_break: TieBreak::None,
+                hp_bonus_rhs: 0,
+            };
+
+            contract.register_player(alice, String::from("Alice"));
+            contract.set_fight_settings(settings);
+
+            let alice_root_coord = SystemCoordinate {
+                root: alice,
+                position_x: 0,
+                position_y: 0,
+            };
+            let planet: Planet = contract.get_system(alice_root_coord).unwrap()
+                .planets[START_WITH_PLANETS as usize].clone();
+
+            let result: FightResult = contract.attack_planet(alice, alice_root_coord, START_WITH_PLANETS,
+                [40; MAX_SHIPS], [ShipModule::default(); MAX_SHIPS], TargetingType::default(), [5; MAX_SHIPS]);
+
+            // Nobody won, so the planet is not captured, and both sides only lose the destroyed ships
+            assert!(!result.lhs_dead && !result.rhs_dead);
+            assert_eq!(result.outcome, Outcome::Draw);
+            assert_eq!(result.settings, contract.get_defence_settings(&planet));
+            assert_eq!(result.settings.max_rounds, settings.max_rounds);
+            assert_eq!(contract.get_fight_settings(), settings);
+            assert_eq!(contract.get_planet_owner(alice_root_coord, START_WITH_PLANETS), AccountId::default());
+            assert_eq!(contract.get_system(alice_root_coord).unwrap()
+                .planets[START_WITH_PLANETS as usize].selection,
+                contract.get_garrison_survivors(&planet, result.ships_lost_rhs));
+
+            let ships: [u32; MAX_SHIPS] = contract
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .get_player_ships(alice);
+            for (&count, &lost) in ships.iter().zip(result.ships_lost_lhs.iter()) {
+                assert_eq!(count, starting_ships - lost as u32);
+            }
+        }
+
+        #[ink::test]
+        fn test_capture_planet_tie_break_connected() {
+            let mut contract: NewOmegaUniverse = prepare_connected_contract();
             let accounts = default_accounts();
             let alice: AccountId = accounts.alice;
             let bob: AccountId = accounts.bob;
+            let starting_ships: u32 = GameParameters::default().starting_ship_count;
+            let settings: FightSettings = FightSettings {
+                max_rounds: 1,
+                tie_break: TieBreak::HpPercentage,
+                hp_bonus_rhs: 0,
+            };
 
             contract.register_player(alice, String::from("Alice"));
             contract.register_player(bob, String::from("Bob"));
+            contract.set_fight_settings(settings);
 
             let alice_root_coord = SystemCoordinate {
                 root: alice,
@@ -1218,10 +4093,65 @@ mod newomegauniverse {
                 position_x: 0,
                 position_y: 0,
             };
+            contract.build_gateway(bob, bob_root_coord, alice);
+            contract.accept_gateway(alice, bob_root_coord, alice_root_coord);
+
+            let planet: Planet = contract.get_system(alice_root_coord).unwrap().planets[0].clone();
+            assert_eq!(planet.owner, alice);
+            let garrison: [u8; MAX_SHIPS] = [5; MAX_SHIPS];
+
+            let result: FightResult = contract.attack_planet(bob, alice_root_coord, 0,
+                [40; MAX_SHIPS], [ShipModule::default(); MAX_SHIPS], TargetingType::default(), garrison);
+
+            // Won by the tie-break, so part of the defending garrison survived
+            assert!(!result.lhs_dead && !result.rhs_dead);
+            assert_eq!(result.outcome, Outcome::AttackerWin);
+            assert_eq!(contract.get_planet_owner(alice_root_coord, 0), bob);
+            assert_eq!(contract.get_system(alice_root_coord).unwrap().planets[0].selection, garrison);
+
+            // The survivors retreat to the hangar of the previous owner
+            let survivors: [u8; MAX_SHIPS] = contract.get_garrison_survivors(&planet, result.ships_lost_rhs);
+            assert!(survivors.iter().any(|&count| count > 0));
+            let alice_ships: [u32; MAX_SHIPS] = contract
+                .new_omega_storage
+                .as_ref()
+                .unwrap()
+                .get_player_ships(alice);
+            for i in 0..MAX_SHIPS {
+                assert_eq!(alice_ships[i], starting_ships + survivors[i] as u32);
+            }
+        }
 
-            assert!(!contract.can_attack_planet(alice, bob_root_coord));
-            contract.build_gateway(alice, alice_root_coord);
-            assert!(contract.can_attack_planet(alice, bob_root_coord));
+        #[ink::test]
+        #[should_panic]
+        fn test_set_fight_settings_zero_rounds() {
+            let mut contract: NewOmegaUniverse = prepare_contract();
+
+            contract.set_fight_settings(FightSettings {
+                max_rounds: 0,
+                tie_break: TieBreak::None,
+                hp_bonus_rhs: 0,
+            });
+        }
+
+        #[ink::test]
+        #[should_panic]
+        fn test_capture_planet_retired_ship_connected() {
+            let mut contract: NewOmegaUniverse = prepare_connected_contract();
+            let accounts = default_accounts();
+            let alice: AccountId = accounts.alice;
+
+            contract.register_player(alice, String::from("Alice"));
+            contract.new_omega_game.as_mut().unwrap().retire_ship(3);
+
+            let alice_root_coord = SystemCoordinate {
+                root: alice,
+                position_x: 0,
+                position_y: 0,
+            };
+
+            contract.attack_planet(alice, alice_root_coord, START_WITH_PLANETS, [40; MAX_SHIPS],
+                [ShipModule::default(); MAX_SHIPS], TargetingType::default(), [0; MAX_SHIPS]);
         }
     }
 }
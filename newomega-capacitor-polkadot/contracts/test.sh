@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+
+set -eu
+
+# The packages are checked one at a time: in a workspace wide run, the `ink-as-dependency`
+# feature the contracts enable on each other would be unified, leaving out the contract
+# implementations (and their tests).
+packages=(
+    newomegaengine
+    newomegalayout
+    newomegaaccessmacro
+    newomegaaccess
+    newomega
+    newomegaparameters
+    newomegastorage
+    newomegagame
+    newomegainterfaces
+    newomegauniverse
+    newomegaranked
+    newomegaindustrial
+    newomegasim
+    newomegabalance
+    newomegadelegator
+)
+
+for package in "${packages[@]}"; do
+    cargo test --locked -p "$package"
+    cargo clippy --locked -p "$package" --all-targets -- -D warnings
+done